@@ -0,0 +1,119 @@
+//! Compares parsing order book frames by dispatching on the topic against the untagged enum
+//! used before, which tries each variant in turn. With the `simd-json` feature, `parse_mut`
+//! with simd-json is compared against `parse` with serde_json, as the clients parse frames.
+//!
+//! Synthetic `orderbook.500` frames are used by default. Set `BYBIT_BENCH_RECORDING` to a
+//! recording of a linear or inverse channel (see `Recorder`) to use its `orderbook.500` frames.
+
+use bybit::ws::record::Replayer;
+use bybit::ws::response::*;
+use bybit::ws::Arg;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use serde::Deserialize;
+
+/// [`FuturePublicResponse`] as an untagged enum.
+#[allow(dead_code, clippy::large_enum_variant)]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Untagged<'a> {
+    #[serde(borrow)]
+    Orderbook(BasePublicResponse<'a, Orderbook<'a>>),
+    Trade(BasePublicResponse<'a, Vec<Trade<'a>>>),
+    Ticker(BaseTickerPublicResponse<'a, FutureTicker<'a>>),
+    Kline(BasePublicResponse<'a, Vec<Kline<'a>>>),
+    Liquidation(BasePublicResponse<'a, Liquidation<'a>>),
+    Op(OpResponse<'a>),
+}
+
+fn levels(rng: &mut u64, n: usize, mid: f64, side: f64) -> String {
+    let levels: Vec<_> = (0..n)
+        .map(|i| {
+            // A xorshift generator, so frames are the same on every run.
+            *rng ^= *rng << 13;
+            *rng ^= *rng >> 7;
+            *rng ^= *rng << 17;
+            let size = (*rng % 100_000) as f64 / 1000.0;
+            format!(
+                r#"["{:.2}","{:.3}"]"#,
+                mid + side * (i as f64 + 1.0) * 0.5,
+                size
+            )
+        })
+        .collect();
+    levels.join(",")
+}
+
+/// A snapshot of 500 levels followed by deltas of a few levels, like a busy stream.
+fn synthetic() -> Vec<String> {
+    let mut rng = 0x2545f4914f6cdd1d;
+    (0..100)
+        .map(|u| {
+            let (type_, n) = if u == 0 { ("snapshot", 500) } else { ("delta", 20) };
+            format!(
+                r#"{{"topic":"orderbook.500.BTCUSDT","type":"{}","ts":{},"data":{{"s":"BTCUSDT","b":[{}],"a":[{}],"u":{},"seq":{}}}}}"#,
+                type_,
+                1672304484978u64 + u,
+                levels(&mut rng, n, 16500.0, -1.0),
+                levels(&mut rng, n, 16500.0, 1.0),
+                u + 1,
+                7961638724u64 + u,
+            )
+        })
+        .collect()
+}
+
+fn recorded(path: &str) -> Vec<String> {
+    Replayer::<FuturePublicResponseArg>::open(path)
+        .expect("failed to open the recording")
+        .frames()
+        .map(|frame| frame.expect("malformed recording").frame)
+        .filter(|frame| frame.contains(r#""topic":"orderbook.500."#))
+        .collect()
+}
+
+fn parse(c: &mut Criterion) {
+    let frames = match std::env::var("BYBIT_BENCH_RECORDING") {
+        Ok(path) => recorded(&path),
+        Err(_) => synthetic(),
+    };
+    assert!(!frames.is_empty(), "no orderbook.500 frames");
+    let bytes = frames.iter().map(|frame| frame.len() as u64).sum();
+
+    let mut group = c.benchmark_group("orderbook.500");
+    group.throughput(Throughput::Bytes(bytes));
+    group.bench_function("parse", |b| {
+        b.iter(|| {
+            for frame in &frames {
+                let res = FuturePublicResponseArg::parse(frame).unwrap();
+                black_box(res);
+            }
+        })
+    });
+    group.bench_function("untagged", |b| {
+        b.iter(|| {
+            for frame in &frames {
+                let res: Untagged = serde_json::from_str(frame).unwrap();
+                black_box(res);
+            }
+        })
+    });
+
+    #[cfg(feature = "simd-json")]
+    group.bench_function("parse_mut", |b| {
+        let mut buffer = Vec::new();
+        let mut buffers = simd_json::Buffers::default();
+        b.iter(|| {
+            for frame in &frames {
+                // Clients parse a copy of each frame, which is rewritten by simd-json.
+                buffer.clear();
+                buffer.extend_from_slice(frame.as_bytes());
+                let res = FuturePublicResponseArg::parse_mut(&mut buffer, &mut buffers).unwrap();
+                black_box(res);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+use bybit::ws::response::SpotPublicResponse;
+use bybit::ws::spot;
+use bybit::WebSocketApiClient;
+use futures_util::StreamExt;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let mut client = WebSocketApiClient::spot().build();
+
+    let symbol = "ETHUSDT";
+
+    client.subscribe_orderbook(symbol, spot::OrderbookDepth::Level1);
+    client.subscribe_trade(symbol);
+    client.subscribe_ticker(symbol);
+
+    let mut stream = match client.stream().await {
+        Ok(stream) => stream,
+        Err(e) => return eprintln!("Error: {e}"),
+    };
+
+    while let Some(res) = stream.next().await {
+        match res {
+            Ok(SpotPublicResponse::Orderbook(res)) => println!("Orderbook: {:?}", res),
+            Ok(SpotPublicResponse::Trade(res)) => println!("Trade: {:?}", res),
+            Ok(SpotPublicResponse::Ticker(res)) => println!("Ticker: {:?}", res),
+            Ok(res) => println!("Other: {:?}", res),
+            Err(e) => return eprintln!("Error: {e}"),
+        }
+    }
+}
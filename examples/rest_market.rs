@@ -0,0 +1,28 @@
+use bybit::enums::Category;
+use bybit::rest::market::{KlineRequest, TickersRequest};
+use bybit::{KlineInterval, RestApiClient};
+
+fn main() {
+    let client = RestApiClient::builder().build();
+
+    match client.server_time() {
+        Ok(time) => println!("Server time: {:?}", time),
+        Err(e) => eprintln!("{}", e),
+    }
+
+    let req = KlineRequest::new(Category::Spot, "BTCUSDT", KlineInterval::Min60).limit(5);
+    match client.kline(&req) {
+        Ok(res) => res.list.iter().for_each(|kline| println!("{:?}", kline)),
+        Err(e) => eprintln!("{}", e),
+    }
+
+    match client.spot_tickers(&TickersRequest::new().symbol("BTCUSDT")) {
+        Ok(res) => println!("{:?}", res.list),
+        Err(e) => eprintln!("{}", e),
+    }
+
+    match client.orderbook(Category::Spot, "BTCUSDT", Some(5)) {
+        Ok(snapshot) => println!("{:?}", snapshot.book),
+        Err(e) => eprintln!("{}", e),
+    }
+}
@@ -0,0 +1,52 @@
+use bybit::enums::{Category, OrderType, Side};
+use bybit::rest::trade::{CancelOrderRequest, PlaceOrderRequest};
+use bybit::ws::response::TradeResponse;
+use bybit::WebSocketApiClient;
+use std::env;
+
+fn main() {
+    env_logger::init();
+
+    let api_key: String = env::var("BYBIT_API_KEY").unwrap();
+    let secret: String = env::var("BYBIT_SECRET").unwrap();
+
+    let client = WebSocketApiClient::trade()
+        .testnet()
+        .build_with_credentials(api_key, secret);
+    let handle = client.handle();
+
+    if let Err(e) = client.run(|res| match res {
+        TradeResponse::Auth(res) => {
+            println!("Auth: {:?}", res);
+            let req = PlaceOrderRequest::new(
+                Category::Linear,
+                "BTCUSDT",
+                Side::Buy,
+                OrderType::Limit,
+                "0.001",
+            )
+            .price("10000");
+            match handle.place_order(&req) {
+                Ok(req_id) => println!("Sent order.create: {req_id}"),
+                Err(e) => eprintln!("Error: {e}"),
+            }
+        }
+        TradeResponse::Create(res) => {
+            println!("Create: {:?}", res);
+            if res.is_ok() {
+                let req = CancelOrderRequest::new(Category::Linear, "BTCUSDT")
+                    .order_id(res.data.order_id.as_ref());
+                if let Err(e) = handle.cancel_order(&req) {
+                    eprintln!("Error: {e}");
+                }
+            }
+        }
+        TradeResponse::Cancel(res) => {
+            println!("Cancel: {:?}", res);
+            handle.shutdown();
+        }
+        res => println!("{:?}", res),
+    }) {
+        eprintln!("Error: {e}")
+    }
+}
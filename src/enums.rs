@@ -0,0 +1,458 @@
+//! Categorical values sent by Bybit.
+//!
+//! Every enum has an `Unknown` variant which keeps the raw value,
+//! so values introduced by Bybit later never break parsing.
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $value:literal,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+            /// A value unknown to this version of the library.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The value sent by Bybit.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown(value.to_owned()),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct EnumVisitor;
+
+                impl<'de> Visitor<'de> for EnumVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str(concat!("a ", stringify!($name), " string"))
+                    }
+
+                    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                        Ok(v.into())
+                    }
+                }
+
+                deserializer.deserialize_str(EnumVisitor)
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// The data type of a public topic.
+    pub enum DataType {
+        Snapshot => "snapshot",
+        Delta => "delta",
+    }
+}
+
+string_enum! {
+    /// Product type.
+    pub enum Category {
+        Spot => "spot",
+        Linear => "linear",
+        Inverse => "inverse",
+        Option => "option",
+    }
+}
+
+string_enum! {
+    /// Order, trade or position side.
+    ///
+    /// The side of an empty position is `Unknown("")`.
+    pub enum Side {
+        Buy => "Buy",
+        Sell => "Sell",
+    }
+}
+
+string_enum! {
+    /// Order status.
+    pub enum OrderStatus {
+        /// The order has been accepted by the system but not yet put through the matching engine.
+        Created => "Created",
+        /// The order has been placed successfully.
+        New => "New",
+        Rejected => "Rejected",
+        PartiallyFilled => "PartiallyFilled",
+        /// Spot only. The order is partially filled and the rest is cancelled.
+        PartiallyFilledCanceled => "PartiallyFilledCanceled",
+        Filled => "Filled",
+        Cancelled => "Cancelled",
+        /// The conditional order is created but not triggered yet.
+        Untriggered => "Untriggered",
+        /// The conditional order is triggered.
+        Triggered => "Triggered",
+        /// The conditional order is cancelled before triggered.
+        Deactivated => "Deactivated",
+        /// The conditional order is triggered and converted to an active order.
+        Active => "Active",
+    }
+}
+
+impl OrderStatus {
+    /// Whether the order is closed and will not be updated anymore,
+    /// i.e., filled, cancelled, rejected or deactivated.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Rejected
+                | OrderStatus::PartiallyFilledCanceled
+                | OrderStatus::Filled
+                | OrderStatus::Cancelled
+                | OrderStatus::Deactivated
+        )
+    }
+}
+
+string_enum! {
+    /// Order type.
+    pub enum OrderType {
+        Market => "Market",
+        Limit => "Limit",
+    }
+}
+
+string_enum! {
+    /// Stop order type.
+    pub enum StopOrderType {
+        TakeProfit => "TakeProfit",
+        StopLoss => "StopLoss",
+        TrailingStop => "TrailingStop",
+        Stop => "Stop",
+        PartialTakeProfit => "PartialTakeProfit",
+        PartialStopLoss => "PartialStopLoss",
+        /// Spot TP/SL order.
+        TpslOrder => "tpslOrder",
+    }
+}
+
+string_enum! {
+    /// Time in force.
+    pub enum TimeInForce {
+        /// Good till cancel.
+        Gtc => "GTC",
+        /// Immediate or cancel.
+        Ioc => "IOC",
+        /// Fill or kill.
+        Fok => "FOK",
+        PostOnly => "PostOnly",
+    }
+}
+
+string_enum! {
+    /// The price type to trigger an order.
+    pub enum TriggerBy {
+        LastPrice => "LastPrice",
+        IndexPrice => "IndexPrice",
+        MarkPrice => "MarkPrice",
+    }
+}
+
+string_enum! {
+    /// Cancel type.
+    pub enum CancelType {
+        CancelByUser => "CancelByUser",
+        CancelByReduceOnly => "CancelByReduceOnly",
+        CancelByPrepareLiq => "CancelByPrepareLiq",
+        CancelAllBeforeLiq => "CancelAllBeforeLiq",
+        CancelByPrepareAdl => "CancelByPrepareAdl",
+        CancelAllBeforeAdl => "CancelAllBeforeAdl",
+        CancelByAdmin => "CancelByAdmin",
+        CancelByTpSlTsClear => "CancelByTpSlTsClear",
+        CancelByPzSideCh => "CancelByPzSideCh",
+    }
+}
+
+string_enum! {
+    /// Execution type.
+    pub enum ExecType {
+        Trade => "Trade",
+        /// Auto-deleveraging.
+        AdlTrade => "AdlTrade",
+        Funding => "Funding",
+        /// Liquidation.
+        BustTrade => "BustTrade",
+        Delivery => "Delivery",
+        Settle => "Settle",
+        BlockTrade => "BlockTrade",
+        MovePosition => "MovePosition",
+    }
+}
+
+string_enum! {
+    /// Tick direction.
+    pub enum TickDirection {
+        /// Price rise.
+        PlusTick => "PlusTick",
+        /// Trade occurs at the same price as the previous trade, which occurred at a price higher than that for the trade preceding it.
+        ZeroPlusTick => "ZeroPlusTick",
+        /// Price drop.
+        MinusTick => "MinusTick",
+        /// Trade occurs at the same price as the previous trade, which occurred at a price lower than that for the trade preceding it.
+        ZeroMinusTick => "ZeroMinusTick",
+    }
+}
+
+string_enum! {
+    /// Position status.
+    pub enum PositionStatus {
+        Normal => "Normal",
+        /// In the liquidation progress.
+        Liq => "Liq",
+        /// In the auto-deleverage progress.
+        Adl => "Adl",
+    }
+}
+
+string_enum! {
+    /// Tp/Sl mode.
+    pub enum TpslMode {
+        Full => "Full",
+        Partial => "Partial",
+    }
+}
+
+string_enum! {
+    /// Account type.
+    pub enum AccountType {
+        Unified => "UNIFIED",
+        Contract => "CONTRACT",
+        Spot => "SPOT",
+        Fund => "FUND",
+        Option => "OPTION",
+        Investment => "INVESTMENT",
+    }
+}
+
+string_enum! {
+    /// Instrument status.
+    pub enum InstrumentStatus {
+        PreLaunch => "PreLaunch",
+        Trading => "Trading",
+        Settling => "Settling",
+        Delivering => "Delivering",
+        Closed => "Closed",
+    }
+}
+
+string_enum! {
+    /// Contract type of a future instrument.
+    pub enum ContractType {
+        InversePerpetual => "InversePerpetual",
+        LinearPerpetual => "LinearPerpetual",
+        LinearFutures => "LinearFutures",
+        InverseFutures => "InverseFutures",
+    }
+}
+
+string_enum! {
+    /// Option type.
+    pub enum OptionsType {
+        Call => "Call",
+        Put => "Put",
+    }
+}
+
+string_enum! {
+    /// The interval of open interest and long/short ratio data.
+    pub enum IntervalTime {
+        Min5 => "5min",
+        Min15 => "15min",
+        Min30 => "30min",
+        Hour1 => "1h",
+        Hour4 => "4h",
+        Day1 => "1d",
+    }
+}
+
+string_enum! {
+    /// Margin mode of a unified account.
+    pub enum MarginMode {
+        IsolatedMargin => "ISOLATED_MARGIN",
+        RegularMargin => "REGULAR_MARGIN",
+        PortfolioMargin => "PORTFOLIO_MARGIN",
+    }
+}
+
+string_enum! {
+    /// Transaction type of a transaction log.
+    pub enum TransactionType {
+        TransferIn => "TRANSFER_IN",
+        TransferOut => "TRANSFER_OUT",
+        Trade => "TRADE",
+        Settlement => "SETTLEMENT",
+        Delivery => "DELIVERY",
+        Liquidation => "LIQUIDATION",
+        Bonus => "BONUS",
+        FeeRefund => "FEE_REFUND",
+        Interest => "INTEREST",
+        CurrencyBuy => "CURRENCY_BUY",
+        CurrencySell => "CURRENCY_SELL",
+    }
+}
+
+string_enum! {
+    /// Status of an internal or universal transfer.
+    pub enum TransferStatus {
+        Success => "SUCCESS",
+        Pending => "PENDING",
+        Failed => "FAILED",
+    }
+}
+
+string_enum! {
+    /// Status of a withdrawal.
+    pub enum WithdrawStatus {
+        SecurityCheck => "SecurityCheck",
+        Pending => "Pending",
+        Success => "success",
+        CancelByUser => "CancelByUser",
+        Reject => "Reject",
+        Fail => "Fail",
+        BlockchainConfirmed => "BlockchainConfirmed",
+    }
+}
+
+/// Status of a deposit, which Bybit sends as a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepositStatus {
+    ToBeConfirmed,
+    Processing,
+    Success,
+    Failed,
+    /// `0` which Bybit defines as unknown, or a value unknown to this version of the library.
+    Unknown(u8),
+}
+
+impl DepositStatus {
+    /// The value sent by Bybit.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            DepositStatus::ToBeConfirmed => 1,
+            DepositStatus::Processing => 2,
+            DepositStatus::Success => 3,
+            DepositStatus::Failed => 4,
+            DepositStatus::Unknown(value) => *value,
+        }
+    }
+}
+
+impl From<u8> for DepositStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => DepositStatus::ToBeConfirmed,
+            2 => DepositStatus::Processing,
+            3 => DepositStatus::Success,
+            4 => DepositStatus::Failed,
+            _ => DepositStatus::Unknown(value),
+        }
+    }
+}
+
+impl fmt::Display for DepositStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_u8())
+    }
+}
+
+impl Serialize for DepositStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for DepositStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StatusVisitor;
+
+        impl<'de> Visitor<'de> for StatusVisitor {
+            type Value = DepositStatus;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a DepositStatus number")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                u8::try_from(v)
+                    .map(DepositStatus::from)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse::<u8>()
+                    .map(DepositStatus::from)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_any(StatusVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_enum() {
+        let side: Side = serde_json::from_str(r#""Sell""#).unwrap();
+        assert_eq!(side, Side::Sell);
+        let tif: TimeInForce = serde_json::from_str(r#""GTC""#).unwrap();
+        assert_eq!(tif, TimeInForce::Gtc);
+        let status: OrderStatus = serde_json::from_str(r#""Expired""#).unwrap();
+        assert_eq!(status, OrderStatus::Unknown("Expired".to_string()));
+        assert_eq!(status.as_str(), "Expired");
+        assert_eq!(
+            serde_json::to_string(&StopOrderType::TpslOrder).unwrap(),
+            r#""tpslOrder""#
+        );
+        let status: DepositStatus = serde_json::from_str("3").unwrap();
+        assert_eq!(status, DepositStatus::Success);
+        let status: DepositStatus = serde_json::from_str("0").unwrap();
+        assert_eq!(status, DepositStatus::Unknown(0));
+    }
+}
@@ -11,5 +11,38 @@ pub enum BybitError {
     SerdeError(#[from] SerdeError),
 
     #[error("Tungstenite error: {0}")]
-    TungsteniteError(#[from] TungsteniteError),
+    TungsteniteError(Box<TungsteniteError>),
+
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
+
+    #[error("Invalid number: {0}")]
+    InvalidNumber(String),
+
+    #[error("Order book out of sync: {0}")]
+    OrderBookOutOfSync(String),
+
+    #[error("HTTP error: {0}")]
+    HttpError(Box<ureq::Error>),
+
+    #[error("API error {code}: {msg}")]
+    ApiError { code: i64, msg: String },
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+impl From<ureq::Error> for BybitError {
+    fn from(err: ureq::Error) -> Self {
+        BybitError::HttpError(Box::new(err))
+    }
+}
+
+impl From<TungsteniteError> for BybitError {
+    fn from(err: TungsteniteError) -> Self {
+        BybitError::TungsteniteError(Box::new(err))
+    }
 }
@@ -1,14 +1,24 @@
+pub mod enums;
 pub mod error;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod rest;
 pub mod util;
 pub mod ws;
 
+pub use rest::RestApiClient;
 pub use ws::WebSocketApiClient;
 
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FutureRole {
     Linear,
     Inverse,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum KlineInterval {
     Min1,
     Min3,
@@ -45,3 +55,18 @@ impl From<KlineInterval> for &str {
         }
     }
 }
+
+impl From<FutureRole> for enums::Category {
+    fn from(value: FutureRole) -> Self {
+        match value {
+            FutureRole::Linear => enums::Category::Linear,
+            FutureRole::Inverse => enums::Category::Inverse,
+        }
+    }
+}
+
+impl serde::Serialize for KlineInterval {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str((*self).into())
+    }
+}
@@ -0,0 +1,589 @@
+//! A local mock of Bybit's V5 WebSocket server for integration tests. Requires the `mock` feature.
+//!
+//! The server speaks the public, option, private or trade protocol on `127.0.0.1`. It answers `ping`,
+//! `auth` (verifying the signature), `subscribe` and `unsubscribe`, or the order requests of
+//! the trade channel, pushes scripted messages after a topic is subscribed, and can reject
+//! topics and orders, hold `auth` replies, push messages and drop connections on demand.
+//! Point a client at it with the `uri()` method of its builder:
+//!
+//! ```no_run
+//! use bybit::mock::{self, MockServer};
+//! use bybit::ws::future::OrderbookDepth;
+//! use bybit::WebSocketApiClient;
+//!
+//! let server = MockServer::public().unwrap();
+//! server.on_subscribe(
+//!     "orderbook.1.BTCUSDT",
+//!     [mock::public_message(
+//!         "orderbook.1.BTCUSDT",
+//!         "snapshot",
+//!         r#"{"s":"BTCUSDT","b":[["16493.50","0.006"]],"a":[["16611.00","0.029"]],"u":18521288,"seq":7961638724}"#,
+//!     )],
+//! );
+//!
+//! let mut client = WebSocketApiClient::future_linear().uri(server.uri()).build();
+//! client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+//! ```
+
+pub mod fixtures;
+
+use log::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tungstenite::{Message, WebSocket};
+
+use crate::error::Result;
+use crate::util::{millis, sign};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Build a message of a public topic, e.g., an order book `snapshot` or `delta`.
+pub fn public_message(topic: &str, data_type: &str, data: &str) -> String {
+    format!(
+        r#"{{"topic":"{}","type":"{}","ts":{},"data":{}}}"#,
+        topic,
+        data_type,
+        millis(),
+        data
+    )
+}
+
+/// Build a message of a private topic, e.g., `order`. `data` is the JSON array of the items.
+pub fn private_message(topic: &str, data: &str) -> String {
+    format!(
+        r#"{{"id":"mock-{}","topic":"{}","creationTime":{},"data":{}}}"#,
+        millis(),
+        topic,
+        millis(),
+        data
+    )
+}
+
+/// Poll `condition` until it is true or `timeout` elapses, and return whether it is true.
+pub fn wait_until<F>(timeout: Duration, mut condition: F) -> bool
+where
+    F: FnMut() -> bool,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        if condition() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// The protocols which the channels reply to requests with.
+#[derive(Default, Clone, Copy, PartialEq)]
+enum Protocol {
+    /// Replies with `success` and `ret_msg`, as the public and private channels do.
+    #[default]
+    Standard,
+    /// Replies subscriptions with `failTopics` and `successTopics`, and pings without `success`.
+    Option,
+    /// Replies with `retCode` and `retMsg`, and handles orders.
+    Trade,
+}
+
+/// The actions sent to a connection by the server.
+enum Action {
+    Push(String),
+    Disconnect,
+}
+
+#[derive(Default)]
+struct State {
+    /// API key and secret of the private and trade channels. `None` for public channels.
+    credentials: Option<(String, String)>,
+    protocol: Protocol,
+    scripts: HashMap<String, Vec<String>>,
+    rejections: HashMap<String, String>,
+    /// The `retCode` and `retMsg` to reject order requests with, by op.
+    order_rejections: HashMap<String, (i64, String)>,
+    orders: usize,
+    requests: Vec<Value>,
+    connections: Vec<Sender<Action>>,
+    accepted: usize,
+    drop_on_accept: bool,
+    hold_auth: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    stopped: AtomicBool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A local WebSocket server which mocks a public or private channel of Bybit.
+///
+/// It runs on background threads until it is dropped.
+pub struct MockServer {
+    uri: String,
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Start a server of a public channel, e.g., spot or linear.
+    pub fn public() -> io::Result<Self> {
+        Self::start(None, Protocol::Standard)
+    }
+
+    /// Start a server of the option channel, which has its own replies to `subscribe` and `ping`.
+    pub fn option() -> io::Result<Self> {
+        Self::start(None, Protocol::Option)
+    }
+
+    /// Start a server of the private channel, which accepts `auth` with the API key and secret.
+    pub fn private<S: AsRef<str>>(api_key: S, secret: S) -> io::Result<Self> {
+        Self::start(Some(credentials(api_key, secret)), Protocol::Standard)
+    }
+
+    /// Start a server of the trade channel, which accepts `auth` with the API key and secret,
+    /// then accepts orders unless they are rejected with `reject_orders`.
+    pub fn trade<S: AsRef<str>>(api_key: S, secret: S) -> io::Result<Self> {
+        Self::start(Some(credentials(api_key, secret)), Protocol::Trade)
+    }
+
+    fn start(credentials: Option<(String, String)>, protocol: Protocol) -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let uri = format!("ws://{}", listener.local_addr()?);
+        let shared = Arc::new(Shared::default());
+        {
+            let mut state = shared.lock();
+            state.credentials = credentials;
+            state.protocol = protocol;
+        }
+
+        let accepting = shared.clone();
+        let thread = thread::spawn(move || accept(listener, accepting));
+        Ok(Self {
+            uri,
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    /// The URI to connect to, e.g., `ws://127.0.0.1:12345`.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Push `messages` in order each time `topic` is subscribed, including resubscriptions.
+    pub fn on_subscribe<I, S>(&self, topic: &str, messages: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.shared.lock().scripts.insert(
+            topic.to_string(),
+            messages.into_iter().map(Into::into).collect(),
+        );
+    }
+
+    /// Reject subscriptions to `topic` with `reason` as `ret_msg`.
+    pub fn reject(&self, topic: &str, reason: &str) {
+        self.shared
+            .lock()
+            .rejections
+            .insert(topic.to_string(), reason.to_string());
+    }
+
+    /// Reject order requests of `op`, e.g., `order.cancel`, with `ret_code` and `ret_msg`.
+    pub fn reject_orders(&self, op: &str, ret_code: i64, ret_msg: &str) {
+        self.shared
+            .lock()
+            .order_rejections
+            .insert(op.to_string(), (ret_code, ret_msg.to_string()));
+    }
+
+    /// Push a message to all connections and return the number of them.
+    pub fn push<S: Into<String>>(&self, message: S) -> usize {
+        let message = message.into();
+        let mut state = self.shared.lock();
+        state
+            .connections
+            .retain(|tx| tx.send(Action::Push(message.clone())).is_ok());
+        state.connections.len()
+    }
+
+    /// Drop all connections without a close frame, as if the network failed.
+    pub fn disconnect(&self) {
+        for tx in self.shared.lock().connections.drain(..) {
+            let _ = tx.send(Action::Disconnect);
+        }
+    }
+
+    /// Drop new connections right after accepting the handshake if `enabled`,
+    /// e.g., to test the backoff of reconnecting.
+    pub fn drop_on_accept(&self, enabled: bool) {
+        self.shared.lock().drop_on_accept = enabled;
+    }
+
+    /// Don't reply to `auth` requests if `enabled`, e.g., to test requests sent
+    /// before a connection is authenticated.
+    pub fn hold_auth(&self, enabled: bool) {
+        self.shared.lock().hold_auth = enabled;
+    }
+
+    /// The requests received so far from all connections, in order.
+    pub fn requests(&self) -> Vec<Value> {
+        self.shared.lock().requests.clone()
+    }
+
+    /// The number of requests of `op` including `arg`, e.g., subscriptions to a topic.
+    pub fn count(&self, op: &str, arg: &str) -> usize {
+        self.shared
+            .lock()
+            .requests
+            .iter()
+            .filter(|req| req["op"] == op)
+            .filter(|req| match req["args"].as_array() {
+                Some(args) => args.iter().any(|a| a == arg),
+                None => false,
+            })
+            .count()
+    }
+
+    /// The number of connections accepted so far.
+    pub fn connections(&self) -> usize {
+        self.shared.lock().accepted
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::Relaxed);
+        self.disconnect();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn credentials<S: AsRef<str>>(api_key: S, secret: S) -> (String, String) {
+    (api_key.as_ref().to_string(), secret.as_ref().to_string())
+}
+
+fn accept(listener: TcpListener, shared: Arc<Shared>) {
+    while !shared.stopped.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let (tx, rx) = mpsc::channel();
+                let conn_id = {
+                    let mut state = shared.lock();
+                    state.accepted += 1;
+                    state.connections.push(tx);
+                    format!("mock-conn-{}", state.accepted)
+                };
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    if let Err(e) = serve(stream, conn_id, &shared, rx) {
+                        debug!("Mock connection closed: {}", e);
+                    }
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                error!("Mock server failed to accept: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+/// A connection of the mock server.
+struct Connection<'a> {
+    ws: WebSocket<TcpStream>,
+    conn_id: String,
+    shared: &'a Shared,
+    authed: bool,
+}
+
+fn serve(
+    stream: TcpStream,
+    conn_id: String,
+    shared: &Shared,
+    actions: Receiver<Action>,
+) -> Result<()> {
+    stream
+        .set_nonblocking(false)
+        .map_err(tungstenite::Error::Io)?;
+    let ws = tungstenite::accept(stream).map_err(|e| match e {
+        tungstenite::HandshakeError::Failure(e) => e,
+        tungstenite::HandshakeError::Interrupted(_) => tungstenite::Error::ConnectionClosed,
+    })?;
+    if shared.lock().drop_on_accept {
+        return Ok(());
+    }
+    ws.get_ref()
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .map_err(tungstenite::Error::Io)?;
+    let mut conn = Connection {
+        ws,
+        conn_id,
+        shared,
+        authed: false,
+    };
+
+    while !shared.stopped.load(Ordering::Relaxed) {
+        while let Ok(action) = actions.try_recv() {
+            match action {
+                Action::Push(message) => conn.ws.write_message(Message::Text(message))?,
+                // Dropping the socket closes the TCP connection without a close frame.
+                Action::Disconnect => return Ok(()),
+            }
+        }
+        match conn.ws.read_message() {
+            Ok(Message::Text(text)) => conn.handle(&text)?,
+            // The close frame is answered by the next read, which then returns `ConnectionClosed`.
+            Ok(_) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(tungstenite::Error::Io(e))
+                if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {
+            }
+            Err(e) => Err(e)?,
+        }
+    }
+    Ok(())
+}
+
+impl Connection<'_> {
+    fn handle(&mut self, text: &str) -> Result<()> {
+        let req: Value = match serde_json::from_str(text) {
+            Ok(req) => req,
+            Err(_) => return self.reply(json!({ "success": false, "ret_msg": "Invalid JSON" })),
+        };
+        let protocol = {
+            let mut state = self.shared.lock();
+            state.requests.push(req.clone());
+            state.protocol
+        };
+        match protocol {
+            Protocol::Trade => return self.handle_trade(&req),
+            Protocol::Option => return self.handle_option(&req),
+            Protocol::Standard => {}
+        }
+
+        let op = req["op"].as_str().unwrap_or_default();
+        let req_id = req["req_id"].clone();
+        let args: Vec<&str> = req["args"]
+            .as_array()
+            .map(|args| args.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let private = self.shared.lock().credentials.is_some();
+
+        match op {
+            "ping" if private => self.reply(json!({
+                "req_id": req_id,
+                "op": "pong",
+                "args": [millis().to_string()],
+                "conn_id": self.conn_id,
+            })),
+            "ping" => self.op_reply(&req_id, "ping", true, "pong"),
+            "auth" if self.shared.lock().hold_auth => Ok(()),
+            "auth" => {
+                let result = self.verify(&args);
+                self.authed = result.is_ok();
+                let ret_msg = result.err().unwrap_or_default();
+                self.op_reply(&req_id, "auth", self.authed, ret_msg)
+            }
+            "subscribe" | "unsubscribe" if private && !self.authed => {
+                self.op_reply(&req_id, op, false, "Request not authorized")
+            }
+            "subscribe" => {
+                let (rejection, scripts) = {
+                    let state = self.shared.lock();
+                    let rejection = args
+                        .iter()
+                        .find_map(|topic| state.rejections.get(*topic).cloned());
+                    let scripts: Vec<String> = args
+                        .iter()
+                        .filter_map(|topic| state.scripts.get(*topic))
+                        .flatten()
+                        .cloned()
+                        .collect();
+                    (rejection, scripts)
+                };
+                if let Some(reason) = rejection {
+                    return self.op_reply(&req_id, op, false, &reason);
+                }
+                self.op_reply(&req_id, op, true, "")?;
+                for message in scripts {
+                    self.ws.write_message(Message::Text(message))?;
+                }
+                Ok(())
+            }
+            "unsubscribe" => self.op_reply(&req_id, op, true, ""),
+            _ => self.op_reply(&req_id, op, false, "Unsupported op"),
+        }
+    }
+
+    /// Handle a request of the option channel, which replies subscriptions with the failed
+    /// and succeeded topics.
+    fn handle_option(&mut self, req: &Value) -> Result<()> {
+        let op = req["op"].as_str().unwrap_or_default();
+        let args: Vec<&str> = req["args"]
+            .as_array()
+            .map(|args| args.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        match op {
+            "ping" => self.reply(json!({ "args": [millis().to_string()], "op": "pong" })),
+            "subscribe" | "unsubscribe" => {
+                let (failed, scripts) = {
+                    let state = self.shared.lock();
+                    let failed: Vec<&str> = args
+                        .iter()
+                        .filter(|topic| state.rejections.contains_key(**topic))
+                        .copied()
+                        .collect();
+                    let scripts: Vec<String> = args
+                        .iter()
+                        .filter(|_| op == "subscribe")
+                        .filter_map(|topic| state.scripts.get(*topic))
+                        .flatten()
+                        .cloned()
+                        .collect();
+                    (failed, scripts)
+                };
+                let succeeded: Vec<&str> = args
+                    .iter()
+                    .filter(|topic| !failed.contains(topic))
+                    .copied()
+                    .collect();
+                self.reply(json!({
+                    "success": failed.is_empty(),
+                    "conn_id": self.conn_id,
+                    "data": { "failTopics": failed, "successTopics": succeeded },
+                    "type": "COMMAND_RESP",
+                }))?;
+                for message in scripts {
+                    self.ws.write_message(Message::Text(message))?;
+                }
+                Ok(())
+            }
+            _ => self.op_reply(&req["req_id"], op, false, "Unsupported op"),
+        }
+    }
+
+    /// Handle a request of the trade channel, which replies with `retCode` and `retMsg`.
+    fn handle_trade(&mut self, req: &Value) -> Result<()> {
+        let op = req["op"].as_str().unwrap_or_default();
+        let args: Vec<&str> = req["args"]
+            .as_array()
+            .map(|args| args.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        match op {
+            "ping" => self.reply(json!({
+                "retCode": 0,
+                "retMsg": "OK",
+                "op": "pong",
+                "data": [millis().to_string()],
+                "connId": self.conn_id,
+            })),
+            "auth" if self.shared.lock().hold_auth => Ok(()),
+            "auth" => {
+                let result = self.verify(&args);
+                self.authed = result.is_ok();
+                self.reply(json!({
+                    "retCode": if self.authed { 0 } else { 10004 },
+                    "retMsg": result.err().unwrap_or("OK"),
+                    "op": "auth",
+                    "connId": self.conn_id,
+                }))
+            }
+            "order.create" | "order.amend" | "order.cancel" => {
+                let result = {
+                    let mut state = self.shared.lock();
+                    match state.order_rejections.get(op) {
+                        _ if !self.authed => Err((10003, "Request not authorized".to_string())),
+                        Some(rejection) => Err(rejection.clone()),
+                        None => {
+                            state.orders += 1;
+                            Ok(state.orders)
+                        }
+                    }
+                };
+                let (ret_code, ret_msg, data) = match result {
+                    Ok(n) => (
+                        0,
+                        "OK".to_string(),
+                        json!({
+                            "orderId": format!("mock-order-{}", n),
+                            "orderLinkId": req["args"][0]["orderLinkId"].as_str().unwrap_or_default(),
+                        }),
+                    ),
+                    Err((ret_code, ret_msg)) => (ret_code, ret_msg, json!({})),
+                };
+                self.reply(json!({
+                    "reqId": req["reqId"],
+                    "retCode": ret_code,
+                    "retMsg": ret_msg,
+                    "op": op,
+                    "data": data,
+                    "connId": self.conn_id,
+                }))
+            }
+            _ => self.reply(json!({
+                "reqId": req["reqId"],
+                "retCode": 10001,
+                "retMsg": "Unsupported op",
+                "op": op,
+                "connId": self.conn_id,
+            })),
+        }
+    }
+
+    /// Verify the args of `auth`: API key, expiry time and signature.
+    fn verify(&self, args: &[&str]) -> Result<(), &'static str> {
+        let state = self.shared.lock();
+        let Some((api_key, secret)) = &state.credentials else {
+            return Err("Unsupported op");
+        };
+        let [key, expires, signature] = args else {
+            return Err("Params Error");
+        };
+        if key != api_key {
+            return Err("Invalid apikey");
+        }
+        match expires.parse::<u128>() {
+            Ok(expires) if expires > millis() => {}
+            _ => return Err("Params Error"),
+        }
+        if *signature != sign(secret, &format!("GET/realtime{}", expires)) {
+            return Err("Invalid sign");
+        }
+        Ok(())
+    }
+
+    fn op_reply(&mut self, req_id: &Value, op: &str, success: bool, ret_msg: &str) -> Result<()> {
+        self.reply(json!({
+            "success": success,
+            "ret_msg": ret_msg,
+            "conn_id": self.conn_id,
+            "req_id": req_id,
+            "op": op,
+        }))
+    }
+
+    fn reply(&mut self, reply: Value) -> Result<()> {
+        Ok(self.ws.write_message(Message::Text(reply.to_string()))?)
+    }
+}
@@ -0,0 +1,139 @@
+//! Sample items of the private channel for tests, e.g., to push with a [`MockServer`](super::MockServer).
+//!
+//! Each item has every field Bybit sends, and `fields` override them, so a test only spells out
+//! the fields it checks, e.g., `order(json!({"orderStatus": "Filled"}))`. A `null` field is removed.
+
+use serde_json::{json, Value};
+
+use super::private_message;
+
+/// An item of the `order` topic.
+pub fn order(fields: Value) -> String {
+    item(
+        json!({
+            "category": "linear",
+            "orderId": "f6e324ff",
+            "orderLinkId": "test-001",
+            "blockTradeId": "",
+            "symbol": "ETHUSDT",
+            "price": "1600.00",
+            "qty": "0.10",
+            "side": "Buy",
+            "isLeverage": "",
+            "positionIdx": 0,
+            "orderStatus": "New",
+            "cancelType": "UNKNOWN",
+            "rejectReason": "EC_NoError",
+            "avgPrice": "0",
+            "leavesQty": "0.10",
+            "leavesValue": "160",
+            "cumExecQty": "0.00",
+            "cumExecValue": "0",
+            "cumExecFee": "0",
+            "timeInForce": "GTC",
+            "orderType": "Limit",
+            "stopOrderType": "UNKNOWN",
+            "orderIv": "",
+            "triggerPrice": "0.00",
+            "takeProfit": "0.00",
+            "stopLoss": "0.00",
+            "tpTriggerBy": "UNKNOWN",
+            "slTriggerBy": "UNKNOWN",
+            "triggerDirection": 0,
+            "triggerBy": "UNKNOWN",
+            "lastPriceOnCreated": "",
+            "reduceOnly": false,
+            "closeOnTrigger": false,
+            "createdTime": "1000",
+            "updatedTime": "1000",
+        }),
+        fields,
+    )
+}
+
+/// An item of the `execution` topic, which fills half of the default [`order`].
+pub fn execution(fields: Value) -> String {
+    item(
+        json!({
+            "category": "linear",
+            "symbol": "ETHUSDT",
+            "isLeverage": "",
+            "orderId": "f6e324ff",
+            "orderLinkId": "test-001",
+            "side": "Buy",
+            "orderPrice": "1600.00",
+            "orderQty": "0.10",
+            "leavesQty": "0.05",
+            "orderType": "Limit",
+            "stopOrderType": "UNKNOWN",
+            "execFee": "0.008",
+            "execId": "e1",
+            "execPrice": "1600.00",
+            "execQty": "0.05",
+            "execType": "Trade",
+            "execValue": "80",
+            "execTime": "1000",
+            "isMaker": true,
+            "feeRate": "0.0001",
+            "tradeIv": "",
+            "markIv": "",
+            "markPrice": "1601.00",
+            "indexPrice": "",
+            "underlyingPrice": "",
+            "blockTradeId": "",
+        }),
+        fields,
+    )
+}
+
+/// An item of the `position` topic.
+pub fn position(fields: Value) -> String {
+    item(
+        json!({
+            "positionIdx": 0,
+            "tradeMode": 0,
+            "riskId": 1,
+            "riskLimitValue": "2000000",
+            "symbol": "ETHUSDT",
+            "side": "Buy",
+            "size": "0.1",
+            "entryPrice": "1600",
+            "leverage": "10",
+            "positionValue": "160",
+            "markPrice": "1601",
+            "positionIM": "16",
+            "positionMM": "0.8",
+            "takeProfit": "0",
+            "stopLoss": "0",
+            "trailingStop": "0",
+            "unrealisedPnl": "0.1",
+            "cumRealisedPnl": "0",
+            "createdTime": "1000",
+            "updatedTime": "1000",
+            "tpslMode": "Full",
+            "liqPrice": "",
+            "bustPrice": "",
+            "positionStatus": "Normal",
+        }),
+        fields,
+    )
+}
+
+/// A message of a private topic with a single item, e.g., `message("order", &order(json!({})))`.
+pub fn message(topic: &str, item: &str) -> String {
+    private_message(topic, &format!("[{}]", item))
+}
+
+fn item(mut item: Value, fields: Value) -> String {
+    let (Some(item_fields), Value::Object(fields)) = (item.as_object_mut(), fields) else {
+        panic!("fields must be an object");
+    };
+    for (key, value) in fields {
+        if value.is_null() {
+            item_fields.remove(&key);
+        } else {
+            item_fields.insert(key, value);
+        }
+    }
+    item.to_string()
+}
@@ -0,0 +1,159 @@
+//! Account endpoints (`/v5/account/*`). They require api key and secret key.
+//!
+//! Wallets are returned as the same [`Wallet`] type as the private websocket wallet topic.
+
+use serde::Serialize;
+
+use super::response::{AccountInfo, FeeRate, List, TransactionLog};
+use super::{optional_fields, RestApiClient};
+use crate::enums::{AccountType, Category, MarginMode, TransactionType};
+use crate::error::Result;
+use crate::ws::response::Wallet;
+
+/// Parameters to query transaction logs of a unified account.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionLogRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account_type: Option<AccountType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<Category>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    transaction_type: Option<TransactionType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl TransactionLogRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+optional_fields!(TransactionLogRequest {
+    /// Account type. Default: `UNIFIED`.
+    account_type: AccountType,
+    /// Product type.
+    category: Category,
+    /// Currency, e.g., USDC.
+    currency: String,
+    /// Base coin.
+    base_coin: String,
+    /// Transaction type.
+    transaction_type: TransactionType,
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+    /// Limit for data size per page. `[1, 50]`. Default: `20`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WalletQuery<'r> {
+    account_type: AccountType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coin: Option<&'r str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FeeRateQuery<'r> {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<&'r str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MarginModeBody {
+    set_margin_mode: MarginMode,
+}
+
+impl RestApiClient {
+    /// Query the wallet balance. `coin` can be a comma separated list, e.g., `USDT,USDC`.
+    pub fn wallet_balance(
+        &self,
+        account_type: AccountType,
+        coin: Option<&str>,
+    ) -> Result<List<'static, Wallet<'static>>> {
+        let req = WalletQuery { account_type, coin };
+        self.get_signed("/v5/account/wallet-balance", &req)
+    }
+
+    /// Query the trading fee rates. Options are queried by `base_coin`, others by `symbol`.
+    pub fn fee_rate(
+        &self,
+        category: Category,
+        symbol: Option<&str>,
+        base_coin: Option<&str>,
+    ) -> Result<List<'static, FeeRate<'static>>> {
+        let req = FeeRateQuery {
+            category,
+            symbol,
+            base_coin,
+        };
+        self.get_signed("/v5/account/fee-rate", &req)
+    }
+
+    /// Query the account information, such as the margin mode.
+    pub fn account_info(&self) -> Result<AccountInfo> {
+        self.get_signed("/v5/account/info", &())
+    }
+
+    /// Query the transaction logs of a unified account.
+    pub fn transaction_log(
+        &self,
+        req: &TransactionLogRequest,
+    ) -> Result<List<'static, TransactionLog<'static>>> {
+        self.get_signed("/v5/account/transaction-log", req)
+    }
+
+    /// Set the margin mode of a unified account.
+    pub fn set_margin_mode(&self, mode: MarginMode) -> Result<()> {
+        let body = MarginModeBody {
+            set_margin_mode: mode,
+        };
+        self.post_signed_empty("/v5/account/set-margin-mode", &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::{parse_body, query_string};
+
+    #[test]
+    fn test_account() {
+        let req = TransactionLogRequest::new()
+            .category(Category::Linear)
+            .transaction_type(TransactionType::Trade);
+        assert_eq!(query_string(&req).unwrap(), "category=linear&type=TRADE");
+
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"unifiedMarginStatus":3,"marginMode":"REGULAR_MARGIN","updatedTime":"1672125046000"},"retExtInfo":{},"time":1672125046000}"#;
+        let info: AccountInfo = parse_body(body).unwrap();
+        assert_eq!(info.margin_mode, MarginMode::RegularMargin);
+
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"nextPageCursor":"21963%3A1%2C14954%3A1","list":[{"symbol":"XRPUSDT","side":"Buy","funding":"-0.003676","orderLinkId":"","orderId":"1672128000-8-592324-1-2","fee":"0.00000000","change":"-0.003676","cashFlow":"0","transactionTime":"1672128000000","type":"SETTLEMENT","feeRate":"0.0001","size":"100","qty":"100","cashBalance":"5086.55825002","currency":"USDT","category":"linear","tradePrice":"0.3676","tradeId":"534c0003-4bf7-486f-aa02-78cee36825e4"}]},"retExtInfo":{},"time":1672132481405}"#;
+        let logs: List<TransactionLog> = parse_body(body).unwrap();
+        let log = &logs.list[0];
+        assert_eq!(log.transaction_type, TransactionType::Settlement);
+        assert_eq!(log.category, Category::Linear);
+        assert_eq!(log.cash_balance.to_string(), "5086.55825002");
+    }
+}
@@ -0,0 +1,432 @@
+//! Asset endpoints (`/v5/asset/*`). They require api key and secret key.
+//!
+//! Transfer requests generate their `transfer_id` when they are created, so sending the same
+//! request again, e.g., retrying after a timeout, never transfers twice.
+
+use serde::Serialize;
+
+use super::response::{
+    CoinInfo, DepositAddress, DepositRecord, InternalTransfer, List, Rows, TransferId,
+    UniversalTransfer, WithdrawId, WithdrawRecord,
+};
+use super::{invalid, optional_fields, RestApiClient};
+use crate::enums::{AccountType, TransferStatus};
+use crate::error::Result;
+use crate::util::{millis, uuid};
+
+/// Parameters to transfer between accounts of the same member, e.g., from `FUND` to `UNIFIED`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalTransferRequest {
+    transfer_id: String,
+    coin: String,
+    amount: String,
+    from_account_type: AccountType,
+    to_account_type: AccountType,
+}
+
+impl InternalTransferRequest {
+    /// Create a request with a new random `transfer_id`.
+    pub fn new<S: AsRef<str>, A: ToString>(
+        coin: S,
+        amount: A,
+        from_account_type: AccountType,
+        to_account_type: AccountType,
+    ) -> Self {
+        Self {
+            transfer_id: uuid(),
+            coin: coin.as_ref().to_owned(),
+            amount: amount.to_string(),
+            from_account_type,
+            to_account_type,
+        }
+    }
+
+    /// Use the given `transfer_id`, which must be a UUID, instead of the generated one.
+    pub fn with_transfer_id<S: AsRef<str>>(mut self, transfer_id: S) -> Self {
+        self.transfer_id = transfer_id.as_ref().to_owned();
+        self
+    }
+
+    /// The ID of the transfer.
+    pub fn transfer_id(&self) -> &str {
+        &self.transfer_id
+    }
+
+    /// Check the required fields.
+    pub fn validate(&self) -> Result<()> {
+        if self.coin.is_empty() || self.amount.is_empty() {
+            return invalid("coin and amount are required");
+        }
+        if self.from_account_type == self.to_account_type {
+            return invalid("from and to account types must be different");
+        }
+        Ok(())
+    }
+}
+
+/// Parameters to transfer between the master account and sub-accounts.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UniversalTransferRequest {
+    transfer_id: String,
+    coin: String,
+    amount: String,
+    from_member_id: u64,
+    to_member_id: u64,
+    from_account_type: AccountType,
+    to_account_type: AccountType,
+}
+
+impl UniversalTransferRequest {
+    /// Create a request with a new random `transfer_id`.
+    pub fn new<S: AsRef<str>, A: ToString>(
+        coin: S,
+        amount: A,
+        from_member_id: u64,
+        to_member_id: u64,
+        from_account_type: AccountType,
+        to_account_type: AccountType,
+    ) -> Self {
+        Self {
+            transfer_id: uuid(),
+            coin: coin.as_ref().to_owned(),
+            amount: amount.to_string(),
+            from_member_id,
+            to_member_id,
+            from_account_type,
+            to_account_type,
+        }
+    }
+
+    /// Use the given `transfer_id`, which must be a UUID, instead of the generated one.
+    pub fn with_transfer_id<S: AsRef<str>>(mut self, transfer_id: S) -> Self {
+        self.transfer_id = transfer_id.as_ref().to_owned();
+        self
+    }
+
+    /// The ID of the transfer.
+    pub fn transfer_id(&self) -> &str {
+        &self.transfer_id
+    }
+
+    /// Check the required fields.
+    pub fn validate(&self) -> Result<()> {
+        if self.coin.is_empty() || self.amount.is_empty() {
+            return invalid("coin and amount are required");
+        }
+        if self.from_member_id == self.to_member_id
+            && self.from_account_type == self.to_account_type
+        {
+            return invalid("from and to accounts must be different");
+        }
+        Ok(())
+    }
+}
+
+/// Parameters to query internal or universal transfer records.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferHistoryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transfer_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<TransferStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl TransferHistoryRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+optional_fields!(TransferHistoryRequest {
+    /// Transfer ID.
+    transfer_id: String,
+    /// Coin.
+    coin: String,
+    /// Transfer status.
+    status: TransferStatus,
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+    /// Limit for data size per page. `[1, 50]`. Default: `20`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Parameters to query deposit records.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositRecordsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl DepositRecordsRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+optional_fields!(DepositRecordsRequest {
+    /// Coin.
+    coin: String,
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+    /// Limit for data size per page. `[1, 50]`. Default: `50`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Parameters to query withdrawal records.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawRecordsRequest {
+    #[serde(rename = "withdrawID", skip_serializing_if = "Option::is_none")]
+    withdraw_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    withdraw_type: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl WithdrawRecordsRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+optional_fields!(WithdrawRecordsRequest {
+    /// Withdrawal ID.
+    withdraw_id: String,
+    /// Coin.
+    coin: String,
+    /// `0`: on chain, `1`: off chain, `2`: all. Default: `0`.
+    withdraw_type: u8,
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+    /// Limit for data size per page. `[1, 50]`. Default: `50`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Parameters to withdraw to an address, which must be in the address book.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawRequest {
+    coin: String,
+    chain: String,
+    address: String,
+    amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    force_chain: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account_type: Option<AccountType>,
+}
+
+impl WithdrawRequest {
+    pub fn new<S: AsRef<str>, A: ToString>(coin: S, chain: S, address: S, amount: A) -> Self {
+        Self {
+            coin: coin.as_ref().to_owned(),
+            chain: chain.as_ref().to_owned(),
+            address: address.as_ref().to_owned(),
+            amount: amount.to_string(),
+            tag: None,
+            force_chain: None,
+            account_type: None,
+        }
+    }
+
+    /// Check the required fields.
+    pub fn validate(&self) -> Result<()> {
+        if self.coin.is_empty()
+            || self.chain.is_empty()
+            || self.address.is_empty()
+            || self.amount.is_empty()
+        {
+            return invalid("coin, chain, address and amount are required");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(WithdrawRequest {
+    /// Tag of the address. Required if the address has a tag.
+    tag: String,
+    /// `0`: withdraw off chain to a Bybit address if possible, `1`: always on chain. Default: `0`.
+    force_chain: u8,
+    /// `SPOT` or `FUND`. Default: `SPOT`.
+    account_type: AccountType,
+});
+
+#[derive(Serialize)]
+struct WithTimestamp<'r, R> {
+    timestamp: u64,
+    #[serde(flatten)]
+    request: &'r R,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoinQuery<'r> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coin: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain_type: Option<&'r str>,
+}
+
+impl RestApiClient {
+    /// Query the information of coins, such as the chains and the withdrawal fees.
+    pub fn coin_info(&self, coin: Option<&str>) -> Result<Rows<'static, CoinInfo<'static>>> {
+        let req = CoinQuery {
+            coin,
+            chain_type: None,
+        };
+        self.get_signed("/v5/asset/coin/query-info", &req)
+    }
+
+    /// Transfer between accounts of the same member.
+    pub fn internal_transfer(&self, req: &InternalTransferRequest) -> Result<TransferId<'static>> {
+        req.validate()?;
+        self.post_signed("/v5/asset/transfer/inter-transfer", req)
+    }
+
+    /// Query internal transfer records.
+    pub fn internal_transfers(
+        &self,
+        req: &TransferHistoryRequest,
+    ) -> Result<List<'static, InternalTransfer<'static>>> {
+        self.get_signed("/v5/asset/transfer/query-inter-transfer-list", req)
+    }
+
+    /// Transfer between the master account and sub-accounts. It requires the master account's key.
+    pub fn universal_transfer(
+        &self,
+        req: &UniversalTransferRequest,
+    ) -> Result<TransferId<'static>> {
+        req.validate()?;
+        self.post_signed("/v5/asset/transfer/universal-transfer", req)
+    }
+
+    /// Query universal transfer records.
+    pub fn universal_transfers(
+        &self,
+        req: &TransferHistoryRequest,
+    ) -> Result<List<'static, UniversalTransfer<'static>>> {
+        self.get_signed("/v5/asset/transfer/query-universal-transfer-list", req)
+    }
+
+    /// Query deposit records.
+    pub fn deposit_records(
+        &self,
+        req: &DepositRecordsRequest,
+    ) -> Result<Rows<'static, DepositRecord<'static>>> {
+        self.get_signed("/v5/asset/deposit/query-record", req)
+    }
+
+    /// Query withdrawal records.
+    pub fn withdraw_records(
+        &self,
+        req: &WithdrawRecordsRequest,
+    ) -> Result<Rows<'static, WithdrawRecord<'static>>> {
+        self.get_signed("/v5/asset/withdraw/query-record", req)
+    }
+
+    /// Query the deposit addresses of a coin, optionally on a single chain.
+    pub fn deposit_address<S: AsRef<str>>(
+        &self,
+        coin: S,
+        chain_type: Option<&str>,
+    ) -> Result<DepositAddress<'static>> {
+        let req = CoinQuery {
+            coin: Some(coin.as_ref()),
+            chain_type,
+        };
+        self.get_signed("/v5/asset/deposit/query-address", &req)
+    }
+
+    /// Withdraw to an address in the address book.
+    pub fn withdraw(&self, req: &WithdrawRequest) -> Result<WithdrawId<'static>> {
+        req.validate()?;
+        let body = WithTimestamp {
+            timestamp: millis() as u64,
+            request: req,
+        };
+        self.post_signed("/v5/asset/withdraw/create", &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{DepositStatus, WithdrawStatus};
+    use crate::rest::parse_body;
+
+    #[test]
+    fn test_transfer() {
+        let req =
+            InternalTransferRequest::new("USDT", "100", AccountType::Fund, AccountType::Unified);
+        let retried = req.clone();
+        assert_eq!(req.transfer_id(), retried.transfer_id());
+        let other =
+            InternalTransferRequest::new("USDT", "100", AccountType::Fund, AccountType::Unified);
+        assert_ne!(req.transfer_id(), other.transfer_id());
+        let body = serde_json::to_value(&req).unwrap();
+        assert_eq!(body["fromAccountType"], "FUND");
+        assert_eq!(body["transferId"], req.transfer_id());
+        assert!(
+            InternalTransferRequest::new("USDT", "100", AccountType::Fund, AccountType::Fund)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_records() {
+        let body = r#"{"retCode":0,"retMsg":"success","result":{"rows":[{"coin":"USDT","chain":"ETH","amount":"10000","txID":"skip-notification-scene-test-amount-202212270944-533285-USDT","status":3,"toAddress":"test-amount-address","tag":"","depositFee":"","successAt":"1672134274000","confirmations":"10000","txIndex":"","blockHash":""}],"nextPageCursor":"eyJtaW5JRCI6MTA0NjA0MywibWF4SUQiOjEwNDYwNDN9"},"retExtInfo":{},"time":1672191992512}"#;
+        let res: Rows<DepositRecord> = parse_body(body).unwrap();
+        assert_eq!(res.rows[0].status, DepositStatus::Success);
+
+        let body = r#"{"retCode":0,"retMsg":"success","result":{"rows":[{"coin":"USDT","chain":"ETH","amount":"8","txID":"","status":"SecurityCheck","toAddress":"0x99ced335d1e9e5e5d71d3b5c1c4e19a3c5f3e3e4","tag":"","withdrawFee":"5","createTime":"1670922217000","updateTime":"1670922217000","withdrawId":"9976","withdrawType":0}],"nextPageCursor":"eyJtaW5JRCI6OTk3NiwibWF4SUQiOjk5NzZ9"},"retExtInfo":{},"time":1670988051992}"#;
+        let res: Rows<WithdrawRecord> = parse_body(body).unwrap();
+        assert_eq!(res.rows[0].status, WithdrawStatus::SecurityCheck);
+        assert_eq!(res.rows[0].withdraw_id, "9976");
+    }
+}
@@ -0,0 +1,530 @@
+//! Market data endpoints (`/v5/market/*`). They do not require authentication.
+
+use serde::Serialize;
+
+use super::response::*;
+use super::{optional_fields, RestApiClient};
+use crate::enums::{Category, IntervalTime};
+use crate::error::Result;
+use crate::ws::response::{FutureTicker, SpotTicker};
+use crate::{FutureRole, KlineInterval};
+
+/// A request with the product type.
+#[derive(Serialize)]
+struct WithCategory<'r, R> {
+    category: Category,
+    #[serde(flatten)]
+    request: &'r R,
+}
+
+/// Query parameters of the kline endpoints.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KlineRequest {
+    category: Category,
+    symbol: String,
+    interval: KlineInterval,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+}
+
+impl KlineRequest {
+    pub fn new<S: AsRef<str>>(category: Category, symbol: S, interval: KlineInterval) -> Self {
+        Self {
+            category,
+            symbol: symbol.as_ref().to_owned(),
+            interval,
+            start: None,
+            end: None,
+            limit: None,
+        }
+    }
+}
+
+optional_fields!(KlineRequest {
+    /// The start timestamp (ms).
+    start: u64,
+    /// The end timestamp (ms).
+    end: u64,
+    /// Limit for data size per page. `[1, 1000]`. Default: `200`.
+    limit: u32,
+});
+
+/// Query parameters of the instruments info endpoint.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentsInfoRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl InstrumentsInfoRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+optional_fields!(InstrumentsInfoRequest {
+    /// Symbol name.
+    symbol: String,
+    /// Instrument status, e.g., `Trading`.
+    status: String,
+    /// Base coin. Apply to linear, inverse and option only.
+    base_coin: String,
+    /// Limit for data size per page. `[1, 1000]`. Default: `500`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Query parameters of the tickers endpoint.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TickersRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exp_date: Option<String>,
+}
+
+impl TickersRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+optional_fields!(TickersRequest {
+    /// Symbol name.
+    symbol: String,
+    /// Base coin. Apply to option only.
+    base_coin: String,
+    /// Expiry date, e.g., `25DEC22`. Apply to option only.
+    exp_date: String,
+});
+
+/// Query parameters of the funding rate history endpoint.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingHistoryRequest {
+    category: Category,
+    symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+}
+
+impl FundingHistoryRequest {
+    pub fn new<S: AsRef<str>>(role: FutureRole, symbol: S) -> Self {
+        Self {
+            category: role.into(),
+            symbol: symbol.as_ref().to_owned(),
+            start_time: None,
+            end_time: None,
+            limit: None,
+        }
+    }
+}
+
+optional_fields!(FundingHistoryRequest {
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+    /// Limit for data size per page. `[1, 200]`. Default: `200`.
+    limit: u32,
+});
+
+/// Query parameters of the recent trade endpoint.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentTradeRequest {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    option_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+}
+
+impl RecentTradeRequest {
+    pub fn new(category: Category) -> Self {
+        Self {
+            category,
+            symbol: None,
+            base_coin: None,
+            option_type: None,
+            limit: None,
+        }
+    }
+}
+
+optional_fields!(RecentTradeRequest {
+    /// Symbol name. Required for spot, linear and inverse.
+    symbol: String,
+    /// Base coin. Apply to option only.
+    base_coin: String,
+    /// `Call` or `Put`. Apply to option only.
+    option_type: String,
+    /// Limit for data size per page. Spot: `[1, 60]`, others: `[1, 1000]`.
+    limit: u32,
+});
+
+/// Query parameters of the open interest endpoint.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterestRequest {
+    category: Category,
+    symbol: String,
+    interval_time: IntervalTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl OpenInterestRequest {
+    pub fn new<S: AsRef<str>>(role: FutureRole, symbol: S, interval_time: IntervalTime) -> Self {
+        Self {
+            category: role.into(),
+            symbol: symbol.as_ref().to_owned(),
+            interval_time,
+            start_time: None,
+            end_time: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+}
+
+optional_fields!(OpenInterestRequest {
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+    /// Limit for data size per page. `[1, 200]`. Default: `50`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Query parameters of the historical volatility endpoint.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalVolatilityRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+}
+
+impl HistoricalVolatilityRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+optional_fields!(HistoricalVolatilityRequest {
+    /// Base coin. Default: `BTC`.
+    base_coin: String,
+    /// Period.
+    period: u32,
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+});
+
+/// Query parameters of the delivery price endpoint.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryPriceRequest {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl DeliveryPriceRequest {
+    pub fn new(category: Category) -> Self {
+        Self {
+            category,
+            symbol: None,
+            base_coin: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+}
+
+optional_fields!(DeliveryPriceRequest {
+    /// Symbol name.
+    symbol: String,
+    /// Base coin. Apply to option only.
+    base_coin: String,
+    /// Limit for data size per page. `[1, 200]`. Default: `50`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Query parameters of the long/short ratio endpoint.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRatioRequest {
+    category: Category,
+    symbol: String,
+    period: IntervalTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+}
+
+impl AccountRatioRequest {
+    pub fn new<S: AsRef<str>>(role: FutureRole, symbol: S, period: IntervalTime) -> Self {
+        Self {
+            category: role.into(),
+            symbol: symbol.as_ref().to_owned(),
+            period,
+            limit: None,
+        }
+    }
+}
+
+optional_fields!(AccountRatioRequest {
+    /// Limit for data size per page. `[1, 500]`. Default: `50`.
+    limit: u32,
+});
+
+#[derive(Serialize)]
+struct SymbolQuery<'r> {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct CoinQuery<'r> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coin: Option<&'r str>,
+}
+
+impl RestApiClient {
+    /// Get the server time.
+    pub fn server_time(&self) -> Result<ServerTime> {
+        self.get("/v5/market/time", &())
+    }
+
+    /// Query the kline. The list is sorted by start time in descending order.
+    pub fn kline(&self, req: &KlineRequest) -> Result<List<'static, KlineItem>> {
+        self.get("/v5/market/kline", req)
+    }
+
+    /// Query the mark price kline. Spot does not have this endpoint.
+    pub fn mark_price_kline(&self, req: &KlineRequest) -> Result<List<'static, PriceKlineItem>> {
+        self.get("/v5/market/mark-price-kline", req)
+    }
+
+    /// Query the index price kline. Spot does not have this endpoint.
+    pub fn index_price_kline(&self, req: &KlineRequest) -> Result<List<'static, PriceKlineItem>> {
+        self.get("/v5/market/index-price-kline", req)
+    }
+
+    /// Query the premium index price kline. Apply to linear only.
+    pub fn premium_index_price_kline(
+        &self,
+        req: &KlineRequest,
+    ) -> Result<List<'static, PriceKlineItem>> {
+        self.get("/v5/market/premium-index-price-kline", req)
+    }
+
+    /// Query the spot instruments.
+    pub fn spot_instruments(
+        &self,
+        req: &InstrumentsInfoRequest,
+    ) -> Result<List<'static, SpotInstrument<'static>>> {
+        let req = WithCategory {
+            category: Category::Spot,
+            request: req,
+        };
+        self.get("/v5/market/instruments-info", &req)
+    }
+
+    /// Query the linear or inverse future instruments.
+    pub fn future_instruments(
+        &self,
+        role: FutureRole,
+        req: &InstrumentsInfoRequest,
+    ) -> Result<List<'static, FutureInstrument<'static>>> {
+        let req = WithCategory {
+            category: role.into(),
+            request: req,
+        };
+        self.get("/v5/market/instruments-info", &req)
+    }
+
+    /// Query the option instruments.
+    pub fn option_instruments(
+        &self,
+        req: &InstrumentsInfoRequest,
+    ) -> Result<List<'static, OptionInstrument<'static>>> {
+        let req = WithCategory {
+            category: Category::Option,
+            request: req,
+        };
+        self.get("/v5/market/instruments-info", &req)
+    }
+
+    /// Query the orderbook snapshot.
+    ///
+    /// `limit` is the depth of each side. Spot: `[1, 200]`, linear and inverse: `[1, 500]`,
+    /// option: `[1, 25]`.
+    pub fn orderbook<S: AsRef<str>>(
+        &self,
+        category: Category,
+        symbol: S,
+        limit: Option<u32>,
+    ) -> Result<OrderbookSnapshot<'static>> {
+        let req = SymbolQuery {
+            category,
+            symbol: Some(symbol.as_ref()),
+            limit,
+        };
+        self.get("/v5/market/orderbook", &req)
+    }
+
+    /// Query the spot tickers.
+    pub fn spot_tickers(&self, req: &TickersRequest) -> Result<List<'static, SpotTicker<'static>>> {
+        let req = WithCategory {
+            category: Category::Spot,
+            request: req,
+        };
+        self.get("/v5/market/tickers", &req)
+    }
+
+    /// Query the linear or inverse future tickers.
+    pub fn future_tickers(
+        &self,
+        role: FutureRole,
+        req: &TickersRequest,
+    ) -> Result<List<'static, FutureTicker<'static>>> {
+        let req = WithCategory {
+            category: role.into(),
+            request: req,
+        };
+        self.get("/v5/market/tickers", &req)
+    }
+
+    /// Query the option tickers. Either `symbol` or `base_coin` is required.
+    pub fn option_tickers(
+        &self,
+        req: &TickersRequest,
+    ) -> Result<List<'static, OptionTicker<'static>>> {
+        let req = WithCategory {
+            category: Category::Option,
+            request: req,
+        };
+        self.get("/v5/market/tickers", &req)
+    }
+
+    /// Query the funding rate history.
+    pub fn funding_history(
+        &self,
+        req: &FundingHistoryRequest,
+    ) -> Result<List<'static, FundingRate<'static>>> {
+        self.get("/v5/market/funding/history", req)
+    }
+
+    /// Query the recent public trades.
+    pub fn recent_trade(
+        &self,
+        req: &RecentTradeRequest,
+    ) -> Result<List<'static, RecentTrade<'static>>> {
+        self.get("/v5/market/recent-trade", req)
+    }
+
+    /// Query the open interest.
+    pub fn open_interest(&self, req: &OpenInterestRequest) -> Result<List<'static, OpenInterest>> {
+        self.get("/v5/market/open-interest", req)
+    }
+
+    /// Query the option historical volatility.
+    pub fn historical_volatility(
+        &self,
+        req: &HistoricalVolatilityRequest,
+    ) -> Result<Vec<HistoricalVolatility>> {
+        let req = WithCategory {
+            category: Category::Option,
+            request: req,
+        };
+        self.get("/v5/market/historical-volatility", &req)
+    }
+
+    /// Query the insurance pool data. All coins are returned if `coin` is `None`.
+    pub fn insurance(&self, coin: Option<&str>) -> Result<Insurance<'static>> {
+        self.get("/v5/market/insurance", &CoinQuery { coin })
+    }
+
+    /// Query the risk limits of linear or inverse future symbols.
+    pub fn risk_limit(
+        &self,
+        role: FutureRole,
+        symbol: Option<&str>,
+    ) -> Result<List<'static, RiskLimit<'static>>> {
+        let req = SymbolQuery {
+            category: role.into(),
+            symbol,
+            limit: None,
+        };
+        self.get("/v5/market/risk-limit", &req)
+    }
+
+    /// Query the delivery price of inverse futures or options.
+    pub fn delivery_price(
+        &self,
+        req: &DeliveryPriceRequest,
+    ) -> Result<List<'static, DeliveryPrice<'static>>> {
+        self.get("/v5/market/delivery-price", req)
+    }
+
+    /// Query the long/short ratio of accounts.
+    pub fn account_ratio(
+        &self,
+        req: &AccountRatioRequest,
+    ) -> Result<List<'static, AccountRatio<'static>>> {
+        self.get("/v5/market/account-ratio", req)
+    }
+}
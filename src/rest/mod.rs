@@ -0,0 +1,402 @@
+//! A blocking client for Bybit's REST V5 APIs.
+
+pub mod account;
+pub mod asset;
+pub mod market;
+pub mod position;
+pub mod response;
+pub mod trade;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::enums::Category;
+use crate::error::{BybitError, Result};
+use crate::util::{millis, sign};
+use crate::ws::response::IntoOwned;
+use crate::ws::Credentials;
+
+const MAINNET: &str = "https://api.bybit.com";
+const TESTNET: &str = "https://api-testnet.bybit.com";
+const DEFAULT_RECV_WINDOW: u64 = 5000;
+
+/// A client for Bybit's REST APIs.
+///
+/// The client is cheap to clone. Clones share the same connection pool.
+#[derive(Clone)]
+pub struct RestApiClient {
+    base_url: String,
+    agent: ureq::Agent,
+    credentials: Option<Credentials>,
+    recv_window: u64,
+}
+
+impl RestApiClient {
+    /// Get a builder for building rest api client.
+    pub fn builder() -> RestApiClientBuilder {
+        RestApiClientBuilder::new()
+    }
+
+    /// Send a GET request and return the `result` field of the response.
+    fn get<T, Q>(&self, path: &str, query: &Q) -> Result<T>
+    where
+        T: FromBody,
+        Q: Serialize + ?Sized,
+    {
+        let body = self.send("GET", path, &query_string(query)?, None, false)?;
+        parse_body(&body)
+    }
+
+    /// Send a signed GET request and return the `result` field of the response.
+    fn get_signed<T, Q>(&self, path: &str, query: &Q) -> Result<T>
+    where
+        T: FromBody,
+        Q: Serialize + ?Sized,
+    {
+        let body = self.send("GET", path, &query_string(query)?, None, true)?;
+        parse_body(&body)
+    }
+
+    /// Send a signed POST request and return the `result` field of the response.
+    fn post_signed<T, B>(&self, path: &str, body: &B) -> Result<T>
+    where
+        T: FromBody,
+        B: Serialize + ?Sized,
+    {
+        let body = self.send("POST", path, "", Some(&serde_json::to_string(body)?), true)?;
+        parse_body(&body)
+    }
+
+    /// Send a signed POST request whose `result` field is empty or not needed.
+    fn post_signed_empty<B>(&self, path: &str, body: &B) -> Result<()>
+    where
+        B: Serialize + ?Sized,
+    {
+        let body = self.send("POST", path, "", Some(&serde_json::to_string(body)?), true)?;
+        check_ret_code(&body)
+    }
+
+    /// Send a request and return the response body.
+    ///
+    /// A signed request signs `timestamp + api_key + recv_window + payload`, where payload is
+    /// the query string of a GET request or the JSON body of a POST request.
+    fn send(
+        &self,
+        method: &str,
+        path: &str,
+        query: &str,
+        body: Option<&str>,
+        signed: bool,
+    ) -> Result<String> {
+        let url = if query.is_empty() {
+            format!("{}{}", self.base_url, path)
+        } else {
+            format!("{}{}?{}", self.base_url, path, query)
+        };
+        let mut request = self.agent.request(method, &url);
+        if signed {
+            let credentials = self
+                .credentials
+                .as_ref()
+                .ok_or_else(|| BybitError::AuthFailed("missing api key and secret".to_string()))?;
+            let timestamp = millis().to_string();
+            let recv_window = self.recv_window.to_string();
+            let payload = format!(
+                "{}{}{}{}",
+                timestamp,
+                credentials.api_key,
+                recv_window,
+                body.unwrap_or(query)
+            );
+            request = request
+                .set("X-BAPI-API-KEY", &credentials.api_key)
+                .set("X-BAPI-TIMESTAMP", &timestamp)
+                .set("X-BAPI-RECV-WINDOW", &recv_window)
+                .set("X-BAPI-SIGN", &sign(&credentials.secret, &payload));
+        }
+        let response = match body {
+            Some(body) => request
+                .set("Content-Type", "application/json")
+                .send_string(body)?,
+            None => request.call()?,
+        };
+        Ok(response.into_string().map_err(ureq::Error::from)?)
+    }
+}
+
+impl fmt::Debug for RestApiClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestApiClient")
+            .field("base_url", &self.base_url)
+            .field("authenticated", &self.credentials.is_some())
+            .field("recv_window", &self.recv_window)
+            .finish()
+    }
+}
+
+pub struct RestApiClientBuilder {
+    base_url: String,
+    timeout: Option<Duration>,
+    credentials: Option<Credentials>,
+    recv_window: u64,
+}
+
+impl RestApiClientBuilder {
+    /// Create a new `RestApiClientBuilder`. Base URL is set to the mainnet.
+    pub fn new() -> Self {
+        Self {
+            base_url: MAINNET.to_string(),
+            timeout: None,
+            credentials: None,
+            recv_window: DEFAULT_RECV_WINDOW,
+        }
+    }
+
+    /// Change base URL to the testnet.
+    pub fn testnet(mut self) -> Self {
+        self.base_url = TESTNET.to_string();
+        self
+    }
+
+    /// Set base URL to the URL specified, e.g., `https://api.bytick.com`.
+    pub fn base_url<S: AsRef<str>>(mut self, base_url: S) -> Self {
+        self.base_url = base_url.as_ref().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Set the timeout of a whole request, including connecting and reading the response.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set api key and secret key, which are required by private endpoints, e.g., placing orders.
+    pub fn credentials<S: AsRef<str>>(mut self, api_key: S, secret: S) -> Self {
+        self.credentials = Some(Credentials {
+            api_key: api_key.as_ref().to_owned(),
+            secret: secret.as_ref().to_owned(),
+        });
+        self
+    }
+
+    /// Set how long (ms) a signed request is valid after it is sent. Default: `5000`.
+    pub fn recv_window(mut self, recv_window: u64) -> Self {
+        self.recv_window = recv_window;
+        self
+    }
+
+    /// Build a rest api client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend of the system cannot be initialized.
+    pub fn build(self) -> RestApiClient {
+        let connector = native_tls::TlsConnector::new().expect("failed to initialize TLS");
+        let mut agent = ureq::AgentBuilder::new().tls_connector(Arc::new(connector));
+        if let Some(timeout) = self.timeout {
+            agent = agent.timeout(timeout);
+        }
+        RestApiClient {
+            base_url: self.base_url,
+            agent: agent.build(),
+            credentials: self.credentials,
+            recv_window: self.recv_window,
+        }
+    }
+}
+
+impl Default for RestApiClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Return an `InvalidRequest` error.
+fn invalid<T>(msg: impl Into<String>) -> Result<T> {
+    Err(BybitError::InvalidRequest(msg.into()))
+}
+
+/// Whether the category is linear or inverse.
+fn is_future(category: &Category) -> bool {
+    matches!(category, Category::Linear | Category::Inverse)
+}
+
+/// Add setters for optional fields of a request.
+macro_rules! optional_fields {
+    ($name:ident { $($(#[$meta:meta])* $field:ident: $ty:ident),* $(,)? }) => {
+        impl $name {
+            $($crate::rest::optional_fields!(@setter $(#[$meta])* $field: $ty);)*
+        }
+    };
+    (@setter $(#[$meta:meta])* $field:ident: String) => {
+        $(#[$meta])*
+        pub fn $field(mut self, $field: impl ToString) -> Self {
+            self.$field = Some($field.to_string());
+            self
+        }
+    };
+    (@setter $(#[$meta:meta])* $field:ident: $ty:ident) => {
+        $(#[$meta])*
+        pub fn $field(mut self, $field: $ty) -> Self {
+            self.$field = Some($field);
+            self
+        }
+    };
+}
+
+pub(crate) use optional_fields;
+
+/// An owned type parsed from the `result` field of a response.
+///
+/// The result is deserialized to the borrowed type first, which is the same type
+/// shared with websocket responses, then converted to the owned one.
+pub(crate) trait FromBody: Sized {
+    type Borrowed<'a>: Deserialize<'a> + IntoOwned<Owned = Self>;
+}
+
+macro_rules! impl_from_body {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl $crate::rest::FromBody for $name<'static> {
+                type Borrowed<'a> = $name<'a>;
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_from_body;
+
+macro_rules! impl_from_body_static {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl $crate::rest::FromBody for $ty {
+                type Borrowed<'a> = $ty;
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_from_body_static;
+
+impl<T: FromBody> FromBody for Vec<T> {
+    type Borrowed<'a> = Vec<T::Borrowed<'a>>;
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Header<'a> {
+    ret_code: i64,
+    #[serde(borrow)]
+    ret_msg: std::borrow::Cow<'a, str>,
+}
+
+#[derive(Deserialize)]
+struct Envelope<T> {
+    result: T,
+}
+
+/// Return an `ApiError` if `retCode` of the response body is not zero.
+fn check_ret_code(body: &str) -> Result<()> {
+    let header: Header = serde_json::from_str(body)?;
+    if header.ret_code != 0 {
+        return Err(BybitError::ApiError {
+            code: header.ret_code,
+            msg: header.ret_msg.into_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Parse the `result` field of a response body.
+fn parse_body<T: FromBody>(body: &str) -> Result<T> {
+    check_ret_code(body)?;
+    let envelope: Envelope<T::Borrowed<'_>> = serde_json::from_str(body)?;
+    Ok(envelope.result.into_owned())
+}
+
+/// Convert a request to query pairs. `None` fields are expected to be skipped by the request.
+fn query_pairs<Q: Serialize + ?Sized>(query: &Q) -> Result<Vec<(String, String)>> {
+    let pairs = match serde_json::to_value(query)? {
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(s) => (key, s),
+                value => (key, value.to_string()),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(pairs)
+}
+
+/// Convert a request to a URL-encoded query string.
+fn query_string<Q: Serialize + ?Sized>(query: &Q) -> Result<String> {
+    let pairs = query_pairs(query)?;
+    let pairs: Vec<String> = pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect();
+    Ok(pairs.join("&"))
+}
+
+/// Percent-encode all characters except the unreserved ones.
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::market::KlineRequest;
+    use crate::rest::response::{KlineItem, List};
+    use crate::KlineInterval;
+
+    #[test]
+    fn test_query_pairs() {
+        let req = KlineRequest::new(Category::Linear, "BTCUSDT", KlineInterval::Min5).limit(2);
+        let pairs = query_pairs(&req).unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("category".to_string(), "linear".to_string()),
+                ("interval".to_string(), "5".to_string()),
+                ("limit".to_string(), "2".to_string()),
+                ("symbol".to_string(), "BTCUSDT".to_string()),
+            ]
+        );
+        assert_eq!(
+            query_string(&req).unwrap(),
+            "category=linear&interval=5&limit=2&symbol=BTCUSDT"
+        );
+        assert_eq!(encode("page_args=1 2"), "page_args%3D1%202");
+    }
+
+    #[test]
+    fn test_parse_body() {
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"symbol":"BTCUSDT","category":"linear","list":[["1670608800000","17071","17073","17027","17055.5","268611","15.74462667"]]},"retExtInfo":{},"time":1672025956592}"#;
+        let res: List<KlineItem> = parse_body(body).unwrap();
+        assert_eq!(res.symbol.as_deref(), Some("BTCUSDT"));
+        assert_eq!(res.list.len(), 1);
+
+        let body = r#"{"retCode":10001,"retMsg":"Not supported symbols","result":{},"retExtInfo":{},"time":1672025956592}"#;
+        match parse_body::<List<KlineItem>>(body) {
+            Err(BybitError::ApiError { code, msg }) => {
+                assert_eq!(code, 10001);
+                assert_eq!(msg, "Not supported symbols");
+            }
+            res => panic!("unexpected result: {:?}", res.map(|res| res.list.len())),
+        }
+    }
+}
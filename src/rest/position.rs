@@ -0,0 +1,430 @@
+//! Position endpoints (`/v5/position/*`). They require api key and secret key.
+//!
+//! Positions are returned as the same [`Position`] type as the private websocket position topic,
+//! so a REST snapshot and websocket updates can be merged into one state.
+
+use serde::Serialize;
+
+use super::response::{ClosedPnl, List};
+use super::{invalid, is_future, optional_fields, RestApiClient};
+use crate::enums::{Category, TpslMode, TriggerBy};
+use crate::error::Result;
+use crate::ws::response::Position;
+
+/// Return an error if the category is not linear or inverse.
+fn require_future(category: &Category) -> Result<()> {
+    if !is_future(category) {
+        return invalid("only linear and inverse are supported");
+    }
+    Ok(())
+}
+
+/// Parameters to query positions.
+///
+/// Linear and inverse require either `symbol` or `settle_coin`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionListRequest {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    settle_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl PositionListRequest {
+    pub fn new(category: Category) -> Self {
+        Self {
+            category,
+            symbol: None,
+            base_coin: None,
+            settle_coin: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        if is_future(&self.category) && self.symbol.is_none() && self.settle_coin.is_none() {
+            return invalid("either symbol or settle_coin is required");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(PositionListRequest {
+    /// Symbol name. Positions of the symbol are returned even if they are empty.
+    symbol: String,
+    /// Base coin. Apply to options only.
+    base_coin: String,
+    /// Settle coin. Only non-empty positions are returned.
+    settle_coin: String,
+    /// Limit for data size per page. `[1, 200]`. Default: `20`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Parameters to switch the position mode.
+///
+/// Either `symbol` or `coin` is required. Apply to USDT perpetual and inverse futures.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchModeRequest {
+    category: Category,
+    mode: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coin: Option<String>,
+}
+
+impl SwitchModeRequest {
+    /// `mode` is `0` for one-way mode and `3` for hedge mode.
+    pub fn new(category: Category, mode: u8) -> Self {
+        Self {
+            category,
+            mode,
+            symbol: None,
+            coin: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        require_future(&self.category)?;
+        if self.symbol.is_none() && self.coin.is_none() {
+            return invalid("either symbol or coin is required");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(SwitchModeRequest {
+    /// Symbol name.
+    symbol: String,
+    /// Settle coin. Switch all symbols settled in the coin.
+    coin: String,
+});
+
+/// Parameters to set take profit, stop loss or trailing stop of a position.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TradingStopRequest {
+    category: Category,
+    symbol: String,
+    position_idx: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    take_profit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_loss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trailing_stop: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tp_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sl_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active_price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tpsl_mode: Option<TpslMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tp_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sl_size: Option<String>,
+}
+
+impl TradingStopRequest {
+    /// `position_idx` is `0` for one-way mode, `1` for the buy side and `2` for the sell side of hedge mode.
+    pub fn new<S: AsRef<str>>(category: Category, symbol: S, position_idx: u8) -> Self {
+        Self {
+            category,
+            symbol: symbol.as_ref().to_owned(),
+            position_idx,
+            take_profit: None,
+            stop_loss: None,
+            trailing_stop: None,
+            tp_trigger_by: None,
+            sl_trigger_by: None,
+            active_price: None,
+            tpsl_mode: None,
+            tp_size: None,
+            sl_size: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        require_future(&self.category)?;
+        if self.symbol.is_empty() {
+            return invalid("symbol is required");
+        }
+        if self.tpsl_mode == Some(TpslMode::Partial)
+            && ((self.take_profit.is_some() && self.tp_size.is_none())
+                || (self.stop_loss.is_some() && self.sl_size.is_none()))
+        {
+            return invalid("tp_size and sl_size are required for partial mode");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(TradingStopRequest {
+    /// Take profit price. `"0"` cancels the take profit.
+    take_profit: String,
+    /// Stop loss price. `"0"` cancels the stop loss.
+    stop_loss: String,
+    /// Trailing stop by price distance. `"0"` cancels the trailing stop.
+    trailing_stop: String,
+    /// The price type to trigger take profit.
+    tp_trigger_by: TriggerBy,
+    /// The price type to trigger stop loss.
+    sl_trigger_by: TriggerBy,
+    /// The price to activate the trailing stop.
+    active_price: String,
+    /// Tp/Sl mode. Default: `Full`.
+    tpsl_mode: TpslMode,
+    /// Take profit size. Required for partial mode.
+    tp_size: String,
+    /// Stop loss size. Required for partial mode.
+    sl_size: String,
+});
+
+/// Parameters to query closed profit and loss.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosedPnlRequest {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl ClosedPnlRequest {
+    pub fn new(category: Category) -> Self {
+        Self {
+            category,
+            symbol: None,
+            start_time: None,
+            end_time: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        require_future(&self.category)
+    }
+}
+
+optional_fields!(ClosedPnlRequest {
+    /// Symbol name.
+    symbol: String,
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+    /// Limit for data size per page. `[1, 100]`. Default: `50`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LeverageBody<'r> {
+    category: Category,
+    symbol: &'r str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trade_mode: Option<u8>,
+    buy_leverage: String,
+    sell_leverage: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MarginBody<'r> {
+    category: Category,
+    symbol: &'r str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_add_margin: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    margin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position_idx: Option<u8>,
+}
+
+/// Fill the category of positions, which REST responses do not have.
+fn fill_category(
+    mut res: List<'static, Position<'static>>,
+    category: &Category,
+) -> List<'static, Position<'static>> {
+    for position in &mut res.list {
+        if position.category.is_none() {
+            position.category = Some(category.clone());
+        }
+    }
+    res
+}
+
+impl RestApiClient {
+    /// Query positions.
+    pub fn positions(&self, req: &PositionListRequest) -> Result<List<'static, Position<'static>>> {
+        req.validate()?;
+        let res = self.get_signed("/v5/position/list", req)?;
+        Ok(fill_category(res, &req.category))
+    }
+
+    /// Set the leverage of a linear or inverse symbol.
+    pub fn set_leverage<S: AsRef<str>, L: ToString>(
+        &self,
+        category: Category,
+        symbol: S,
+        buy_leverage: L,
+        sell_leverage: L,
+    ) -> Result<()> {
+        require_future(&category)?;
+        let body = LeverageBody {
+            category,
+            symbol: symbol.as_ref(),
+            trade_mode: None,
+            buy_leverage: buy_leverage.to_string(),
+            sell_leverage: sell_leverage.to_string(),
+        };
+        self.post_signed_empty("/v5/position/set-leverage", &body)
+    }
+
+    /// Switch between cross margin (`trade_mode` `0`) and isolated margin (`trade_mode` `1`)
+    /// of a linear or inverse symbol, setting the leverage as well.
+    pub fn switch_isolated<S: AsRef<str>, L: ToString>(
+        &self,
+        category: Category,
+        symbol: S,
+        trade_mode: u8,
+        buy_leverage: L,
+        sell_leverage: L,
+    ) -> Result<()> {
+        require_future(&category)?;
+        let body = LeverageBody {
+            category,
+            symbol: symbol.as_ref(),
+            trade_mode: Some(trade_mode),
+            buy_leverage: buy_leverage.to_string(),
+            sell_leverage: sell_leverage.to_string(),
+        };
+        self.post_signed_empty("/v5/position/switch-isolated", &body)
+    }
+
+    /// Switch between one-way mode and hedge mode.
+    pub fn switch_position_mode(&self, req: &SwitchModeRequest) -> Result<()> {
+        req.validate()?;
+        self.post_signed_empty("/v5/position/switch-mode", req)
+    }
+
+    /// Set take profit, stop loss or trailing stop of a position.
+    pub fn set_trading_stop(&self, req: &TradingStopRequest) -> Result<()> {
+        req.validate()?;
+        self.post_signed_empty("/v5/position/trading-stop", req)
+    }
+
+    /// Turn on or off adding margin automatically of an isolated margin position.
+    pub fn set_auto_add_margin<S: AsRef<str>>(
+        &self,
+        category: Category,
+        symbol: S,
+        auto_add_margin: bool,
+        position_idx: Option<u8>,
+    ) -> Result<()> {
+        require_future(&category)?;
+        let body = MarginBody {
+            category,
+            symbol: symbol.as_ref(),
+            auto_add_margin: Some(auto_add_margin as u8),
+            margin: None,
+            position_idx,
+        };
+        self.post_signed_empty("/v5/position/set-auto-add-margin", &body)
+    }
+
+    /// Add margin to an isolated margin position, or reduce it with a negative `margin`.
+    ///
+    /// Query the position with [`RestApiClient::positions`] or watch the position topic for the new margin.
+    pub fn add_margin<S: AsRef<str>, M: ToString>(
+        &self,
+        category: Category,
+        symbol: S,
+        margin: M,
+        position_idx: Option<u8>,
+    ) -> Result<()> {
+        require_future(&category)?;
+        let body = MarginBody {
+            category,
+            symbol: symbol.as_ref(),
+            auto_add_margin: None,
+            margin: Some(margin.to_string()),
+            position_idx,
+        };
+        self.post_signed_empty("/v5/position/add-margin", &body)
+    }
+
+    /// Query closed profit and loss, sorted by created time in descending order.
+    pub fn closed_pnl(&self, req: &ClosedPnlRequest) -> Result<List<'static, ClosedPnl<'static>>> {
+        req.validate()?;
+        self.get_signed("/v5/position/closed-pnl", req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest::parse_body;
+
+    #[test]
+    fn test_validate() {
+        assert!(PositionListRequest::new(Category::Linear)
+            .validate()
+            .is_err());
+        assert!(PositionListRequest::new(Category::Linear)
+            .settle_coin("USDT")
+            .validate()
+            .is_ok());
+        assert!(PositionListRequest::new(Category::Option)
+            .validate()
+            .is_ok());
+        assert!(SwitchModeRequest::new(Category::Spot, 0)
+            .symbol("BTCUSDT")
+            .validate()
+            .is_err());
+        let req = TradingStopRequest::new(Category::Linear, "BTCUSDT", 0)
+            .tpsl_mode(TpslMode::Partial)
+            .take_profit("30000");
+        assert!(req.validate().is_err());
+        assert!(req.tp_size("0.01").validate().is_ok());
+    }
+
+    #[test]
+    fn test_positions() {
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"positionIdx":0,"riskId":1,"riskLimitValue":"2000000","symbol":"BTCUSDT","side":"Buy","size":"0.01","avgPrice":"16800.5","positionValue":"168.005","tradeMode":0,"autoAddMargin":0,"positionStatus":"Normal","leverage":"10","markPrice":"16840.15","liqPrice":"","bustPrice":"","positionIM":"16.8005","positionMM":"0.840025","tpslMode":"Full","takeProfit":"0.00","stopLoss":"0.00","trailingStop":"0.00","unrealisedPnl":"0.3965","cumRealisedPnl":"-0.0924","createdTime":"1672121182216","updatedTime":"1672210000000"}],"nextPageCursor":"","category":"linear"},"retExtInfo":{},"time":1672280219169}"#;
+        let res: List<Position> = parse_body(body).unwrap();
+        let res = fill_category(res, &Category::Linear);
+        let position = &res.list[0];
+        assert_eq!(position.category, Some(Category::Linear));
+        assert_eq!(position.entry_price.to_string(), "16800.5");
+        assert_eq!(position.size.to_string(), "0.01");
+    }
+}
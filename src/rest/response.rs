@@ -0,0 +1,1079 @@
+//! Response types of the REST APIs.
+//!
+//! Types whose shape overlaps with websocket responses, such as orderbook and tickers,
+//! are shared with [`crate::ws::response`].
+
+use serde::Deserialize;
+use std::borrow::Cow;
+
+use super::{impl_from_body, impl_from_body_static, FromBody};
+use crate::enums::*;
+use crate::ws::response::de;
+use crate::ws::response::{
+    impl_into_owned, impl_into_owned_base, impl_into_owned_static, FutureTicker, Number,
+    OptionalNumber, Order, Orderbook, Position, SpotTicker, Timestamp, Wallet,
+};
+
+/// A (paginated) list of items.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct List<'a, T> {
+    /// Product type. Some endpoints do not have this field.
+    #[serde(default)]
+    pub category: Option<Category>,
+    /// Symbol name. Only the endpoints querying a single symbol have this field.
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub symbol: Option<Cow<'a, str>>,
+    /// Items.
+    pub list: Vec<T>,
+    /// Cursor for the next page. It is empty or missing on the last page.
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub next_page_cursor: Option<Cow<'a, str>>,
+}
+
+impl<T: FromBody> FromBody for List<'static, T> {
+    type Borrowed<'a> = List<'a, T::Borrowed<'a>>;
+}
+
+/// A (paginated) list of rows, used by the asset endpoints instead of [`List`].
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Rows<'a, T> {
+    /// Items.
+    pub rows: Vec<T>,
+    /// Cursor for the next page. It is empty or missing on the last page.
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub next_page_cursor: Option<Cow<'a, str>>,
+}
+
+impl<T: FromBody> FromBody for Rows<'static, T> {
+    type Borrowed<'a> = Rows<'a, T::Borrowed<'a>>;
+}
+
+/// The kline data. The list is sorted by start time in descending order.
+#[derive(Deserialize, Debug, Clone)]
+pub struct KlineItem {
+    /// The start timestamp (ms).
+    #[serde(deserialize_with = "de::owned_timestamp")]
+    pub start: Timestamp<'static>,
+    /// Open price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub open: Number<'static>,
+    /// Highest price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub high: Number<'static>,
+    /// Lowest price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub low: Number<'static>,
+    /// Close price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub close: Number<'static>,
+    /// Trade volume. Unit of contract: pieces of contract. Unit of spot: quantity of coins.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub volume: Number<'static>,
+    /// Turnover. Unit of figure: quantity of quota coin.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub turnover: Number<'static>,
+}
+
+/// The mark price, index price or premium index price kline data.
+/// The list is sorted by start time in descending order.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PriceKlineItem {
+    /// The start timestamp (ms).
+    #[serde(deserialize_with = "de::owned_timestamp")]
+    pub start: Timestamp<'static>,
+    /// Open price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub open: Number<'static>,
+    /// Highest price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub high: Number<'static>,
+    /// Lowest price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub low: Number<'static>,
+    /// Close price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub close: Number<'static>,
+}
+
+/// Lot size filter of an instrument. The fields vary on the category.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LotSizeFilter {
+    /// The precision of base coin. Unique field for spot.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub base_precision: Option<Number<'static>>,
+    /// The precision of quote coin. Unique field for spot.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub quote_precision: Option<Number<'static>>,
+    /// Minimum order quantity.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub min_order_qty: Option<Number<'static>>,
+    /// Maximum order quantity.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub max_order_qty: Option<Number<'static>>,
+    /// Minimum order amount. Unique field for spot.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub min_order_amt: Option<Number<'static>>,
+    /// Maximum order amount. Unique field for spot.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub max_order_amt: Option<Number<'static>>,
+    /// The step to increase/reduce order quantity. Spot does not have this field.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub qty_step: Option<Number<'static>>,
+    /// Maximum order quantity for PostOnly order. Unique field for future.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub post_only_max_order_qty: Option<Number<'static>>,
+}
+
+/// Price filter of an instrument.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceFilter {
+    /// The step to increase/reduce order price.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub tick_size: Number<'static>,
+    /// Minimum order price. Spot does not have this field.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub min_price: Option<Number<'static>>,
+    /// Maximum order price. Spot does not have this field.
+    #[serde(default, deserialize_with = "de::owned_option_number")]
+    pub max_price: Option<Number<'static>>,
+}
+
+/// Leverage filter of a future instrument.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageFilter {
+    /// Minimum leverage.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub min_leverage: Number<'static>,
+    /// Maximum leverage.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub max_leverage: Number<'static>,
+    /// The step to increase/reduce leverage.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub leverage_step: Number<'static>,
+}
+
+/// The spot instrument.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotInstrument<'a> {
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Base coin.
+    #[serde(borrow)]
+    pub base_coin: Cow<'a, str>,
+    /// Quote coin.
+    #[serde(borrow)]
+    pub quote_coin: Cow<'a, str>,
+    /// Whether or not this symbol belongs to innovation zone. `0`: false, `1`: true.
+    #[serde(borrow)]
+    pub innovation: Cow<'a, str>,
+    /// Instrument status.
+    pub status: InstrumentStatus,
+    /// Whether or not margin trading is supported, e.g., `both`, `none`.
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub margin_trading: Option<Cow<'a, str>>,
+    /// Lot size filter.
+    pub lot_size_filter: LotSizeFilter,
+    /// Price filter.
+    pub price_filter: PriceFilter,
+}
+
+/// The linear or inverse future instrument.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FutureInstrument<'a> {
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Contract type.
+    pub contract_type: ContractType,
+    /// Instrument status.
+    pub status: InstrumentStatus,
+    /// Base coin.
+    #[serde(borrow)]
+    pub base_coin: Cow<'a, str>,
+    /// Quote coin.
+    #[serde(borrow)]
+    pub quote_coin: Cow<'a, str>,
+    /// Settle coin.
+    #[serde(borrow)]
+    pub settle_coin: Cow<'a, str>,
+    /// Launch timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub launch_time: Timestamp<'a>,
+    /// Delivery timestamp (ms). It is `0` for perpetual contracts.
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub delivery_time: Timestamp<'a>,
+    /// Delivery fee rate. It is empty for perpetual contracts.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub delivery_fee_rate: OptionalNumber<'a>,
+    /// Price scale.
+    #[serde(borrow)]
+    pub price_scale: Cow<'a, str>,
+    /// Leverage filter.
+    pub leverage_filter: LeverageFilter,
+    /// Price filter.
+    pub price_filter: PriceFilter,
+    /// Lot size filter.
+    pub lot_size_filter: LotSizeFilter,
+    /// Whether or not to support unified margin trade.
+    #[serde(default)]
+    pub unified_margin_trade: bool,
+    /// Funding interval (minute).
+    #[serde(default)]
+    pub funding_interval: u32,
+}
+
+/// The option instrument.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionInstrument<'a> {
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Option type.
+    pub options_type: OptionsType,
+    /// Instrument status.
+    pub status: InstrumentStatus,
+    /// Base coin.
+    #[serde(borrow)]
+    pub base_coin: Cow<'a, str>,
+    /// Quote coin.
+    #[serde(borrow)]
+    pub quote_coin: Cow<'a, str>,
+    /// Settle coin.
+    #[serde(borrow)]
+    pub settle_coin: Cow<'a, str>,
+    /// Launch timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub launch_time: Timestamp<'a>,
+    /// Delivery timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub delivery_time: Timestamp<'a>,
+    /// Delivery fee rate.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub delivery_fee_rate: OptionalNumber<'a>,
+    /// Price filter.
+    pub price_filter: PriceFilter,
+    /// Lot size filter.
+    pub lot_size_filter: LotSizeFilter,
+}
+
+/// The orderbook snapshot.
+#[derive(Deserialize, Debug, Clone)]
+pub struct OrderbookSnapshot<'a> {
+    /// The orderbook data, shared with the websocket `snapshot` message.
+    #[serde(borrow, flatten)]
+    pub book: Orderbook<'a>,
+    /// The timestamp (ms) that the system generates the data.
+    pub ts: u64,
+}
+
+/// The option ticker.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionTicker<'a> {
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Best bid price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub bid1_price: Number<'a>,
+    /// Best bid size.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub bid1_size: Number<'a>,
+    /// Best bid iv.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub bid1_iv: Number<'a>,
+    /// Best ask price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub ask1_price: Number<'a>,
+    /// Best ask size.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub ask1_size: Number<'a>,
+    /// Best ask iv.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub ask1_iv: Number<'a>,
+    /// Last price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub last_price: Number<'a>,
+    /// The highest price in the last 24 hours.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub high_price_24h: Number<'a>,
+    /// The lowest price in the last 24 hours.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub low_price_24h: Number<'a>,
+    /// Mark price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub mark_price: Number<'a>,
+    /// Index price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub index_price: Number<'a>,
+    /// Mark price iv.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub mark_iv: Number<'a>,
+    /// Underlying price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub underlying_price: Number<'a>,
+    /// Open interest size.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub open_interest: Number<'a>,
+    /// Turnover for 24h.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub turnover_24h: Number<'a>,
+    /// Volume for 24h.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub volume_24h: Number<'a>,
+    /// Total volume.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub total_volume: Number<'a>,
+    /// Total turnover.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub total_turnover: Number<'a>,
+    /// Delta.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub delta: Number<'a>,
+    /// Gamma.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub gamma: Number<'a>,
+    /// Vega.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub vega: Number<'a>,
+    /// Theta.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub theta: Number<'a>,
+    /// Predicated delivery price. It has value when 30 min before delivery.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub predicted_delivery_price: OptionalNumber<'a>,
+    /// The change in the last 24 hous.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub change_24h: Number<'a>,
+}
+
+/// The funding rate history.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate<'a> {
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Funding rate.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub funding_rate: Number<'a>,
+    /// Funding rate timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub funding_rate_timestamp: Timestamp<'a>,
+}
+
+/// The public trading history.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentTrade<'a> {
+    /// Execution ID.
+    #[serde(borrow)]
+    pub exec_id: Cow<'a, str>,
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Trade price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub price: Number<'a>,
+    /// Trade size.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub size: Number<'a>,
+    /// Side of taker.
+    pub side: Side,
+    /// Trade timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub time: Timestamp<'a>,
+    /// Whether the trade is block trade.
+    pub is_block_trade: bool,
+}
+
+/// The open interest.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterest {
+    /// Open interest.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub open_interest: Number<'static>,
+    /// The timestamp (ms).
+    #[serde(deserialize_with = "de::owned_timestamp")]
+    pub timestamp: Timestamp<'static>,
+}
+
+/// The historical volatility of option.
+#[derive(Deserialize, Debug, Clone)]
+pub struct HistoricalVolatility {
+    /// Period.
+    pub period: u32,
+    /// Volatility.
+    #[serde(deserialize_with = "de::owned_number")]
+    pub value: Number<'static>,
+    /// The timestamp (ms).
+    #[serde(deserialize_with = "de::owned_timestamp")]
+    pub time: Timestamp<'static>,
+}
+
+/// The insurance pool data.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Insurance<'a> {
+    /// Data updated timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub updated_time: Timestamp<'a>,
+    /// Insurance balances.
+    #[serde(borrow)]
+    pub list: Vec<InsuranceItem<'a>>,
+}
+
+/// The insurance balance of a coin.
+#[derive(Deserialize, Debug, Clone)]
+pub struct InsuranceItem<'a> {
+    /// Coin.
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    /// Balance.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub balance: Number<'a>,
+    /// Coin USD value.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub value: Number<'a>,
+}
+
+/// The risk limit.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskLimit<'a> {
+    /// Risk ID.
+    pub id: u64,
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Position limit.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub risk_limit_value: Number<'a>,
+    /// Maintain margin rate.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub maintenance_margin: Number<'a>,
+    /// Initial margin rate.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub initial_margin: Number<'a>,
+    /// Allowed leverages.
+    #[serde(borrow, default, deserialize_with = "de::vec_cow")]
+    pub section: Vec<Cow<'a, str>>,
+    /// Whether it is the lowest risk limit. `1`: true, `0`: false.
+    pub is_lowest_risk: u8,
+    /// Allowed max leverage.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub max_leverage: Number<'a>,
+}
+
+/// The delivery price.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryPrice<'a> {
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Delivery price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub delivery_price: Number<'a>,
+    /// Delivery timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub delivery_time: Timestamp<'a>,
+}
+
+/// The long/short ratio of accounts.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRatio<'a> {
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// The ratio of the number of long position.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub buy_ratio: Number<'a>,
+    /// The ratio of the number of short position.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub sell_ratio: Number<'a>,
+    /// The timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub timestamp: Timestamp<'a>,
+}
+
+/// The server time.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerTime {
+    /// Server timestamp (sec).
+    #[serde(deserialize_with = "de::owned_timestamp")]
+    pub time_second: Timestamp<'static>,
+    /// Server timestamp (nano).
+    #[serde(deserialize_with = "de::owned_timestamp")]
+    pub time_nano: Timestamp<'static>,
+}
+
+/// The IDs of a created, amended or cancelled order.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderId<'a> {
+    /// Order ID.
+    #[serde(borrow)]
+    pub order_id: Cow<'a, str>,
+    /// User customised order ID.
+    #[serde(borrow)]
+    pub order_link_id: Cow<'a, str>,
+}
+
+/// The result of an order in a batch.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOrder<'a> {
+    /// Product type.
+    pub category: Category,
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Order ID. It is empty if the order failed.
+    #[serde(borrow)]
+    pub order_id: Cow<'a, str>,
+    /// User customised order ID.
+    #[serde(borrow)]
+    pub order_link_id: Cow<'a, str>,
+    /// Result code of the order. `0` means success.
+    #[serde(skip)]
+    pub code: i64,
+    /// Result message of the order.
+    #[serde(skip)]
+    pub msg: Cow<'a, str>,
+}
+
+impl<'a> BatchOrder<'a> {
+    /// Whether the order succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.code == 0
+    }
+}
+
+/// The result code and message of an order in a batch.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct BatchStatus<'a> {
+    pub(crate) code: i64,
+    #[serde(borrow)]
+    pub(crate) msg: Cow<'a, str>,
+}
+
+/// The closed profit and loss of a position.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosedPnl<'a> {
+    /// Symbol name.
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    /// Order ID.
+    #[serde(borrow)]
+    pub order_id: Cow<'a, str>,
+    /// Side of the closing order.
+    pub side: Side,
+    /// Order quantity.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub qty: Number<'a>,
+    /// Order price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub order_price: Number<'a>,
+    /// Order type.
+    pub order_type: OrderType,
+    /// Execution type.
+    pub exec_type: ExecType,
+    /// Closed size.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub closed_size: Number<'a>,
+    /// Cumulated position value of the entry.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub cum_entry_value: Number<'a>,
+    /// Average entry price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub avg_entry_price: Number<'a>,
+    /// Cumulated position value of the exit.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub cum_exit_value: Number<'a>,
+    /// Average exit price.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub avg_exit_price: Number<'a>,
+    /// Closed profit and loss.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub closed_pnl: Number<'a>,
+    /// The number of fills of the closing order.
+    #[serde(deserialize_with = "de::from_str")]
+    pub fill_count: u32,
+    /// Leverage.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub leverage: Number<'a>,
+    /// Created timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub created_time: Timestamp<'a>,
+    /// Updated timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub updated_time: Timestamp<'a>,
+}
+
+/// The trading fee rate of a symbol.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRate<'a> {
+    /// Symbol name. It is empty for options, whose fee rate is set per base coin.
+    #[serde(borrow, default)]
+    pub symbol: Cow<'a, str>,
+    /// Base coin. Options only.
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub base_coin: Option<Cow<'a, str>>,
+    /// Taker fee rate.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub taker_fee_rate: Number<'a>,
+    /// Maker fee rate.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub maker_fee_rate: Number<'a>,
+}
+
+/// The account information.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    /// Account status.
+    /// - `1`: classic account.
+    /// - `3`: unified trade account 1.0.
+    /// - `4`: unified trade account 1.0 (pro).
+    /// - `5`: unified trade account 2.0.
+    /// - `6`: unified trade account 2.0 (pro).
+    pub unified_margin_status: u8,
+    /// Margin mode.
+    pub margin_mode: MarginMode,
+    /// Updated timestamp (ms).
+    #[serde(deserialize_with = "de::owned_timestamp")]
+    pub updated_time: Timestamp<'static>,
+}
+
+/// A transaction log of a unified account.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionLog<'a> {
+    /// Symbol name.
+    #[serde(borrow, default)]
+    pub symbol: Cow<'a, str>,
+    /// Product type.
+    pub category: Category,
+    /// Side. `None` for transactions other than trades.
+    pub side: Side,
+    /// Transaction timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub transaction_time: Timestamp<'a>,
+    /// Transaction type.
+    #[serde(rename = "type")]
+    pub transaction_type: TransactionType,
+    /// Quantity. Positive for buy and negative for sell.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub qty: OptionalNumber<'a>,
+    /// Position size after the transaction.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub size: OptionalNumber<'a>,
+    /// Currency, e.g., USDC.
+    #[serde(borrow)]
+    pub currency: Cow<'a, str>,
+    /// Trade price.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub trade_price: OptionalNumber<'a>,
+    /// Funding fee. Positive means paid and negative means received.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub funding: OptionalNumber<'a>,
+    /// Trading fee. Positive means paid and negative means rebate.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub fee: OptionalNumber<'a>,
+    /// Cash flow, e.g., realised PnL or transferred amount, not including fees.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub cash_flow: OptionalNumber<'a>,
+    /// Change of the wallet balance: `cash_flow - fee - funding`.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub change: Number<'a>,
+    /// Wallet balance after the transaction.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub cash_balance: Number<'a>,
+    /// Fee rate of the trade.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub fee_rate: OptionalNumber<'a>,
+    /// Trade ID.
+    #[serde(borrow, default)]
+    pub trade_id: Cow<'a, str>,
+    /// Order ID.
+    #[serde(borrow, default)]
+    pub order_id: Cow<'a, str>,
+    /// User customised order ID.
+    #[serde(borrow, default)]
+    pub order_link_id: Cow<'a, str>,
+}
+
+/// The information of a coin.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinInfo<'a> {
+    /// Coin name.
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    /// Coin.
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    /// The remaining amount which can be withdrawn today.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub remain_amount: Number<'a>,
+    /// The chains of the coin.
+    #[serde(borrow, default)]
+    pub chains: Vec<CoinChain<'a>>,
+}
+
+/// A chain of a coin.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinChain<'a> {
+    /// Chain type.
+    #[serde(borrow)]
+    pub chain_type: Cow<'a, str>,
+    /// Chain.
+    #[serde(borrow)]
+    pub chain: Cow<'a, str>,
+    /// The number of confirmations for a deposit.
+    #[serde(borrow)]
+    pub confirmation: Cow<'a, str>,
+    /// Withdrawal fee. Empty if withdrawals are not supported.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub withdraw_fee: OptionalNumber<'a>,
+    /// Minimum deposit amount.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub deposit_min: OptionalNumber<'a>,
+    /// Minimum withdrawal amount.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub withdraw_min: OptionalNumber<'a>,
+    /// Precision of the amount.
+    #[serde(borrow)]
+    pub min_accuracy: Cow<'a, str>,
+    /// Whether deposits are supported. `0`: false, `1`: true.
+    #[serde(deserialize_with = "de::from_str")]
+    pub chain_deposit: u8,
+    /// Whether withdrawals are supported. `0`: false, `1`: true.
+    #[serde(deserialize_with = "de::from_str")]
+    pub chain_withdraw: u8,
+}
+
+/// The ID of a created transfer.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferId<'a> {
+    /// Transfer ID.
+    #[serde(borrow)]
+    pub transfer_id: Cow<'a, str>,
+}
+
+/// An internal transfer between accounts of the same member.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalTransfer<'a> {
+    /// Transfer ID.
+    #[serde(borrow)]
+    pub transfer_id: Cow<'a, str>,
+    /// Coin.
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    /// Amount.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub amount: Number<'a>,
+    /// From account type.
+    pub from_account_type: AccountType,
+    /// To account type.
+    pub to_account_type: AccountType,
+    /// Transfer timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub timestamp: Timestamp<'a>,
+    /// Transfer status.
+    pub status: TransferStatus,
+}
+
+/// A universal transfer between the master account and sub-accounts.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UniversalTransfer<'a> {
+    /// Transfer ID.
+    #[serde(borrow)]
+    pub transfer_id: Cow<'a, str>,
+    /// Coin.
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    /// Amount.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub amount: Number<'a>,
+    /// From member ID.
+    #[serde(borrow)]
+    pub from_member_id: Cow<'a, str>,
+    /// To member ID.
+    #[serde(borrow)]
+    pub to_member_id: Cow<'a, str>,
+    /// From account type.
+    pub from_account_type: AccountType,
+    /// To account type.
+    pub to_account_type: AccountType,
+    /// Transfer timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub timestamp: Timestamp<'a>,
+    /// Transfer status.
+    pub status: TransferStatus,
+}
+
+/// A deposit record.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositRecord<'a> {
+    /// Coin.
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    /// Chain.
+    #[serde(borrow)]
+    pub chain: Cow<'a, str>,
+    /// Amount.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub amount: Number<'a>,
+    /// Transaction ID.
+    #[serde(rename = "txID", borrow)]
+    pub tx_id: Cow<'a, str>,
+    /// Deposit status.
+    pub status: DepositStatus,
+    /// Deposit address.
+    #[serde(borrow)]
+    pub to_address: Cow<'a, str>,
+    /// Tag of the deposit address.
+    #[serde(borrow, default)]
+    pub tag: Cow<'a, str>,
+    /// Deposit fee.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub deposit_fee: OptionalNumber<'a>,
+    /// Deposit success timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub success_at: Timestamp<'a>,
+    /// The number of confirmations.
+    #[serde(borrow)]
+    pub confirmations: Cow<'a, str>,
+    /// Transaction sequence number.
+    #[serde(borrow, default)]
+    pub tx_index: Cow<'a, str>,
+    /// Hash number on the chain.
+    #[serde(borrow, default)]
+    pub block_hash: Cow<'a, str>,
+}
+
+/// A withdrawal record.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawRecord<'a> {
+    /// Withdrawal ID.
+    #[serde(borrow)]
+    pub withdraw_id: Cow<'a, str>,
+    /// Transaction ID. It is empty until the withdrawal is on the chain.
+    #[serde(rename = "txID", borrow)]
+    pub tx_id: Cow<'a, str>,
+    /// Withdrawal type. `0`: on chain, `1`: off chain.
+    pub withdraw_type: u8,
+    /// Coin.
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    /// Chain.
+    #[serde(borrow)]
+    pub chain: Cow<'a, str>,
+    /// Amount.
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub amount: Number<'a>,
+    /// Withdrawal fee.
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub withdraw_fee: OptionalNumber<'a>,
+    /// Withdrawal status.
+    pub status: WithdrawStatus,
+    /// Withdrawal address, or the UID for off chain withdrawals.
+    #[serde(borrow)]
+    pub to_address: Cow<'a, str>,
+    /// Tag of the withdrawal address.
+    #[serde(borrow, default)]
+    pub tag: Cow<'a, str>,
+    /// Created timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub create_time: Timestamp<'a>,
+    /// Updated timestamp (ms).
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub update_time: Timestamp<'a>,
+}
+
+/// The deposit addresses of a coin.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAddress<'a> {
+    /// Coin.
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
+    /// The addresses on each chain.
+    #[serde(borrow)]
+    pub chains: Vec<DepositChain<'a>>,
+}
+
+/// The deposit address on a chain.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositChain<'a> {
+    /// Chain type.
+    #[serde(borrow)]
+    pub chain_type: Cow<'a, str>,
+    /// Deposit address.
+    #[serde(borrow)]
+    pub address_deposit: Cow<'a, str>,
+    /// Tag of the deposit address.
+    #[serde(borrow, default)]
+    pub tag_deposit: Cow<'a, str>,
+    /// Chain.
+    #[serde(borrow)]
+    pub chain: Cow<'a, str>,
+}
+
+/// The ID of a created withdrawal.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WithdrawId<'a> {
+    /// Withdrawal ID.
+    #[serde(borrow)]
+    pub id: Cow<'a, str>,
+}
+
+impl_into_owned_static!(
+    KlineItem,
+    PriceKlineItem,
+    LotSizeFilter,
+    PriceFilter,
+    LeverageFilter,
+    OpenInterest,
+    HistoricalVolatility,
+    ServerTime,
+    AccountInfo
+);
+
+impl_into_owned_base! {
+    List { category, symbol, list, next_page_cursor }
+    Rows { rows, next_page_cursor }
+}
+
+impl_into_owned! {
+    SpotInstrument {
+        symbol, base_coin, quote_coin, innovation, status, margin_trading, lot_size_filter,
+        price_filter
+    }
+    FutureInstrument {
+        symbol, contract_type, status, base_coin, quote_coin, settle_coin, launch_time,
+        delivery_time, delivery_fee_rate, price_scale, leverage_filter, price_filter,
+        lot_size_filter, unified_margin_trade, funding_interval
+    }
+    OptionInstrument {
+        symbol, options_type, status, base_coin, quote_coin, settle_coin, launch_time,
+        delivery_time, delivery_fee_rate, price_filter, lot_size_filter
+    }
+    OrderbookSnapshot { book, ts }
+    OptionTicker {
+        symbol, bid1_price, bid1_size, bid1_iv, ask1_price, ask1_size, ask1_iv, last_price,
+        high_price_24h, low_price_24h, mark_price, index_price, mark_iv, underlying_price,
+        open_interest, turnover_24h, volume_24h, total_volume, total_turnover, delta, gamma, vega,
+        theta, predicted_delivery_price, change_24h
+    }
+    FundingRate { symbol, funding_rate, funding_rate_timestamp }
+    RecentTrade { exec_id, symbol, price, size, side, time, is_block_trade }
+    Insurance { updated_time, list }
+    InsuranceItem { coin, balance, value }
+    RiskLimit {
+        id, symbol, risk_limit_value, maintenance_margin, initial_margin, section, is_lowest_risk,
+        max_leverage
+    }
+    DeliveryPrice { symbol, delivery_price, delivery_time }
+    AccountRatio { symbol, buy_ratio, sell_ratio, timestamp }
+    OrderId { order_id, order_link_id }
+    BatchOrder { category, symbol, order_id, order_link_id, code, msg }
+    ClosedPnl {
+        symbol, order_id, side, qty, order_price, order_type, exec_type, closed_size,
+        cum_entry_value, avg_entry_price, cum_exit_value, avg_exit_price, closed_pnl, fill_count,
+        leverage, created_time, updated_time
+    }
+    FeeRate { symbol, base_coin, taker_fee_rate, maker_fee_rate }
+    TransactionLog {
+        symbol, category, side, transaction_time, transaction_type, qty, size, currency,
+        trade_price, funding, fee, cash_flow, change, cash_balance, fee_rate, trade_id, order_id,
+        order_link_id
+    }
+    CoinInfo { name, coin, remain_amount, chains }
+    CoinChain {
+        chain_type, chain, confirmation, withdraw_fee, deposit_min, withdraw_min, min_accuracy,
+        chain_deposit, chain_withdraw
+    }
+    TransferId { transfer_id }
+    InternalTransfer {
+        transfer_id, coin, amount, from_account_type, to_account_type, timestamp, status
+    }
+    UniversalTransfer {
+        transfer_id, coin, amount, from_member_id, to_member_id, from_account_type,
+        to_account_type, timestamp, status
+    }
+    DepositRecord {
+        coin, chain, amount, tx_id, status, to_address, tag, deposit_fee, success_at,
+        confirmations, tx_index, block_hash
+    }
+    WithdrawRecord {
+        withdraw_id, tx_id, withdraw_type, coin, chain, amount, withdraw_fee, status, to_address,
+        tag, create_time, update_time
+    }
+    DepositAddress { coin, chains }
+    DepositChain { chain_type, address_deposit, tag_deposit, chain }
+    WithdrawId { id }
+}
+
+impl_from_body!(
+    SpotTicker,
+    FutureTicker,
+    SpotInstrument,
+    FutureInstrument,
+    OptionInstrument,
+    OrderbookSnapshot,
+    OptionTicker,
+    FundingRate,
+    RecentTrade,
+    Insurance,
+    RiskLimit,
+    DeliveryPrice,
+    AccountRatio,
+    OrderId,
+    Order,
+    Position,
+    Wallet,
+    ClosedPnl,
+    FeeRate,
+    TransactionLog,
+    CoinInfo,
+    TransferId,
+    InternalTransfer,
+    UniversalTransfer,
+    DepositRecord,
+    WithdrawRecord,
+    DepositAddress,
+    WithdrawId
+);
+
+impl_from_body_static!(
+    KlineItem,
+    PriceKlineItem,
+    OpenInterest,
+    HistoricalVolatility,
+    ServerTime,
+    AccountInfo
+);
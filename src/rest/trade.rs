@@ -0,0 +1,768 @@
+//! Trading endpoints (`/v5/order/*`). They require api key and secret key.
+//!
+//! Requests are validated per category before being sent, so obviously invalid orders
+//! fail fast with `BybitError::InvalidRequest` instead of a round trip.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::response::{BatchOrder, BatchStatus, List, OrderId};
+use super::{check_ret_code, invalid, is_future, optional_fields, RestApiClient};
+use crate::enums::{Category, OrderStatus, OrderType, Side, StopOrderType, TimeInForce, TriggerBy};
+use crate::error::Result;
+use crate::ws::response::{IntoOwned, Order};
+
+/// The max number of orders in a batch. Spot allows 10 only.
+const MAX_BATCH_SIZE: usize = 20;
+const MAX_SPOT_BATCH_SIZE: usize = 10;
+
+/// Parameters to place an order.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaceOrderRequest {
+    category: Category,
+    symbol: String,
+    side: Side,
+    order_type: OrderType,
+    qty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_leverage: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trigger_direction: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trigger_price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_iv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position_idx: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_link_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    take_profit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_loss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tp_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sl_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reduce_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    close_on_trigger: Option<bool>,
+}
+
+impl PlaceOrderRequest {
+    /// `qty` is the order quantity, e.g., `"0.01"`.
+    pub fn new<S: AsRef<str>, Q: ToString>(
+        category: Category,
+        symbol: S,
+        side: Side,
+        order_type: OrderType,
+        qty: Q,
+    ) -> Self {
+        Self {
+            category,
+            symbol: symbol.as_ref().to_owned(),
+            side,
+            order_type,
+            qty: qty.to_string(),
+            is_leverage: None,
+            price: None,
+            trigger_direction: None,
+            order_filter: None,
+            trigger_price: None,
+            trigger_by: None,
+            order_iv: None,
+            time_in_force: None,
+            position_idx: None,
+            order_link_id: None,
+            take_profit: None,
+            stop_loss: None,
+            tp_trigger_by: None,
+            sl_trigger_by: None,
+            reduce_only: None,
+            close_on_trigger: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        if self.symbol.is_empty() {
+            return invalid("symbol is required");
+        }
+        if self.qty.is_empty() {
+            return invalid("qty is required");
+        }
+        if self.order_type == OrderType::Limit && self.price.is_none() {
+            return invalid("price is required for limit orders");
+        }
+        if self.category == Category::Option && self.order_link_id.is_none() {
+            return invalid("order_link_id is required for options");
+        }
+        if self.category != Category::Spot
+            && (self.is_leverage.is_some() || self.order_filter.is_some())
+        {
+            return invalid("is_leverage and order_filter apply to spot only");
+        }
+        if self.category != Category::Option && self.order_iv.is_some() {
+            return invalid("order_iv applies to options only");
+        }
+        if !is_future(&self.category) && self.position_idx.is_some() {
+            return invalid("position_idx applies to linear and inverse only");
+        }
+        if self.category == Category::Spot
+            && (self.reduce_only.is_some() || self.close_on_trigger.is_some())
+        {
+            return invalid("reduce_only and close_on_trigger do not apply to spot");
+        }
+        if is_future(&self.category)
+            && self.trigger_price.is_some()
+            && self.trigger_direction.is_none()
+        {
+            return invalid("trigger_direction is required for conditional orders");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(PlaceOrderRequest {
+    /// Whether to borrow. Apply to spot of unified account only. `0`: false, `1`: true.
+    is_leverage: u8,
+    /// Order price. Required for limit orders.
+    price: String,
+    /// Conditional order trigger direction. `1`: rise to trigger price, `2`: fall to trigger price.
+    trigger_direction: u8,
+    /// `Order`, `tpslOrder`. Apply to spot only.
+    order_filter: String,
+    /// The trigger price of a conditional order.
+    trigger_price: String,
+    /// The price type to trigger a conditional order.
+    trigger_by: TriggerBy,
+    /// Implied volatility. Apply to options only.
+    order_iv: String,
+    /// Time in force. Default: `GTC`.
+    time_in_force: TimeInForce,
+    /// Position index. `0`: one-way mode, `1`: buy side of hedge-mode, `2`: sell side of hedge-mode.
+    position_idx: u8,
+    /// User customised order ID. Required for options.
+    order_link_id: String,
+    /// Take profit price.
+    take_profit: String,
+    /// Stop loss price.
+    stop_loss: String,
+    /// The price type to trigger take profit.
+    tp_trigger_by: TriggerBy,
+    /// The price type to trigger stop loss.
+    sl_trigger_by: TriggerBy,
+    /// Reduce only.
+    reduce_only: bool,
+    /// Close on trigger.
+    close_on_trigger: bool,
+});
+
+/// Parameters to amend an open order. Either `order_id` or `order_link_id` is required.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendOrderRequest {
+    category: Category,
+    symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_link_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_iv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trigger_price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    qty: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    take_profit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_loss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tp_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sl_trigger_by: Option<TriggerBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trigger_by: Option<TriggerBy>,
+}
+
+impl AmendOrderRequest {
+    pub fn new<S: AsRef<str>>(category: Category, symbol: S) -> Self {
+        Self {
+            category,
+            symbol: symbol.as_ref().to_owned(),
+            order_id: None,
+            order_link_id: None,
+            order_iv: None,
+            trigger_price: None,
+            qty: None,
+            price: None,
+            take_profit: None,
+            stop_loss: None,
+            tp_trigger_by: None,
+            sl_trigger_by: None,
+            trigger_by: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        if self.symbol.is_empty() {
+            return invalid("symbol is required");
+        }
+        if self.order_id.is_none() && self.order_link_id.is_none() {
+            return invalid("either order_id or order_link_id is required");
+        }
+        if self.category != Category::Option && self.order_iv.is_some() {
+            return invalid("order_iv applies to options only");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(AmendOrderRequest {
+    /// Order ID.
+    order_id: String,
+    /// User customised order ID.
+    order_link_id: String,
+    /// Implied volatility. Apply to options only.
+    order_iv: String,
+    /// The trigger price of a conditional order.
+    trigger_price: String,
+    /// Order quantity after modification.
+    qty: String,
+    /// Order price after modification.
+    price: String,
+    /// Take profit price after modification. `"0"` cancels the take profit.
+    take_profit: String,
+    /// Stop loss price after modification. `"0"` cancels the stop loss.
+    stop_loss: String,
+    /// The price type to trigger take profit.
+    tp_trigger_by: TriggerBy,
+    /// The price type to trigger stop loss.
+    sl_trigger_by: TriggerBy,
+    /// The price type to trigger a conditional order.
+    trigger_by: TriggerBy,
+});
+
+/// Parameters to cancel an open order. Either `order_id` or `order_link_id` is required.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrderRequest {
+    category: Category,
+    symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_link_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_filter: Option<String>,
+}
+
+impl CancelOrderRequest {
+    pub fn new<S: AsRef<str>>(category: Category, symbol: S) -> Self {
+        Self {
+            category,
+            symbol: symbol.as_ref().to_owned(),
+            order_id: None,
+            order_link_id: None,
+            order_filter: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        if self.symbol.is_empty() {
+            return invalid("symbol is required");
+        }
+        if self.order_id.is_none() && self.order_link_id.is_none() {
+            return invalid("either order_id or order_link_id is required");
+        }
+        if self.category != Category::Spot && self.order_filter.is_some() {
+            return invalid("order_filter applies to spot only");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(CancelOrderRequest {
+    /// Order ID.
+    order_id: String,
+    /// User customised order ID.
+    order_link_id: String,
+    /// `Order`, `tpslOrder`. Apply to spot only.
+    order_filter: String,
+});
+
+/// Parameters to cancel all open orders.
+///
+/// Linear and inverse require one of `symbol`, `base_coin` and `settle_coin`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelAllRequest {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    settle_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_order_type: Option<StopOrderType>,
+}
+
+impl CancelAllRequest {
+    pub fn new(category: Category) -> Self {
+        Self {
+            category,
+            symbol: None,
+            base_coin: None,
+            settle_coin: None,
+            order_filter: None,
+            stop_order_type: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        if is_future(&self.category)
+            && self.symbol.is_none()
+            && self.base_coin.is_none()
+            && self.settle_coin.is_none()
+        {
+            return invalid("one of symbol, base_coin and settle_coin is required");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(CancelAllRequest {
+    /// Symbol name.
+    symbol: String,
+    /// Base coin.
+    base_coin: String,
+    /// Settle coin.
+    settle_coin: String,
+    /// `Order`, `tpslOrder`, `StopOrder`.
+    order_filter: String,
+    /// `Stop` cancels conditional orders only. Apply to linear and inverse only.
+    stop_order_type: StopOrderType,
+});
+
+/// Parameters to query open orders.
+///
+/// Linear and inverse require one of `symbol`, `base_coin` and `settle_coin`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RealtimeOrdersRequest {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    settle_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_link_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open_only: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl RealtimeOrdersRequest {
+    pub fn new(category: Category) -> Self {
+        Self {
+            category,
+            symbol: None,
+            base_coin: None,
+            settle_coin: None,
+            order_id: None,
+            order_link_id: None,
+            open_only: None,
+            order_filter: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+
+    /// Check the required fields of the category.
+    pub fn validate(&self) -> Result<()> {
+        if is_future(&self.category)
+            && self.symbol.is_none()
+            && self.base_coin.is_none()
+            && self.settle_coin.is_none()
+        {
+            return invalid("one of symbol, base_coin and settle_coin is required");
+        }
+        Ok(())
+    }
+}
+
+optional_fields!(RealtimeOrdersRequest {
+    /// Symbol name.
+    symbol: String,
+    /// Base coin.
+    base_coin: String,
+    /// Settle coin.
+    settle_coin: String,
+    /// Order ID.
+    order_id: String,
+    /// User customised order ID.
+    order_link_id: String,
+    /// `0`: open orders only, `1`: recently closed orders as well. Default: `0`.
+    open_only: u8,
+    /// `Order`, `StopOrder`, `tpslOrder`.
+    order_filter: String,
+    /// Limit for data size per page. `[1, 50]`. Default: `20`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Parameters to query order history.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderHistoryRequest {
+    category: Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_coin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_link_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_status: Option<OrderStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cursor: Option<String>,
+}
+
+impl OrderHistoryRequest {
+    pub fn new(category: Category) -> Self {
+        Self {
+            category,
+            symbol: None,
+            base_coin: None,
+            order_id: None,
+            order_link_id: None,
+            order_filter: None,
+            order_status: None,
+            start_time: None,
+            end_time: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+}
+
+optional_fields!(OrderHistoryRequest {
+    /// Symbol name.
+    symbol: String,
+    /// Base coin.
+    base_coin: String,
+    /// Order ID.
+    order_id: String,
+    /// User customised order ID.
+    order_link_id: String,
+    /// `Order`, `StopOrder`, `tpslOrder`.
+    order_filter: String,
+    /// Order status.
+    order_status: OrderStatus,
+    /// The start timestamp (ms).
+    start_time: u64,
+    /// The end timestamp (ms).
+    end_time: u64,
+    /// Limit for data size per page. `[1, 50]`. Default: `20`.
+    limit: u32,
+    /// The `next_page_cursor` of the previous page.
+    cursor: String,
+});
+
+/// Check the size of a batch and the category of its orders, and build the request body.
+///
+/// The body is `{"category": ..., "request": [...]}` where the orders do not have the category.
+fn batch_body<R: Serialize>(
+    requests: &[R],
+    category_of: impl Fn(&R) -> &Category,
+) -> Result<Value> {
+    let category = match requests.first() {
+        Some(request) => category_of(request).clone(),
+        None => return invalid("batch is empty"),
+    };
+    let max = match category {
+        Category::Spot => MAX_SPOT_BATCH_SIZE,
+        _ => MAX_BATCH_SIZE,
+    };
+    if requests.len() > max {
+        return invalid(format!(
+            "at most {} orders are allowed in a {} batch",
+            max, category
+        ));
+    }
+    let mut items = Vec::with_capacity(requests.len());
+    for request in requests {
+        if category_of(request) != &category {
+            return invalid("orders in a batch must be of the same category");
+        }
+        let mut item = serde_json::to_value(request)?;
+        if let Value::Object(map) = &mut item {
+            map.remove("category");
+        }
+        items.push(item);
+    }
+    Ok(serde_json::json!({ "category": category, "request": items }))
+}
+
+#[derive(Deserialize)]
+struct Items<T> {
+    list: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchEnvelope<'a> {
+    #[serde(borrow)]
+    result: Items<BatchOrder<'a>>,
+    #[serde(borrow)]
+    ret_ext_info: Items<BatchStatus<'a>>,
+}
+
+/// Parse a batch response, merging the code and message of each order in `retExtInfo`.
+fn parse_batch_body(body: &str) -> Result<Vec<BatchOrder<'static>>> {
+    check_ret_code(body)?;
+    let envelope: BatchEnvelope = serde_json::from_str(body)?;
+    let mut statuses = envelope.ret_ext_info.list.into_iter();
+    let orders = envelope
+        .result
+        .list
+        .into_iter()
+        .map(|mut order| {
+            if let Some(status) = statuses.next() {
+                order.code = status.code;
+                order.msg = status.msg;
+            }
+            order.into_owned()
+        })
+        .collect();
+    Ok(orders)
+}
+
+/// Fill the category of orders, which REST responses do not have.
+fn fill_category(
+    mut res: List<'static, Order<'static>>,
+    category: &Category,
+) -> List<'static, Order<'static>> {
+    for order in &mut res.list {
+        if matches!(order.category, Category::Unknown(_)) {
+            order.category = category.clone();
+        }
+    }
+    res
+}
+
+impl RestApiClient {
+    /// Place an order.
+    pub fn place_order(&self, req: &PlaceOrderRequest) -> Result<OrderId<'static>> {
+        req.validate()?;
+        self.post_signed("/v5/order/create", req)
+    }
+
+    /// Amend an open order.
+    pub fn amend_order(&self, req: &AmendOrderRequest) -> Result<OrderId<'static>> {
+        req.validate()?;
+        self.post_signed("/v5/order/amend", req)
+    }
+
+    /// Cancel an open order.
+    pub fn cancel_order(&self, req: &CancelOrderRequest) -> Result<OrderId<'static>> {
+        req.validate()?;
+        self.post_signed("/v5/order/cancel", req)
+    }
+
+    /// Cancel all open orders. The IDs of the cancelled orders are returned.
+    pub fn cancel_all_orders(
+        &self,
+        req: &CancelAllRequest,
+    ) -> Result<List<'static, OrderId<'static>>> {
+        req.validate()?;
+        self.post_signed("/v5/order/cancel-all", req)
+    }
+
+    /// Place orders of the same category in a batch.
+    ///
+    /// A batch succeeds as a whole even if some orders fail. Check `BatchOrder::is_ok` of each order.
+    pub fn place_batch_orders(
+        &self,
+        reqs: &[PlaceOrderRequest],
+    ) -> Result<Vec<BatchOrder<'static>>> {
+        reqs.iter().try_for_each(PlaceOrderRequest::validate)?;
+        let body = batch_body(reqs, |req| &req.category)?;
+        self.send_batch("/v5/order/create-batch", &body)
+    }
+
+    /// Amend orders of the same category in a batch.
+    ///
+    /// A batch succeeds as a whole even if some orders fail. Check `BatchOrder::is_ok` of each order.
+    pub fn amend_batch_orders(
+        &self,
+        reqs: &[AmendOrderRequest],
+    ) -> Result<Vec<BatchOrder<'static>>> {
+        reqs.iter().try_for_each(AmendOrderRequest::validate)?;
+        let body = batch_body(reqs, |req| &req.category)?;
+        self.send_batch("/v5/order/amend-batch", &body)
+    }
+
+    /// Cancel orders of the same category in a batch.
+    ///
+    /// A batch succeeds as a whole even if some orders fail. Check `BatchOrder::is_ok` of each order.
+    pub fn cancel_batch_orders(
+        &self,
+        reqs: &[CancelOrderRequest],
+    ) -> Result<Vec<BatchOrder<'static>>> {
+        reqs.iter().try_for_each(CancelOrderRequest::validate)?;
+        let body = batch_body(reqs, |req| &req.category)?;
+        self.send_batch("/v5/order/cancel-batch", &body)
+    }
+
+    /// Query open orders, and recently closed orders if `open_only` is `1`.
+    pub fn realtime_orders(
+        &self,
+        req: &RealtimeOrdersRequest,
+    ) -> Result<List<'static, Order<'static>>> {
+        req.validate()?;
+        let res = self.get_signed("/v5/order/realtime", req)?;
+        Ok(fill_category(res, &req.category))
+    }
+
+    /// Query order history.
+    pub fn order_history(
+        &self,
+        req: &OrderHistoryRequest,
+    ) -> Result<List<'static, Order<'static>>> {
+        let res = self.get_signed("/v5/order/history", req)?;
+        Ok(fill_category(res, &req.category))
+    }
+
+    fn send_batch(&self, path: &str, body: &Value) -> Result<Vec<BatchOrder<'static>>> {
+        let body = self.send("POST", path, "", Some(&body.to_string()), true)?;
+        parse_batch_body(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::fixtures;
+    use crate::rest::parse_body;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate() {
+        let req = PlaceOrderRequest::new(
+            Category::Linear,
+            "BTCUSDT",
+            Side::Buy,
+            OrderType::Limit,
+            "0.01",
+        );
+        assert!(req.validate().is_err());
+        let req = req.price("20000");
+        assert!(req.validate().is_ok());
+        assert!(req.clone().is_leverage(1).validate().is_err());
+        assert!(req.clone().trigger_price("21000").validate().is_err());
+        assert!(req
+            .trigger_price("21000")
+            .trigger_direction(1)
+            .validate()
+            .is_ok());
+
+        let req = PlaceOrderRequest::new(
+            Category::Option,
+            "BTC-30DEC22-18000-C",
+            Side::Buy,
+            OrderType::Market,
+            "1",
+        );
+        assert!(req.validate().is_err());
+        assert!(req.order_link_id("option-001").validate().is_ok());
+
+        assert!(CancelOrderRequest::new(Category::Spot, "BTCUSDT")
+            .validate()
+            .is_err());
+        assert!(CancelAllRequest::new(Category::Linear).validate().is_err());
+        assert!(CancelAllRequest::new(Category::Linear)
+            .settle_coin("USDT")
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_batch() {
+        let reqs: Vec<_> = (0..2)
+            .map(|i| {
+                CancelOrderRequest::new(Category::Option, "BTC-30DEC22-18000-C")
+                    .order_link_id(format!("option-{}", i))
+            })
+            .collect();
+        let body = batch_body(&reqs, |req| &req.category).unwrap();
+        assert_eq!(body["category"], "option");
+        assert_eq!(body["request"][1]["orderLinkId"], "option-1");
+        assert!(body["request"][0].get("category").is_none());
+
+        let mixed = vec![
+            CancelOrderRequest::new(Category::Option, "BTC-30DEC22-18000-C").order_id("1"),
+            CancelOrderRequest::new(Category::Linear, "BTCUSDT").order_id("2"),
+        ];
+        assert!(batch_body(&mixed, |req| &req.category).is_err());
+
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"category":"option","symbol":"BTC-30DEC22-18000-C","orderId":"b0d3","orderLinkId":"option-0"},{"category":"option","symbol":"BTC-30DEC22-18000-C","orderId":"","orderLinkId":"option-1"}]},"retExtInfo":{"list":[{"code":0,"msg":"OK"},{"code":110001,"msg":"Order does not exist"}]},"time":1672222808495}"#;
+        let orders = parse_batch_body(body).unwrap();
+        assert!(orders[0].is_ok());
+        assert!(!orders[1].is_ok());
+        assert_eq!(orders[1].msg, "Order does not exist");
+    }
+
+    #[test]
+    fn test_fill_category() {
+        // Orders of the REST APIs carry the category in the list instead.
+        let order = fixtures::order(json!({ "category": null, "orderStatus": "New" }));
+        let body = format!(
+            r#"{{"retCode":0,"retMsg":"OK","result":{{"list":[{}],"nextPageCursor":"","category":"linear"}},"retExtInfo":{{}},"time":1684765770483}}"#,
+            order
+        );
+        let res: List<Order> = parse_body(&body).unwrap();
+        let res = fill_category(res, &Category::Linear);
+        assert_eq!(res.list[0].category, Category::Linear);
+        assert_eq!(res.list[0].order_status, OrderStatus::New);
+    }
+}
@@ -1,5 +1,6 @@
 use hex;
 use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
 use std::time::SystemTime;
 
 pub fn millis() -> u128 {
@@ -15,6 +16,25 @@ pub fn sign(secret: &str, msg: &str) -> String {
     hex::encode(tag.as_ref())
 }
 
+/// Generate a random (version 4) UUID, e.g., `4d9a7f1c-2b3e-4f5a-8c6d-7e8f9a0b1c2d`.
+pub fn uuid() -> String {
+    let mut bytes = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("failed to generate random bytes");
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -24,6 +44,14 @@ mod tests {
         assert!(millis() > 0);
     }
 
+    #[test]
+    fn test_uuid() {
+        let id = uuid();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert_ne!(id, uuid());
+    }
+
     #[test]
     fn test_sign() {
         assert_eq!(
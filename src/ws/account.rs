@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use super::num::{optional_value, value, Value};
+use super::response::{PrivateResponse, Wallet, WalletCoin};
+use crate::enums::AccountType;
+use crate::error::Result;
+
+/// The balances of an account type.
+///
+/// Values are in USD. They are `None` if Bybit sends an empty string, e.g., in non-unified mode.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountBalance {
+    /// Initial margin rate, e.g., `0.5` for 50%.
+    pub im_rate: Option<Value>,
+    /// Maintenance margin rate, e.g., `0.8` for 80%.
+    pub mm_rate: Option<Value>,
+    pub total_equity: Option<Value>,
+    pub total_wallet_balance: Option<Value>,
+    pub total_margin_balance: Option<Value>,
+    pub total_available_balance: Option<Value>,
+    pub total_perp_upl: Option<Value>,
+    pub total_initial_margin: Option<Value>,
+    pub total_maintenance_margin: Option<Value>,
+    coins: HashMap<String, CoinBalance>,
+}
+
+impl AccountBalance {
+    /// Get the balance of a coin, e.g., `USDT`.
+    pub fn coin(&self, coin: &str) -> Option<&CoinBalance> {
+        self.coins.get(coin)
+    }
+
+    /// Iterate over the balances of all coins.
+    pub fn coins(&self) -> impl Iterator<Item = (&str, &CoinBalance)> {
+        self.coins
+            .iter()
+            .map(|(coin, balance)| (coin.as_str(), balance))
+    }
+
+    fn update(&mut self, wallet: &Wallet) -> Result<()> {
+        self.im_rate = optional_value(&wallet.account_im_rate)?;
+        self.mm_rate = optional_value(&wallet.account_mm_rate)?;
+        self.total_equity = optional_value(&wallet.total_equity)?;
+        self.total_wallet_balance = optional_value(&wallet.total_wallet_balance)?;
+        self.total_margin_balance = optional_value(&wallet.total_margin_balance)?;
+        self.total_available_balance = optional_value(&wallet.total_available_balance)?;
+        self.total_perp_upl = optional_value(&wallet.total_perp_upl)?;
+        self.total_initial_margin = optional_value(&wallet.total_initial_margin)?;
+        self.total_maintenance_margin = optional_value(&wallet.total_maintenance_margin)?;
+        for coin in &wallet.coin {
+            self.coins
+                .insert(coin.coin.to_string(), CoinBalance::new(coin)?);
+        }
+        Ok(())
+    }
+}
+
+/// The balance of a coin in an account type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoinBalance {
+    pub equity: Value,
+    pub wallet_balance: Value,
+    pub usd_value: Option<Value>,
+    pub borrow_amount: Option<Value>,
+    pub available_to_borrow: Option<Value>,
+    pub available_to_withdraw: Option<Value>,
+    pub accrued_interest: Option<Value>,
+    pub unrealised_pnl: Option<Value>,
+}
+
+impl CoinBalance {
+    fn new(coin: &WalletCoin) -> Result<Self> {
+        Ok(Self {
+            equity: value(&coin.equity)?,
+            wallet_balance: value(&coin.wallet_balance)?,
+            usd_value: optional_value(&coin.usd_value)?,
+            borrow_amount: optional_value(&coin.borrow_amount)?,
+            available_to_borrow: optional_value(&coin.available_to_borrow)?,
+            available_to_withdraw: optional_value(&coin.available_to_withdraw)?,
+            accrued_interest: optional_value(&coin.accrued_interest)?,
+            unrealised_pnl: optional_value(&coin.unrealised_pnl)?,
+        })
+    }
+}
+
+/// A value of an account type which an alert watches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Initial margin rate.
+    ImRate,
+    /// Maintenance margin rate.
+    MmRate,
+    /// Total equity in USD.
+    TotalEquity,
+    /// Total available balance in USD.
+    TotalAvailableBalance,
+    /// Equity of a coin.
+    CoinEquity(String),
+    /// Borrowed amount of a coin.
+    CoinBorrow(String),
+}
+
+impl Metric {
+    fn get(&self, balance: &AccountBalance) -> Option<Value> {
+        match self {
+            Metric::ImRate => balance.im_rate,
+            Metric::MmRate => balance.mm_rate,
+            Metric::TotalEquity => balance.total_equity,
+            Metric::TotalAvailableBalance => balance.total_available_balance,
+            Metric::CoinEquity(coin) => balance.coin(coin).map(|c| c.equity),
+            Metric::CoinBorrow(coin) => balance.coin(coin).and_then(|c| c.borrow_amount),
+        }
+    }
+}
+
+/// The condition of an alert.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    /// The value is greater than the limit.
+    Above(Value),
+    /// The value is less than the limit.
+    Below(Value),
+}
+
+impl Threshold {
+    fn is_crossed(&self, value: Value) -> bool {
+        match *self {
+            Threshold::Above(limit) => value > limit,
+            Threshold::Below(limit) => value < limit,
+        }
+    }
+}
+
+/// The identifier of a registered alert, used to remove it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AlertId(u64);
+
+/// An alert passed to its callback when the threshold is crossed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub id: AlertId,
+    pub account_type: AccountType,
+    pub metric: Metric,
+    pub threshold: Threshold,
+    /// The value which crossed the threshold.
+    pub value: Value,
+}
+
+type AlertHandler = Box<dyn FnMut(&Alert) + Send>;
+
+struct Watch {
+    id: AlertId,
+    account_type: AccountType,
+    metric: Metric,
+    threshold: Threshold,
+    crossed: bool,
+    handler: AlertHandler,
+}
+
+/// A cache of wallet balances from the wallet topic of the private channel,
+/// with threshold alerts on them.
+///
+/// Balances are kept per account type, and coins are updated as they appear in wallet messages.
+/// An alert calls its callback once when its threshold is crossed, and is armed again when the
+/// value goes back, so a de-risking action is not repeated on every update.
+#[derive(Default)]
+pub struct AccountState {
+    balances: HashMap<AccountType, AccountBalance>,
+    watches: Vec<Watch>,
+    next_id: u64,
+}
+
+impl AccountState {
+    /// Create an empty `AccountState`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a response of the private channel. Responses other than wallets are ignored.
+    pub fn apply(&mut self, res: &PrivateResponse) -> Result<()> {
+        if let PrivateResponse::Wallet(res) = res {
+            for wallet in &res.data {
+                self.apply_wallet(wallet)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply a wallet, e.g., from a wallet message or the wallet balance REST API,
+    /// and call the callbacks of the alerts it crosses.
+    pub fn apply_wallet(&mut self, wallet: &Wallet) -> Result<()> {
+        let balance = self
+            .balances
+            .entry(wallet.account_type.clone())
+            .or_default();
+        balance.update(wallet)?;
+        for watch in &mut self.watches {
+            if watch.account_type != wallet.account_type {
+                continue;
+            }
+            let Some(value) = watch.metric.get(balance) else {
+                continue;
+            };
+            let crossed = watch.threshold.is_crossed(value);
+            if crossed && !watch.crossed {
+                (watch.handler)(&Alert {
+                    id: watch.id,
+                    account_type: watch.account_type.clone(),
+                    metric: watch.metric.clone(),
+                    threshold: watch.threshold,
+                    value,
+                });
+            }
+            watch.crossed = crossed;
+        }
+        Ok(())
+    }
+
+    /// Register an alert on a metric of an account type.
+    pub fn on_alert<F>(
+        &mut self,
+        account_type: AccountType,
+        metric: Metric,
+        threshold: Threshold,
+        handler: F,
+    ) -> AlertId
+    where
+        F: FnMut(&Alert) + Send + 'static,
+    {
+        let id = AlertId(self.next_id);
+        self.next_id += 1;
+        self.watches.push(Watch {
+            id,
+            account_type,
+            metric,
+            threshold,
+            crossed: false,
+            handler: Box::new(handler),
+        });
+        id
+    }
+
+    /// Remove an alert. Returns `false` if it does not exist.
+    pub fn remove_alert(&mut self, id: AlertId) -> bool {
+        let len = self.watches.len();
+        self.watches.retain(|watch| watch.id != id);
+        self.watches.len() != len
+    }
+
+    /// Get the balances of an account type.
+    pub fn balance(&self, account_type: &AccountType) -> Option<&AccountBalance> {
+        self.balances.get(account_type)
+    }
+
+    /// Get the total equity of an account type in USD.
+    pub fn equity(&self, account_type: &AccountType) -> Option<Value> {
+        self.balance(account_type).and_then(|b| b.total_equity)
+    }
+
+    /// Get the total available balance of an account type in USD.
+    pub fn available_balance(&self, account_type: &AccountType) -> Option<Value> {
+        self.balance(account_type)
+            .and_then(|b| b.total_available_balance)
+    }
+
+    /// Get the balance of a coin in an account type.
+    pub fn coin(&self, account_type: &AccountType, coin: &str) -> Option<&CoinBalance> {
+        self.balance(account_type).and_then(|b| b.coin(coin))
+    }
+
+    /// Forget all balances. Alerts are kept and armed again.
+    pub fn reset(&mut self) {
+        self.balances.clear();
+        for watch in &mut self.watches {
+            watch.crossed = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn wallet(mm_rate: &str, equity: &str) -> String {
+        format!(
+            r#"{{"id":"592324d2bce751-ad38-48eb-8f42-4671d1fb4d4e","topic":"wallet","creationTime":1672364262482,"data":[{{"accountIMRate":"0.016","accountMMRate":"{}","totalEquity":"{}","totalWalletBalance":"10000","totalMarginBalance":"10000","totalAvailableBalance":"9000","totalPerpUPL":"0","totalInitialMargin":"1000","totalMaintenanceMargin":"500","coin":[{{"coin":"USDT","equity":"{}","usdValue":"{}","walletBalance":"10000","borrowAmount":"","availableToBorrow":"","availableToWithdraw":"9000","accruedInterest":"","totalOrderIM":"0","totalPositionIM":"1000","totalPositionMM":"500","unrealisedPnl":"0","cumRealisedPnl":"0"}}],"accountType":"UNIFIED"}}]}}"#,
+            mm_rate, equity, equity, equity
+        )
+    }
+
+    fn apply(state: &mut AccountState, text: &str) {
+        state.apply(&serde_json::from_str(text).unwrap()).unwrap();
+    }
+
+    fn v(s: &str) -> Value {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_account_state() {
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let mut state = AccountState::new();
+        let sink = alerts.clone();
+        let id = state.on_alert(
+            AccountType::Unified,
+            Metric::MmRate,
+            Threshold::Above(v("0.8")),
+            move |alert| sink.lock().unwrap().push(alert.value),
+        );
+        let sink = alerts.clone();
+        state.on_alert(
+            AccountType::Contract,
+            Metric::MmRate,
+            Threshold::Above(v("0.8")),
+            move |alert| sink.lock().unwrap().push(alert.value),
+        );
+
+        apply(&mut state, &wallet("0.5", "10000"));
+        assert_eq!(state.equity(&AccountType::Unified), Some(v("10000")));
+        assert_eq!(
+            state.available_balance(&AccountType::Unified),
+            Some(v("9000"))
+        );
+        let usdt = state.coin(&AccountType::Unified, "USDT").unwrap();
+        assert_eq!(usdt.available_to_withdraw, Some(v("9000")));
+        assert_eq!(usdt.borrow_amount, None);
+        assert!(state.equity(&AccountType::Contract).is_none());
+        assert!(alerts.lock().unwrap().is_empty());
+
+        // The alert is called once while the threshold stays crossed.
+        apply(&mut state, &wallet("0.85", "9000"));
+        apply(&mut state, &wallet("0.9", "8000"));
+        assert_eq!(*alerts.lock().unwrap(), vec![v("0.85")]);
+
+        // It is armed again when the value goes back.
+        apply(&mut state, &wallet("0.7", "9000"));
+        apply(&mut state, &wallet("0.81", "8500"));
+        assert_eq!(*alerts.lock().unwrap(), vec![v("0.85"), v("0.81")]);
+
+        assert!(state.remove_alert(id));
+        assert!(!state.remove_alert(id));
+        apply(&mut state, &wallet("0.7", "9000"));
+        apply(&mut state, &wallet("0.95", "7000"));
+        assert_eq!(alerts.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_coin_alert() {
+        let alerts = Arc::new(Mutex::new(0));
+        let mut state = AccountState::new();
+        let sink = alerts.clone();
+        state.on_alert(
+            AccountType::Unified,
+            Metric::CoinEquity("USDT".to_string()),
+            Threshold::Below(v("5000")),
+            move |_| *sink.lock().unwrap() += 1,
+        );
+        apply(&mut state, &wallet("0.5", "4000"));
+        assert_eq!(*alerts.lock().unwrap(), 1);
+
+        // Alerts are armed again after a reset.
+        state.reset();
+        assert!(state.balance(&AccountType::Unified).is_none());
+        apply(&mut state, &wallet("0.5", "4000"));
+        assert_eq!(*alerts.lock().unwrap(), 2);
+    }
+}
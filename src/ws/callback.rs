@@ -1,10 +1,30 @@
 use serde::Deserialize;
 
+/// The kind of responses of a channel, e.g., `SpotPublicResponseArg` for `SpotPublicResponse`.
+///
+/// `parse` reads the topic of a frame first and deserializes the matching response directly,
+/// which is how clients parse frames.
 pub trait Arg {
     type ValueType<'a>: Deserialize<'a>
     where
         Self: 'a;
+
+    /// Parse a frame with `serde_json`.
+    fn parse(frame: &str) -> serde_json::Result<Self::ValueType<'_>> {
+        serde_json::from_str(frame)
+    }
+
+    /// Parse a frame with simd-json, which rewrites the frame in place.
+    /// `buffers` are reused across frames to save allocations.
+    #[cfg(feature = "simd-json")]
+    fn parse_mut<'a>(
+        frame: &'a mut [u8],
+        buffers: &mut simd_json::Buffers,
+    ) -> simd_json::Result<Self::ValueType<'a>> {
+        simd_json::serde::from_slice_with_buffers(frame, buffers)
+    }
 }
 
+/// A callback of `run`, which is called with each response borrowing from the received frame.
 pub trait Callback<A: Arg>: for<'any> FnMut(A::ValueType<'any>) {}
 impl<A: Arg, F: for<'any> FnMut(A::ValueType<'any>)> Callback<A> for F {}
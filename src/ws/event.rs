@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// The connection events of a websocket api client.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The connection is lost. Data received from now on may have gaps, so local state
+    /// such as order books should be invalidated.
+    Disconnected {
+        /// Why the connection is lost.
+        reason: String,
+    },
+    /// The client is going to reconnect after `delay`.
+    Reconnecting {
+        /// The attempt number, starting from 1.
+        attempt: u32,
+        /// The delay before this attempt.
+        delay: Duration,
+    },
+    /// The connection is reestablished, authenticated (for private channels)
+    /// and all topics are resubscribed.
+    Reconnected {
+        /// The number of attempts it took.
+        attempt: u32,
+    },
+    /// A request is rejected by the server, e.g., subscribing to a topic with a misspelled symbol.
+    ///
+    /// Rejected topics are still tracked by the client. Unsubscribe them with the handle if they're not wanted.
+    Rejected {
+        /// The op of the request, e.g., `subscribe` or `order.create`.
+        op: String,
+        /// The args of the request, e.g., the topics. For option channels,
+        /// only the failed topics are reported. For order requests, it is the `req_id`.
+        args: Vec<String>,
+        /// The message returned by the server.
+        reason: String,
+    },
+    /// A topic is resubscribed to get a fresh snapshot, e.g., the local order book is out of sync.
+    ///
+    /// Local state built from the topic is invalid until the snapshot is received.
+    Resync {
+        /// The resubscribed topic.
+        topic: String,
+        /// Why the topic is resubscribed.
+        reason: String,
+    },
+    /// A received frame does not match any response, e.g., a new topic or a changed schema.
+    ///
+    /// The frame is dropped and counted in the client's [`ParseFailures`].
+    ParseFailed {
+        /// The topic of the frame, or its op if it does not have a topic, e.g., `pong`.
+        /// It is empty if the frame has neither.
+        topic: String,
+        /// The deserialization error.
+        error: String,
+        /// The raw text of the frame.
+        text: String,
+    },
+}
+
+pub(crate) type EventHandler = Arc<dyn Fn(Event) + Send + Sync>;
+
+/// The number of received frames which failed to parse, per topic.
+///
+/// Frames without a topic, such as op replies, are counted under their op, and frames
+/// with neither under an empty string. Clones share the same counts, so they can be
+/// read from other threads while the client is running.
+#[derive(Debug, Clone, Default)]
+pub struct ParseFailures {
+    counts: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl ParseFailures {
+    /// The number of failures of a topic.
+    pub fn get(&self, topic: &str) -> u64 {
+        self.lock().get(topic).copied().unwrap_or_default()
+    }
+
+    /// The number of failures of all topics.
+    pub fn total(&self) -> u64 {
+        self.lock().values().sum()
+    }
+
+    /// The numbers of failures of each topic.
+    pub fn counts(&self) -> HashMap<String, u64> {
+        self.lock().clone()
+    }
+
+    pub(crate) fn add(&self, topic: &str) {
+        *self.lock().entry(topic.to_string()).or_default() += 1;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, u64>> {
+        self.counts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
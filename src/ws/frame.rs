@@ -0,0 +1,143 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TryRecvError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::callback::Arg;
+use super::{Handle, Options};
+use crate::error::Result;
+
+/// A text frame received from the websocket server.
+///
+/// Responses borrow strings from the frame to avoid copying,
+/// so the frame owns the text and parses it on demand with [`Frame::parse`].
+pub struct Frame<A> {
+    text: String,
+    options: Arc<Options>,
+    /// Whether a parse failure has been counted, so it is counted once however often it is parsed.
+    failed: AtomicBool,
+    arg: PhantomData<fn() -> A>,
+}
+
+impl<A: Arg> Frame<A> {
+    #[cfg(feature = "async")]
+    pub(super) fn new(text: String, options: &Arc<Options>) -> Self {
+        Self {
+            text,
+            options: options.clone(),
+            failed: AtomicBool::new(false),
+            arg: PhantomData,
+        }
+    }
+
+    /// Parse the frame into a response, e.g., `SpotPublicResponse` for frames of spot channels.
+    ///
+    /// A failure is also counted in the client's `parse_failures()` and notified as
+    /// [`super::event::Event::ParseFailed`] the first time, as it is by `run`.
+    pub fn parse(&self) -> Result<A::ValueType<'_>> {
+        A::parse(&self.text).map_err(|e| {
+            if !self.failed.swap(true, Ordering::Relaxed) {
+                self.options.parse_failed(&self.text, &e);
+            }
+            e.into()
+        })
+    }
+
+    /// The raw text of the frame.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Consume the frame and return the raw text.
+    pub fn into_text(self) -> String {
+        self.text
+    }
+}
+
+impl<A> fmt::Debug for Frame<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Frame").field(&self.text).finish()
+    }
+}
+
+/// The responses received by a websocket api client running on its own thread.
+///
+/// Each frame is parsed on the client thread and delivered as an owned response,
+/// e.g., [`super::response::OwnedSpotPublicResponse`], so it can be kept or sent on freely.
+/// Frames which fail to parse are counted and notified as they are by `run`.
+///
+/// Responses are delivered over a bounded channel, so the client thread blocks
+/// instead of buffering without limit when the consumer is slow.
+/// As an `Iterator`, it ends when the client stops.
+/// Dropping it shuts the client down without waiting for its thread.
+pub struct Responses<R> {
+    rx: Receiver<R>,
+    handle: Handle,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl<R: Send + 'static> Responses<R> {
+    pub(crate) fn spawn<F>(handle: Handle, capacity: usize, run: F) -> Self
+    where
+        F: FnOnce(SyncSender<R>) -> Result<()> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let thread = thread::spawn(move || run(tx));
+        Self {
+            rx,
+            handle,
+            thread: Some(thread),
+        }
+    }
+
+    /// Wait for the next response. Returns `None` if the client has stopped.
+    pub fn recv(&self) -> Option<R> {
+        self.rx.recv().ok()
+    }
+
+    /// Return the next response if there is one, without blocking.
+    pub fn try_recv(&self) -> Result<R, TryRecvError> {
+        self.rx.try_recv()
+    }
+
+    /// Wait for the next response for at most `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<R, RecvTimeoutError> {
+        self.rx.recv_timeout(timeout)
+    }
+
+    /// The handle of the client, e.g., to subscribe to more topics.
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// Shut down the client, wait for its thread to finish and return the result of the client.
+    pub fn shutdown(mut self) -> Result<()> {
+        self.handle.shutdown();
+        // Dropping the receiver unblocks the client if it waits for room in the channel.
+        self.rx = mpsc::sync_channel(0).1;
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(res)) => res,
+            Some(Err(e)) => std::panic::resume_unwind(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R> Drop for Responses<R> {
+    fn drop(&mut self) {
+        if self.thread.is_some() {
+            self.handle.shutdown();
+        }
+    }
+}
+
+impl<R: Send + 'static> Iterator for Responses<R> {
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
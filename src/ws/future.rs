@@ -1,9 +1,18 @@
 use super::callback::Callback;
-use super::response::FuturePublicResponseArg;
-use super::run;
-use super::Subscriber;
+use super::event::{Event, ParseFailures};
+use super::frame::Responses;
+use super::reconnect::ReconnectPolicy;
+use super::record::Recorder;
+use super::response::{
+    FuturePublicResponse, FuturePublicResponseArg, IntoOwned, OwnedFuturePublicResponse,
+};
+#[cfg(feature = "async")]
+use super::stream::{connect_frames, connect_stream, FrameStream, ResponseStream};
+use super::{callback_sink, parse_sink, run};
+use super::{Control, Handle, Options, Subscriber};
 use crate::error::Result;
 use crate::{FutureRole, KlineInterval};
+use std::sync::Arc;
 
 const MAINNET_LINEAR: &str = "wss://stream.bybit.com/v5/public/linear";
 const MAINNET_INVERSE: &str = "wss://stream.bybit.com/v5/public/inverse";
@@ -32,6 +41,8 @@ impl From<OrderbookDepth> for u16 {
 pub struct FutureWebsocketApiClient {
     uri: String,
     subscriber: Subscriber,
+    options: Options,
+    control: Control,
 }
 
 impl FutureWebsocketApiClient {
@@ -55,13 +66,81 @@ impl FutureWebsocketApiClient {
         self.subscriber.sub_liquidation(symbol.as_ref());
     }
 
+    /// Get a handle to control the client from other threads, e.g., to shut it down.
+    pub fn handle(&self) -> Handle {
+        self.control.handle()
+    }
+
+    /// Get the counts of received frames which failed to parse, shared with the running client.
+    pub fn parse_failures(&self) -> ParseFailures {
+        self.options.parse_failures.clone()
+    }
+
     pub fn run<C: Callback<FuturePublicResponseArg>>(&self, callback: C) -> Result<()> {
-        run(&self.uri, self.subscriber.topics(), None, callback)
+        run(
+            &self.uri,
+            &self.subscriber,
+            None,
+            &self.options,
+            &self.control,
+            callback_sink(callback, &self.options),
+        )
+    }
+
+    /// Run the client on a new thread and receive responses over a bounded channel with `capacity`.
+    ///
+    /// Unlike `run`, responses own their data, so they can be kept or sent to other threads.
+    pub fn spawn(self, capacity: usize) -> Responses<OwnedFuturePublicResponse> {
+        Responses::spawn(self.handle(), capacity, move |tx| {
+            run(
+                &self.uri,
+                &self.subscriber,
+                None,
+                &self.options,
+                &self.control,
+                parse_sink::<FuturePublicResponseArg, _>(
+                    |res: FuturePublicResponse| tx.send(res.into_owned()).is_ok(),
+                    &self.options,
+                ),
+            )
+        })
+    }
+
+    /// Connect and return a stream of the received responses. Requires the `async` feature.
+    ///
+    /// Pings are sent by a background task, so it must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn stream(&self) -> Result<ResponseStream<OwnedFuturePublicResponse>> {
+        connect_stream::<FuturePublicResponseArg, _>(
+            &self.uri,
+            &self.subscriber,
+            None,
+            &self.options,
+            &self.control,
+        )
+        .await
+    }
+
+    /// Connect and return a stream of the raw frames, which responses borrow from.
+    /// Requires the `async` feature.
+    ///
+    /// Pings are sent by a background task, so it must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn frames(&self) -> Result<FrameStream<FuturePublicResponseArg>> {
+        connect_frames(
+            &self.uri,
+            &self.subscriber,
+            None,
+            &self.options,
+            &self.control,
+        )
+        .await
     }
 }
 
 pub struct FutureWebSocketApiClientBuilder {
     uri: String,
+    options: Options,
     role: FutureRole,
 }
 
@@ -72,7 +151,11 @@ impl FutureWebSocketApiClientBuilder {
             FutureRole::Linear => MAINNET_LINEAR.to_string(),
             FutureRole::Inverse => MAINNET_INVERSE.to_string(),
         };
-        Self { uri, role }
+        Self {
+            uri,
+            role,
+            options: Options::default(),
+        }
     }
 
     /// Change channel URI to the testnet.
@@ -93,11 +176,36 @@ impl FutureWebSocketApiClientBuilder {
         self
     }
 
+    /// Reconnect with the given policy when the connection is lost.
+    ///
+    /// All topics are resubscribed after reconnecting.
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.options.reconnect = Some(policy);
+        self
+    }
+
+    /// Set a handler which is notified of connection events, such as disconnected and reconnected.
+    pub fn on_event<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        self.options.on_event = Some(Arc::new(handler));
+        self
+    }
+
+    /// Record every received text frame, e.g., to replay an incident later.
+    pub fn record(mut self, recorder: Recorder) -> Self {
+        self.options.recorder = Some(recorder);
+        self
+    }
+
     /// Build a future websocket api client.
     pub fn build(self) -> FutureWebsocketApiClient {
         FutureWebsocketApiClient {
             uri: self.uri,
             subscriber: Subscriber::new(),
+            options: self.options,
+            control: Control::new(),
         }
     }
 }
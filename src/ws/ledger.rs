@@ -0,0 +1,613 @@
+use log::*;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::num::{value, Value};
+use super::response::{Execution, IntoOwned, Order, Position, PrivateResponse};
+use crate::enums::{Category, ExecType, Side};
+use crate::error::Result;
+
+/// How many closed orders are remembered to map their late executions to positions.
+const CLOSED_ORDERS: usize = 1024;
+
+/// How many execution IDs are remembered to ignore redelivered executions.
+const EXECUTIONS: usize = 4096;
+
+/// How many recent orders keep their executions until the order update arrives.
+const PENDING_ORDERS: usize = 1024;
+
+/// The accounting of a position, built from executions.
+///
+/// Sizes are signed: positive for long and negative for short. PnL and fees are in the
+/// quote coin for linear and options, and in the base coin for inverse. Fees are positive
+/// when paid and negative for rebates.
+#[derive(Debug, Clone)]
+pub struct PositionLedger {
+    category: Category,
+    symbol: String,
+    position_idx: u8,
+    size: Value,
+    avg_entry: Value,
+    realised_pnl: Value,
+    maker_fee: Value,
+    taker_fee: Value,
+    funding: Value,
+    mark_price: Option<Value>,
+    baseline: Option<(Value, Value)>,
+}
+
+impl PositionLedger {
+    fn new(category: Category, symbol: &str, position_idx: u8) -> Self {
+        Self {
+            category,
+            symbol: symbol.to_string(),
+            position_idx,
+            size: Value::default(),
+            avg_entry: Value::default(),
+            realised_pnl: Value::default(),
+            maker_fee: Value::default(),
+            taker_fee: Value::default(),
+            funding: Value::default(),
+            mark_price: None,
+            baseline: None,
+        }
+    }
+
+    /// Product type.
+    pub fn category(&self) -> &Category {
+        &self.category
+    }
+
+    /// Symbol name.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Position index. `0` for one-way mode, `1` and `2` for the buy and sell side of hedge mode.
+    pub fn position_idx(&self) -> u8 {
+        self.position_idx
+    }
+
+    /// Net size. Positive for long and negative for short.
+    pub fn size(&self) -> Value {
+        self.size
+    }
+
+    /// Average entry price. It is zero if the position is empty.
+    pub fn avg_entry(&self) -> Value {
+        self.avg_entry
+    }
+
+    /// Realised PnL of closed sizes, excluding fees and funding.
+    pub fn realised_pnl(&self) -> Value {
+        self.realised_pnl
+    }
+
+    /// Trading fees of maker executions.
+    pub fn maker_fee(&self) -> Value {
+        self.maker_fee
+    }
+
+    /// Trading fees of taker executions.
+    pub fn taker_fee(&self) -> Value {
+        self.taker_fee
+    }
+
+    /// Funding fees. Positive when paid.
+    pub fn funding(&self) -> Value {
+        self.funding
+    }
+
+    /// Realised PnL after fees and funding, which is what Bybit's `cum_realised_pnl` counts.
+    pub fn net_realised_pnl(&self) -> Value {
+        self.realised_pnl - self.maker_fee - self.taker_fee - self.funding
+    }
+
+    /// The last mark price of the position messages.
+    pub fn mark_price(&self) -> Option<Value> {
+        self.mark_price
+    }
+
+    /// Unrealised PnL at `price`.
+    pub fn unrealised_pnl_at(&self, price: Value) -> Value {
+        if self.size == Value::default() {
+            return Value::default();
+        }
+        let pnl = pnl(&self.category, self.size.abs(), self.avg_entry, price);
+        if self.size > Value::default() {
+            pnl
+        } else {
+            -pnl
+        }
+    }
+
+    /// Unrealised PnL at the last mark price, or `None` if no position message is received.
+    pub fn unrealised_pnl(&self) -> Option<Value> {
+        self.mark_price.map(|price| self.unrealised_pnl_at(price))
+    }
+
+    fn fill(&mut self, side: &Side, qty: Value, price: Value) {
+        let zero = Value::default();
+        let signed = if *side == Side::Buy { qty } else { -qty };
+        if self.size == zero || (self.size > zero) == (signed > zero) {
+            // Open or increase.
+            let size = self.size.abs();
+            self.avg_entry = if self.category == Category::Inverse {
+                (size + qty) / (size / self.avg_entry_or(price) + qty / price)
+            } else {
+                (self.avg_entry * size + price * qty) / (size + qty)
+            };
+            self.size += signed;
+            return;
+        }
+        // Reduce, close or flip.
+        let closed = qty.min(self.size.abs());
+        let pnl = pnl(&self.category, closed, self.avg_entry, price);
+        self.realised_pnl += if self.size > zero { pnl } else { -pnl };
+        self.size += signed;
+        if self.size == zero {
+            self.avg_entry = zero;
+        } else if (self.size > zero) == (signed > zero) {
+            // Flipped. The rest opens at the execution price.
+            self.avg_entry = price;
+        }
+    }
+
+    fn avg_entry_or(&self, price: Value) -> Value {
+        if self.avg_entry == Value::default() {
+            price
+        } else {
+            self.avg_entry
+        }
+    }
+}
+
+/// The PnL of closing a long `qty` entered at `entry` at `exit`.
+fn pnl(category: &Category, qty: Value, entry: Value, exit: Value) -> Value {
+    if *category == Category::Inverse {
+        qty / entry - qty / exit
+    } else {
+        qty * (exit - entry)
+    }
+}
+
+/// The difference between a [`PositionLedger`] and a position message from Bybit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub symbol: String,
+    pub position_idx: u8,
+    /// The ledger's size minus Bybit's signed size.
+    pub size: Value,
+    /// The ledger's net realised PnL minus Bybit's `cum_realised_pnl`,
+    /// both counted since the first position message of the ledger.
+    pub realised_pnl: Value,
+}
+
+impl Drift {
+    /// Whether both differences are within `tolerance`.
+    pub fn is_within(&self, tolerance: Value) -> bool {
+        self.size.abs() <= tolerance && self.realised_pnl.abs() <= tolerance
+    }
+}
+
+/// Position and PnL accounting from the execution and position topics of the private channel.
+///
+/// Each position, keyed by symbol and `position_idx`, tracks its net size, average entry,
+/// realised PnL, maker and taker fees, and funding. Executions do not have `position_idx`,
+/// so it is taken from the order updates. Executions of an order not seen yet are kept
+/// until its update arrives, for up to 1024 recent orders. Executions whose order update
+/// never arrives are then dropped, logged and counted in [`Ledger::dropped_executions`].
+/// Redelivered executions are ignored by `exec_id`. Funding
+/// executions go to the one-way position if it exists, otherwise to the hedge mode side of
+/// the execution.
+///
+/// A position message seeds the ledger of a new position, and afterwards is reconciled with
+/// it, returning a [`Drift`]. Position messages of unified accounts have no category, which
+/// is then taken from the orders and executions of the symbol. A new position whose category
+/// is not known yet is skipped until one of them arrives. Since `cum_realised_pnl` counts the whole life of the position,
+/// realised PnL is compared from the first position message on.
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    positions: HashMap<(String, u8), PositionLedger>,
+    order_positions: HashMap<String, u8>,
+    closed_orders: VecDeque<String>,
+    pending: HashMap<String, Vec<Execution<'static>>>,
+    pending_orders: VecDeque<String>,
+    dropped_executions: u64,
+    categories: HashMap<String, Category>,
+    exec_ids: HashSet<String>,
+    exec_order: VecDeque<String>,
+}
+
+impl Ledger {
+    /// Create an empty `Ledger`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a response of the private channel and return the drifts of the position messages.
+    ///
+    /// Responses other than positions, orders and executions are ignored.
+    pub fn apply(&mut self, res: &PrivateResponse) -> Result<Vec<Drift>> {
+        match res {
+            PrivateResponse::Order(res) => {
+                for order in &res.data {
+                    self.apply_order(order)?;
+                }
+                Ok(Vec::new())
+            }
+            PrivateResponse::Execution(res) => {
+                for execution in &res.data {
+                    self.apply_execution(execution)?;
+                }
+                Ok(Vec::new())
+            }
+            PrivateResponse::Position(res) => res
+                .data
+                .iter()
+                .filter_map(|position| self.apply_position(position).transpose())
+                .collect(),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Remember the position of an order and apply its pending executions.
+    pub fn apply_order(&mut self, order: &Order) -> Result<()> {
+        let order_id = order.order_id.as_ref();
+        self.remember_category(&order.symbol, &order.category);
+        if !self.order_positions.contains_key(order_id) {
+            self.order_positions
+                .insert(order_id.to_string(), order.position_idx);
+        }
+        if let Some(executions) = self.pending.remove(order_id) {
+            for execution in &executions {
+                self.apply_fill(execution, order.position_idx)?;
+            }
+        }
+        if order.order_status.is_final() {
+            self.closed_orders.push_back(order_id.to_string());
+            if self.closed_orders.len() > CLOSED_ORDERS {
+                if let Some(order_id) = self.closed_orders.pop_front() {
+                    self.order_positions.remove(&order_id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply an execution, or keep it until the update of its order arrives.
+    pub fn apply_execution(&mut self, execution: &Execution) -> Result<()> {
+        if execution.category == Category::Spot || !self.insert_exec_id(&execution.exec_id) {
+            return Ok(());
+        }
+        self.remember_category(&execution.symbol, &execution.category);
+        if execution.exec_type == ExecType::Funding {
+            let one_way = (execution.symbol.to_string(), 0);
+            let position_idx = if self.positions.contains_key(&one_way) {
+                0
+            } else if execution.side == Side::Buy {
+                1
+            } else {
+                2
+            };
+            let fee = value(&execution.exec_fee)?;
+            self.entry(&execution.category, &execution.symbol, position_idx)
+                .funding += fee;
+            return Ok(());
+        }
+        match self.order_positions.get(execution.order_id.as_ref()) {
+            Some(&position_idx) => self.apply_fill(execution, position_idx),
+            None => {
+                self.keep_pending(execution);
+                Ok(())
+            }
+        }
+    }
+
+    /// Keep an execution until the update of its order arrives, dropping the executions
+    /// of the oldest order if too many orders are waiting.
+    fn keep_pending(&mut self, execution: &Execution) {
+        let order_id = execution.order_id.as_ref();
+        if !self.pending.contains_key(order_id) {
+            self.pending_orders.push_back(order_id.to_string());
+            if self.pending_orders.len() > PENDING_ORDERS {
+                let dropped = self.pending_orders.pop_front().and_then(|order_id| {
+                    let executions = self.pending.remove(&order_id)?;
+                    Some((order_id, executions.len()))
+                });
+                if let Some((order_id, count)) = dropped {
+                    warn!(
+                        "Dropped {} execution(s) of order {} without an order update",
+                        count, order_id
+                    );
+                    self.dropped_executions += count as u64;
+                }
+            }
+        }
+        self.pending
+            .entry(order_id.to_string())
+            .or_default()
+            .push(execution.clone().into_owned());
+    }
+
+    /// The number of executions dropped because the update of their order never arrived.
+    pub fn dropped_executions(&self) -> u64 {
+        self.dropped_executions
+    }
+
+    fn remember_category(&mut self, symbol: &str, category: &Category) {
+        if let Category::Unknown(_) = category {
+            return;
+        }
+        if !self.categories.contains_key(symbol) {
+            self.categories.insert(symbol.to_string(), category.clone());
+        }
+    }
+
+    /// Apply an execution to the position of `position_idx`.
+    pub fn apply_fill(&mut self, execution: &Execution, position_idx: u8) -> Result<()> {
+        let qty = value(&execution.exec_qty)?;
+        let price = value(&execution.exec_price)?;
+        let fee = value(&execution.exec_fee)?;
+        let ledger = self.entry(&execution.category, &execution.symbol, position_idx);
+        ledger.fill(&execution.side, qty, price);
+        if execution.is_maker {
+            ledger.maker_fee += fee;
+        } else {
+            ledger.taker_fee += fee;
+        }
+        Ok(())
+    }
+
+    /// Seed the ledger of a new position, or reconcile the ledger with the position.
+    ///
+    /// Returns `None` if the position is new and its category is not known yet.
+    pub fn apply_position(&mut self, position: &Position) -> Result<Option<Drift>> {
+        let size = value(&position.size)?;
+        let size = if position.side == Side::Sell {
+            -size
+        } else {
+            size
+        };
+        let cum_realised_pnl = value(&position.cum_realised_pnl)?;
+        let key = (position.symbol.to_string(), position.position_idx);
+        let ledger = match self.positions.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let category = position
+                    .category
+                    .clone()
+                    .or_else(|| self.categories.get(position.symbol.as_ref()).cloned());
+                let Some(category) = category else {
+                    warn!(
+                        "Skipped position of {} with unknown category",
+                        position.symbol
+                    );
+                    return Ok(None);
+                };
+                let mut ledger =
+                    PositionLedger::new(category, &position.symbol, position.position_idx);
+                ledger.size = size;
+                entry.insert(ledger)
+            }
+        };
+        if ledger.avg_entry == Value::default() && ledger.size == size {
+            ledger.avg_entry = value(&position.entry_price)?;
+        }
+        ledger.mark_price = Some(value(&position.mark_price)?);
+        let (bybit_base, ledger_base) = *ledger
+            .baseline
+            .get_or_insert((cum_realised_pnl, ledger.net_realised_pnl()));
+        Ok(Some(Drift {
+            symbol: ledger.symbol.clone(),
+            position_idx: ledger.position_idx,
+            size: ledger.size - size,
+            realised_pnl: (ledger.net_realised_pnl() - ledger_base)
+                - (cum_realised_pnl - bybit_base),
+        }))
+    }
+
+    /// Get the ledger of a position. `position_idx` is `0` for one-way mode.
+    pub fn get(&self, symbol: &str, position_idx: u8) -> Option<&PositionLedger> {
+        self.positions.get(&(symbol.to_string(), position_idx))
+    }
+
+    /// Iterate over the ledgers of all positions.
+    pub fn positions(&self) -> impl Iterator<Item = &PositionLedger> {
+        self.positions.values()
+    }
+
+    fn insert_exec_id(&mut self, exec_id: &str) -> bool {
+        if !self.exec_ids.insert(exec_id.to_string()) {
+            return false;
+        }
+        self.exec_order.push_back(exec_id.to_string());
+        if self.exec_order.len() > EXECUTIONS {
+            if let Some(exec_id) = self.exec_order.pop_front() {
+                self.exec_ids.remove(&exec_id);
+            }
+        }
+        true
+    }
+
+    fn entry(
+        &mut self,
+        category: &Category,
+        symbol: &str,
+        position_idx: u8,
+    ) -> &mut PositionLedger {
+        self.positions
+            .entry((symbol.to_string(), position_idx))
+            .or_insert_with(|| PositionLedger::new(category.clone(), symbol, position_idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::fixtures;
+    use serde_json::json;
+
+    fn order(order_id: &str, position_idx: u8) -> String {
+        fixtures::order(json!({ "orderId": order_id, "positionIdx": position_idx }))
+    }
+
+    fn execution(order_id: &str, exec_id: &str, side: &str, qty: &str, price: &str) -> String {
+        fixtures::execution(json!({
+            "orderId": order_id,
+            "execId": exec_id,
+            "side": side,
+            "execQty": qty,
+            "execPrice": price,
+            "execFee": "0.1",
+            "isMaker": side == "Buy",
+        }))
+    }
+
+    fn position(side: &str, size: &str, cum_realised_pnl: &str) -> String {
+        fixtures::position(json!({
+            "category": "linear",
+            "side": side,
+            "size": size,
+            "markPrice": "1650",
+            "cumRealisedPnl": cum_realised_pnl,
+        }))
+    }
+
+    fn apply(ledger: &mut Ledger, topic: &str, data: &str) -> Vec<Drift> {
+        let text = fixtures::message(topic, data);
+        ledger.apply(&serde_json::from_str(&text).unwrap()).unwrap()
+    }
+
+    fn v(s: &str) -> Value {
+        s.parse().unwrap()
+    }
+
+    fn close(a: Value, b: &str) -> bool {
+        (a - v(b)).abs() < v("0.0000001")
+    }
+
+    #[test]
+    fn test_ledger() {
+        let mut ledger = Ledger::new();
+        let drifts = apply(&mut ledger, "position", &position("None", "0", "-5"));
+        assert!(drifts[0].is_within(Value::default()));
+
+        // The execution waits for its order to know the position.
+        apply(
+            &mut ledger,
+            "execution",
+            &execution("a", "1", "Buy", "1", "1600"),
+        );
+        assert_eq!(ledger.get("ETHUSDT", 0).unwrap().size(), Value::default());
+        apply(&mut ledger, "order", &order("a", 0));
+        apply(
+            &mut ledger,
+            "execution",
+            &execution("a", "2", "Buy", "1", "1700"),
+        );
+        // A redelivered execution is ignored.
+        apply(
+            &mut ledger,
+            "execution",
+            &execution("a", "2", "Buy", "1", "1700"),
+        );
+
+        let entry = ledger.get("ETHUSDT", 0).unwrap();
+        assert_eq!(entry.size(), v("2"));
+        assert_eq!(entry.avg_entry(), v("1650"));
+        assert_eq!(entry.maker_fee(), v("0.2"));
+
+        // Sell 3: close 2 and open a short of 1 at 1750.
+        apply(&mut ledger, "order", &order("b", 0));
+        apply(
+            &mut ledger,
+            "execution",
+            &execution("b", "3", "Sell", "3", "1750"),
+        );
+        let entry = ledger.get("ETHUSDT", 0).unwrap();
+        assert_eq!(entry.size(), v("-1"));
+        assert_eq!(entry.avg_entry(), v("1750"));
+        assert_eq!(entry.realised_pnl(), v("200"));
+        assert_eq!(entry.taker_fee(), v("0.1"));
+        assert!(close(entry.net_realised_pnl(), "199.7"));
+
+        let drifts = apply(&mut ledger, "position", &position("Sell", "1", "194.7"));
+        assert!(drifts[0].is_within(v("0.0000001")));
+        assert_eq!(
+            ledger.get("ETHUSDT", 0).unwrap().unrealised_pnl(),
+            Some(v("100"))
+        );
+
+        let drifts = apply(&mut ledger, "position", &position("Sell", "2", "190"));
+        assert_eq!(drifts[0].size, v("1"));
+        assert!(close(drifts[0].realised_pnl, "4.7"));
+        assert!(!drifts[0].is_within(v("1")));
+    }
+
+    #[test]
+    fn test_hedge_mode() {
+        let mut ledger = Ledger::new();
+        apply(&mut ledger, "order", &order("a", 1));
+        apply(&mut ledger, "order", &order("b", 2));
+        apply(
+            &mut ledger,
+            "execution",
+            &execution("a", "1", "Buy", "1", "1600"),
+        );
+        apply(
+            &mut ledger,
+            "execution",
+            &execution("b", "2", "Sell", "2", "1600"),
+        );
+        assert_eq!(ledger.get("ETHUSDT", 1).unwrap().size(), v("1"));
+        assert_eq!(ledger.get("ETHUSDT", 2).unwrap().size(), v("-2"));
+        assert!(ledger.get("ETHUSDT", 0).is_none());
+        assert_eq!(ledger.positions().count(), 2);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let mut entry = PositionLedger::new(Category::Inverse, "BTCUSD", 0);
+        entry.fill(&Side::Buy, v("100"), v("20000"));
+        entry.fill(&Side::Buy, v("100"), v("30000"));
+        assert!(close(entry.avg_entry(), "24000"));
+        entry.fill(&Side::Sell, v("200"), v("40000"));
+        assert_eq!(entry.size(), Value::default());
+        // 200 / 24000 - 200 / 40000
+        assert!(close(entry.realised_pnl(), "0.0033333333"));
+    }
+
+    #[test]
+    fn test_unknown_category() {
+        let mut ledger = Ledger::new();
+        let position = fixtures::position(json!({ "symbol": "BTCUSD" }));
+        // Position messages of unified accounts have no category.
+        assert!(apply(&mut ledger, "position", &position).is_empty());
+        assert!(ledger.get("BTCUSD", 0).is_none());
+
+        let order = fixtures::order(json!({ "category": "inverse", "symbol": "BTCUSD" }));
+        apply(&mut ledger, "order", &order);
+        assert_eq!(apply(&mut ledger, "position", &position).len(), 1);
+        let entry = ledger.get("BTCUSD", 0).unwrap();
+        assert_eq!(entry.category(), &Category::Inverse);
+    }
+
+    #[test]
+    fn test_pending() {
+        let mut ledger = Ledger::new();
+        for i in 0..=PENDING_ORDERS {
+            let execution = fixtures::execution(json!({
+                "orderId": i.to_string(),
+                "execId": i.to_string(),
+            }));
+            apply(&mut ledger, "execution", &execution);
+        }
+        // The executions of the oldest order are dropped.
+        assert_eq!(ledger.dropped_executions(), 1);
+        apply(&mut ledger, "order", &order("0", 0));
+        assert!(ledger.get("ETHUSDT", 0).is_none());
+        apply(&mut ledger, "order", &order("1", 0));
+        assert_eq!(ledger.get("ETHUSDT", 0).unwrap().size(), v("0.05"));
+    }
+}
@@ -1,30 +1,46 @@
+pub mod account;
 mod callback;
+pub mod event;
+pub mod frame;
 pub mod future;
+pub mod ledger;
+pub mod num;
 pub mod option;
+pub mod orderbook;
 pub mod private;
+pub mod reconnect;
+pub mod record;
+mod request;
 pub mod response;
 pub mod spot;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod sync;
+pub mod tracker;
+pub mod trade;
 
-use callback::Arg;
-use callback::Callback;
+pub use callback::{Arg, Callback};
 use log::*;
-use serde::Serialize;
 use std::net::TcpStream;
-use std::sync::mpsc::Receiver;
-use std::{sync::mpsc, thread, time::Duration};
-use tungstenite::{connect, stream::MaybeTlsStream, Message, WebSocket};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tungstenite::{stream::MaybeTlsStream, Message, WebSocket};
 
-use crate::error::Result;
-use crate::util::millis;
-use crate::util::sign;
+use crate::error::{BybitError, Result};
 use crate::FutureRole;
 
+use self::event::{Event, EventHandler, ParseFailures};
 use self::future::FutureWebSocketApiClientBuilder;
 use self::option::OptionWebSocketApiClientBuilder;
 use self::private::PrivateWebSocketApiClientBuilder;
+use self::reconnect::ReconnectPolicy;
+use self::record::Recorder;
+use self::request::Requests;
 use self::spot::SpotWebSocketApiClientBuilder;
+use self::trade::TradeWebSocketApiClientBuilder;
 
-/// A factory to create different kind of websocket api clients (spot / future / option / private).
+/// A factory to create different kind of websocket api clients (spot / future / option / private / trade).
 pub struct WebSocketApiClient;
 
 impl WebSocketApiClient {
@@ -52,19 +68,57 @@ impl WebSocketApiClient {
     pub fn private() -> PrivateWebSocketApiClientBuilder {
         PrivateWebSocketApiClientBuilder::new()
     }
+
+    /// Get a builder for building trade websocket api client, which places orders.
+    pub fn trade() -> TradeWebSocketApiClientBuilder {
+        TradeWebSocketApiClientBuilder::new()
+    }
 }
 
+/// The tracked topics of a client, shared with the running connection.
+#[derive(Clone)]
 struct Subscriber {
-    topics: Vec<String>,
+    topics: Arc<Mutex<Vec<String>>>,
 }
 
 impl Subscriber {
     fn new() -> Self {
-        Self { topics: Vec::new() }
+        Self {
+            topics: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn topics(&self) -> Vec<String> {
+        self.lock().clone()
+    }
+
+    /// Track `topics` and return the ones which are not tracked before.
+    fn add(&self, topics: Vec<String>) -> Vec<String> {
+        let mut tracked = self.lock();
+        let mut added = Vec::new();
+        for topic in topics {
+            if !tracked.contains(&topic) && !added.contains(&topic) {
+                added.push(topic);
+            }
+        }
+        tracked.extend(added.iter().cloned());
+        added
+    }
+
+    /// Untrack `topics` and return the ones which are tracked before.
+    fn remove(&self, topics: Vec<String>) -> Vec<String> {
+        let mut tracked = self.lock();
+        let mut removed = Vec::new();
+        for topic in topics {
+            if let Some(i) = tracked.iter().position(|t| *t == topic) {
+                removed.push(tracked.remove(i));
+            }
+        }
+        removed
     }
 
-    fn topics(&self) -> &Vec<String> {
-        &self.topics
+    fn lock(&self) -> MutexGuard<'_, Vec<String>> {
+        self.topics.lock().unwrap_or_else(|e| e.into_inner())
     }
 
     fn sub_orderbook(&mut self, symbol: &str, depth: u16) {
@@ -120,121 +174,515 @@ impl Subscriber {
     }
 
     fn sub(&mut self, topic: String) {
-        self.topics.push(topic);
+        self.add(vec![topic]);
     }
 }
 
-#[derive(Serialize)]
-struct Op<'a> {
-    op: &'a str,
-    args: Vec<String>,
+#[derive(Clone)]
+pub(crate) struct Credentials {
+    pub(crate) api_key: String,
+    pub(crate) secret: String,
 }
 
-struct Credentials {
-    api_key: String,
-    secret: String,
+/// Connection options shared by all kinds of websocket api clients.
+#[derive(Clone, Default)]
+struct Options {
+    reconnect: Option<ReconnectPolicy>,
+    on_event: Option<EventHandler>,
+    recorder: Option<Recorder>,
+    parse_failures: ParseFailures,
+    /// The read timeout of `run`, if not [`READ_TIMEOUT`].
+    read_timeout: Option<Duration>,
 }
 
-fn run<A, C>(
+impl Options {
+    fn notify(&self, event: Event) {
+        if let Some(handler) = &self.on_event {
+            handler(event);
+        }
+    }
+
+    fn record(&self, content: &str) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(content);
+        }
+    }
+
+    /// Count a frame which failed to parse and notify [`Event::ParseFailed`].
+    fn parse_failed(&self, content: &str, error: impl std::fmt::Display) {
+        let topic = response::de::Tag::key(content);
+        error!("Failed to parse frame of {:?}: {}", topic, error);
+        self.parse_failures.add(&topic);
+        self.notify(Event::ParseFailed {
+            topic,
+            error: error.to_string(),
+            text: content.to_string(),
+        });
+    }
+}
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+const PING: &str = "{\"op\":\"ping\"}";
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+/// Reads poll the socket with this timeout, which bounds the delay to write commands,
+/// unless a client sets a shorter one.
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The commands sent to a running client through its [`Handle`].
+enum Command {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+    Resync {
+        topic: String,
+        reason: String,
+    },
+    /// An order request framed by [`trade::TradeHandle`].
+    Send {
+        req_id: String,
+        op: &'static str,
+        text: String,
+    },
+    Shutdown,
+}
+
+impl Command {
+    /// Apply the command to the tracked topics and return the ops and topics to send.
+    fn apply(self, subscriber: &Subscriber, options: &Options) -> Vec<(&'static str, Vec<String>)> {
+        let (op, topics) = match self {
+            Command::Subscribe(topics) => ("subscribe", subscriber.add(topics)),
+            Command::Unsubscribe(topics) => ("unsubscribe", subscriber.remove(topics)),
+            Command::Resync { topic, reason } => {
+                warn!("Resync {}: {}", topic, reason);
+                let subscribed = subscriber.lock().contains(&topic);
+                options.notify(Event::Resync {
+                    topic: topic.clone(),
+                    reason,
+                });
+                if !subscribed {
+                    return Vec::new();
+                }
+                // Bybit pushes a snapshot after subscribing.
+                return vec![
+                    ("unsubscribe", vec![topic.clone()]),
+                    ("subscribe", vec![topic]),
+                ];
+            }
+            Command::Send { req_id, op, .. } => {
+                // Connections send orders directly, so it is only applied when disconnected.
+                // Orders are not queued, as their timestamp would be stale once sent.
+                reject_order(options, op, req_id, "not connected");
+                return Vec::new();
+            }
+            Command::Shutdown => return Vec::new(),
+        };
+        if topics.is_empty() {
+            return Vec::new();
+        }
+        vec![(op, topics)]
+    }
+}
+
+/// Reject an order request which can't be sent, e.g., before the connection is authenticated.
+fn reject_order(options: &Options, op: &str, req_id: String, reason: &str) {
+    warn!("Failed to {} {}: {}", op, req_id, reason);
+    options.notify(Event::Rejected {
+        op: op.to_string(),
+        args: vec![req_id],
+        reason: reason.to_string(),
+    });
+}
+
+/// A handle to control a websocket api client from other threads.
+///
+/// It can be obtained by calling `handle()` of any websocket api client, before or while it is running.
+/// Commands sent before the client is running take effect once it is connected.
+#[derive(Clone, Debug)]
+pub struct Handle {
+    route: Arc<Mutex<Route>>,
+}
+
+impl Handle {
+    /// Subscribe to topics, e.g., `orderbook.50.BTCUSDT` or `publicTrade.ETHUSDT`.
+    ///
+    /// Topics are sent on the running connection, and are also resubscribed after reconnecting.
+    /// Topics already subscribed are ignored.
+    pub fn subscribe<I, S>(&self, topics: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let topics = topics.into_iter().map(Into::into).collect();
+        self.send(Command::Subscribe(topics));
+    }
+
+    /// Unsubscribe from topics. Topics not subscribed are ignored.
+    pub fn unsubscribe<I, S>(&self, topics: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let topics = topics.into_iter().map(Into::into).collect();
+        self.send(Command::Unsubscribe(topics));
+    }
+
+    /// Resubscribe a topic to get a fresh snapshot, e.g., when the local order book is out of sync,
+    /// and notify [`Event::Resync`] with `reason`. Topics not subscribed are not resubscribed.
+    pub fn resync(&self, topic: impl Into<String>, reason: impl Into<String>) {
+        self.send(Command::Resync {
+            topic: topic.into(),
+            reason: reason.into(),
+        });
+    }
+
+    /// Close the connection with a close frame and make `run` of the client return `Ok(())`.
+    ///
+    /// If the client is not running yet, the next `run` returns immediately.
+    pub fn shutdown(&self) {
+        self.send(Command::Shutdown);
+    }
+
+    fn send(&self, command: Command) {
+        let route = self.route.lock().unwrap_or_else(|e| e.into_inner());
+        match &*route {
+            Route::Sync(tx) => {
+                let _ = tx.send(command);
+            }
+            #[cfg(feature = "async")]
+            Route::Async(tx) => {
+                let _ = tx.send(command);
+            }
+        }
+    }
+}
+
+/// The channel [`Handle`]s send commands to.
+///
+/// Streams receive commands over a tokio channel, so that their task wakes up as soon as
+/// a command is sent instead of polling.
+#[derive(Debug)]
+enum Route {
+    Sync(Sender<Command>),
+    #[cfg(feature = "async")]
+    Async(tokio::sync::mpsc::UnboundedSender<Command>),
+}
+
+/// The receiving side of the commands sent by [`Handle`]s.
+struct Control {
+    route: Arc<Mutex<Route>>,
+    tx: Sender<Command>,
+    rx: Arc<Mutex<Receiver<Command>>>,
+}
+
+impl Control {
+    fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            route: Arc::new(Mutex::new(Route::Sync(tx.clone()))),
+            tx,
+            rx: Arc::new(Mutex::new(rx)),
+        }
+    }
+
+    fn handle(&self) -> Handle {
+        Handle {
+            route: self.route.clone(),
+        }
+    }
+
+    /// Route commands to the blocking `run` and return its receiver.
+    fn commands(&self) -> MutexGuard<'_, Receiver<Command>> {
+        let rx = self.rx.lock().unwrap_or_else(|e| e.into_inner());
+        *self.route.lock().unwrap_or_else(|e| e.into_inner()) = Route::Sync(self.tx.clone());
+        rx
+    }
+
+    /// Route commands to a new stream, including the ones sent before it is connected.
+    #[cfg(feature = "async")]
+    fn stream_commands(&self) -> tokio::sync::mpsc::UnboundedReceiver<Command> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut route = self.route.lock().unwrap_or_else(|e| e.into_inner());
+        // The receiver is locked if `run` is running, which then keeps its commands.
+        if let Ok(pending) = self.rx.try_lock() {
+            for command in pending.try_iter() {
+                let _ = tx.send(command);
+            }
+        }
+        *route = Route::Async(tx);
+        rx
+    }
+}
+
+/// Run the client and pass each received text frame to `sink`, until `sink` returns `false`,
+/// the client is shut down or the connection is lost and can't be reestablished.
+fn run<S>(
     uri: &str,
-    topics: &Vec<String>,
+    subscriber: &Subscriber,
     credentials: Option<&Credentials>,
-    mut callback: C,
+    options: &Options,
+    control: &Control,
+    mut sink: S,
 ) -> Result<()>
+where
+    S: FnMut(String) -> bool,
+{
+    let commands = control.commands();
+
+    let mut attempt = 0;
+    loop {
+        // Apply the commands sent while disconnected, e.g., before running, so topics are
+        // subscribed by `connect` and orders are rejected as not connected.
+        while let Ok(command) = commands.try_recv() {
+            match command {
+                Command::Shutdown => return Ok(()),
+                command => {
+                    command.apply(subscriber, options);
+                }
+            }
+        }
+        let err = match connect(uri, &subscriber.topics(), credentials, options) {
+            Ok((ws, mut requests)) => {
+                if attempt > 0 {
+                    info!("Reconnected after {} attempt(s)", attempt);
+                    options.notify(Event::Reconnected { attempt });
+                }
+                let res = serve(ws, &mut requests, subscriber, options, &commands, &mut sink);
+                // Only a healthy connection resets the backoff, so a server which drops
+                // connections right after accepting them is retried with growing delays.
+                if requests.confirmed() {
+                    attempt = 0;
+                }
+                let err = match res {
+                    Ok(()) => return Ok(()),
+                    // Retrying with the same credentials won't help.
+                    Err(err @ BybitError::AuthFailed(_)) => return Err(err),
+                    Err(err) => err,
+                };
+                options.notify(Event::Disconnected {
+                    reason: err.to_string(),
+                });
+                err
+            }
+            Err(e) => e,
+        };
+
+        let policy = match &options.reconnect {
+            Some(policy) if policy.allows(attempt) => policy,
+            Some(policy) => {
+                policy.give_up(&err);
+                return Err(err);
+            }
+            None => return Err(err),
+        };
+
+        attempt += 1;
+        let delay = policy.delay(attempt);
+        warn!("Connection lost: {}. Reconnect in {:?}", err, delay);
+        options.notify(Event::Reconnecting { attempt, delay });
+        let deadline = Instant::now() + delay;
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            match commands.recv_timeout(timeout) {
+                Ok(Command::Shutdown) => return Ok(()),
+                // Topics are subscribed on the next connection.
+                Ok(command) => {
+                    command.apply(subscriber, options);
+                }
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// A sink which parses each text frame and passes the response to `callback`.
+fn callback_sink<A, C>(mut callback: C, options: &Options) -> impl FnMut(String) -> bool
 where
     A: Arg,
     C: Callback<A>,
 {
-    let (mut ws, _) = connect(uri)?;
+    parse_sink::<A, _>(
+        move |res| {
+            callback(res);
+            true
+        },
+        options,
+    )
+}
+
+/// A sink which parses each text frame and passes the response to `f`, until it returns `false`.
+///
+/// Frames which fail to parse are counted and notified with `options`.
+#[cfg(not(feature = "simd-json"))]
+fn parse_sink<A, F>(mut f: F, options: &Options) -> impl FnMut(String) -> bool
+where
+    A: Arg,
+    F: for<'any> FnMut(A::ValueType<'any>) -> bool,
+{
+    let options = options.clone();
+    move |content| match A::parse(&content) {
+        Ok(res) => f(res),
+        Err(e) => {
+            options.parse_failed(&content, e);
+            true
+        }
+    }
+}
+
+/// Parse frames with simd-json. It rewrites the frame, so a copy is parsed
+/// to keep the text of frames which fail to parse.
+#[cfg(feature = "simd-json")]
+fn parse_sink<A, F>(mut f: F, options: &Options) -> impl FnMut(String) -> bool
+where
+    A: Arg,
+    F: for<'any> FnMut(A::ValueType<'any>) -> bool,
+{
+    let options = options.clone();
+    let mut frame = Vec::new();
+    let mut buffers = simd_json::Buffers::default();
+    move |content| {
+        frame.clear();
+        frame.extend_from_slice(content.as_bytes());
+        match A::parse_mut(&mut frame, &mut buffers) {
+            Ok(res) => f(res),
+            Err(e) => {
+                options.parse_failed(&content, e);
+                true
+            }
+        }
+    }
+}
+
+/// Open a connection, then authenticate and subscribe to topics.
+fn connect(
+    uri: &str,
+    topics: &[String],
+    credentials: Option<&Credentials>,
+    options: &Options,
+) -> Result<(Socket, Requests)> {
+    let (mut ws, _) = tungstenite::connect(uri)?;
 
     // Set read timeout to the underlying TCP stream.
     //
     // Read and write are both in the main thread loop. A blocking read call
-    // will starve writing that causes ping op message can't be sent on time.
-    // Read timeout mitigate this situation.
-    set_read_timeout(&ws);
+    // will starve writing that causes ping op message can't be sent on time,
+    // and commands from handles can't be handled in time.
+    // Read timeout mitigate this situation. The trade client sets a short one,
+    // so orders are written with little delay.
+    set_read_timeout(&ws, options.read_timeout.unwrap_or(READ_TIMEOUT));
+
+    let mut requests = Requests::default();
 
     // Authenticate
     if let Some(credentials) = credentials {
-        let req = auth_req(credentials);
-        ws.write_message(Message::Text(req))?;
+        ws.write_message(Message::Text(requests.auth(credentials)))?;
     }
 
     // Subscribe
-    ws.write_message(Message::Text(subscription(topics)))?;
+    if !topics.is_empty() {
+        let req = requests.op("subscribe", topics.to_vec());
+        ws.write_message(Message::Text(req))?;
+    }
+
+    Ok((ws, requests))
+}
 
-    let rx = ping();
+/// Serve the connection until it is shut down, the sink stops or an error occurs.
+fn serve<S>(
+    mut ws: Socket,
+    requests: &mut Requests,
+    subscriber: &Subscriber,
+    options: &Options,
+    commands: &Receiver<Command>,
+    sink: &mut S,
+) -> Result<()>
+where
+    S: FnMut(String) -> bool,
+{
+    let mut last_ping = Instant::now();
     loop {
+        while let Ok(command) = commands.try_recv() {
+            match command {
+                Command::Shutdown => {
+                    close(ws);
+                    return Ok(());
+                }
+                Command::Send { req_id, op, text } if requests.authenticated() => {
+                    requests.track(req_id, op);
+                    ws.write_message(Message::Text(text))?;
+                }
+                Command::Send { req_id, op, .. } => {
+                    reject_order(options, op, req_id, "not authenticated");
+                }
+                command => {
+                    for (op, topics) in command.apply(subscriber, options) {
+                        ws.write_message(Message::Text(requests.op(op, topics)))?;
+                    }
+                }
+            }
+        }
+
         // Ping
-        if let Ok(ping) = rx.try_recv() {
-            ws.write_message(Message::Text(ping.into()))?
+        if last_ping.elapsed() >= PING_INTERVAL {
+            ws.write_message(Message::Text(PING.into()))?;
+            last_ping = Instant::now();
         }
 
         match ws.read_message() {
-            Ok(msg) => match msg {
-                Message::Text(content) => {
-                    debug!("Received: {}", content);
-                    match serde_json::from_str(&content) {
-                        Ok(res) => callback(res),
-                        Err(e) => error!("Error: {}", e),
-                    }
-                }
-                _ => {}
-            },
-            Err(e) => match e {
-                tungstenite::Error::Io(ref ee) => {
-                    if ee.kind() != std::io::ErrorKind::WouldBlock
-                        && ee.kind() != std::io::ErrorKind::TimedOut
-                    {
-                        Err(e)?
-                    }
+            Ok(Message::Text(content)) => {
+                debug!("Received: {}", content);
+                options.record(&content);
+                let reply = requests.reply(&content, options);
+                if !sink(content) {
+                    close(ws);
+                    return Ok(());
                 }
-                _ => Err(e)?,
-            },
+                reply?;
+            }
+            Ok(_) => {}
+            Err(e) if is_timeout(&e) => {}
+            Err(e) => Err(e)?,
+        }
+    }
+}
+
+/// Send a close frame and wait for the server to close the connection.
+fn close(mut ws: Socket) {
+    if let Err(e) = ws.close(None) {
+        debug!("Failed to close: {}", e);
+        return;
+    }
+
+    let deadline = Instant::now() + CLOSE_TIMEOUT;
+    while Instant::now() < deadline {
+        match ws.read_message() {
+            Ok(_) => {}
+            Err(e) if is_timeout(&e) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return,
+            Err(e) => {
+                debug!("Failed to close: {}", e);
+                return;
+            }
         }
     }
 }
 
-fn set_read_timeout(ws: &WebSocket<MaybeTlsStream<TcpStream>>) {
+fn is_timeout(e: &tungstenite::Error) -> bool {
+    match e {
+        tungstenite::Error::Io(e) => {
+            e.kind() == std::io::ErrorKind::WouldBlock || e.kind() == std::io::ErrorKind::TimedOut
+        }
+        _ => false,
+    }
+}
+
+fn set_read_timeout(ws: &Socket, timeout: Duration) {
     match ws.get_ref() {
         MaybeTlsStream::Plain(s) => {
-            s.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+            s.set_read_timeout(Some(timeout)).unwrap();
         }
         MaybeTlsStream::NativeTls(t) => {
-            t.get_ref()
-                .set_read_timeout(Some(Duration::from_secs(10)))
-                .unwrap();
+            t.get_ref().set_read_timeout(Some(timeout)).unwrap();
         }
         _ => unreachable!(),
     };
 }
-
-fn auth_req(credentials: &Credentials) -> String {
-    let expires = millis() + 10000;
-    let val = format!("GET/realtime{}", expires);
-    let signature = sign(&credentials.secret, &val);
-    let auth_req = Op {
-        op: "auth",
-        args: vec![credentials.api_key.clone(), expires.to_string(), signature],
-    };
-    serde_json::to_string(&auth_req).unwrap()
-}
-
-fn subscription(topics: &Vec<String>) -> String {
-    let sub = Op {
-        op: "subscribe",
-        args: topics.clone(),
-    };
-    serde_json::to_string(&sub).unwrap()
-}
-
-fn ping() -> Receiver<&'static str> {
-    let (tx, rx) = mpsc::channel();
-    thread::spawn(move || loop {
-        if let Err(_) = tx.send("{\"op\":\"ping\"}") {
-            break;
-        };
-        thread::sleep(Duration::from_secs(20));
-    });
-    rx
-}
@@ -0,0 +1,41 @@
+use super::response::{Number, OptionalNumber};
+use crate::error::Result;
+
+/// The numeric value of prices, sizes and amounts computed from responses,
+/// e.g., by [`OrderBook`](super::orderbook::OrderBook).
+///
+/// It is `f64`, or `Decimal` if the `decimal` feature is enabled.
+#[cfg(not(feature = "decimal"))]
+pub type Value = f64;
+/// The numeric value of prices, sizes and amounts computed from responses,
+/// e.g., by [`OrderBook`](super::orderbook::OrderBook).
+///
+/// It is `f64`, or `Decimal` if the `decimal` feature is enabled.
+#[cfg(feature = "decimal")]
+pub type Value = rust_decimal::Decimal;
+
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn value(number: &Number) -> Result<Value> {
+    number
+        .parse()
+        .map_err(|_| crate::error::BybitError::InvalidNumber(number.to_string()))
+}
+
+#[cfg(feature = "decimal")]
+pub(crate) fn value(number: &Number) -> Result<Value> {
+    Ok(*number)
+}
+
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn optional_value(number: &OptionalNumber) -> Result<Option<Value>> {
+    if number.is_empty() {
+        Ok(None)
+    } else {
+        value(number).map(Some)
+    }
+}
+
+#[cfg(feature = "decimal")]
+pub(crate) fn optional_value(number: &OptionalNumber) -> Result<Option<Value>> {
+    Ok(*number)
+}
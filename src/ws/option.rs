@@ -1,8 +1,17 @@
 use super::callback::Callback;
-use super::response::OptionPublicResponseArg;
-use super::run;
-use super::Subscriber;
+use super::event::{Event, ParseFailures};
+use super::frame::Responses;
+use super::reconnect::ReconnectPolicy;
+use super::record::Recorder;
+use super::response::{
+    IntoOwned, OptionPublicResponse, OptionPublicResponseArg, OwnedOptionPublicResponse,
+};
+#[cfg(feature = "async")]
+use super::stream::{connect_frames, connect_stream, FrameStream, ResponseStream};
+use super::{callback_sink, parse_sink, run};
+use super::{Control, Handle, Options, Subscriber};
 use crate::error::Result;
+use std::sync::Arc;
 
 const MAINNET_OPTION: &str = "wss://stream.bybit.com/v5/public/option";
 const TESTNET_OPTION: &str = "wss://stream-testnet.bybit.com/v5/public/option";
@@ -25,6 +34,8 @@ impl From<OrderbookDepth> for u16 {
 pub struct OptionWebsocketApiClient {
     uri: String,
     subscriber: Subscriber,
+    options: Options,
+    control: Control,
 }
 
 impl OptionWebsocketApiClient {
@@ -43,13 +54,81 @@ impl OptionWebsocketApiClient {
         self.subscriber.sub_ticker(symbol.as_ref());
     }
 
+    /// Get a handle to control the client from other threads, e.g., to shut it down.
+    pub fn handle(&self) -> Handle {
+        self.control.handle()
+    }
+
+    /// Get the counts of received frames which failed to parse, shared with the running client.
+    pub fn parse_failures(&self) -> ParseFailures {
+        self.options.parse_failures.clone()
+    }
+
     pub fn run<C: Callback<OptionPublicResponseArg>>(&self, callback: C) -> Result<()> {
-        run(&self.uri, self.subscriber.topics(), None, callback)
+        run(
+            &self.uri,
+            &self.subscriber,
+            None,
+            &self.options,
+            &self.control,
+            callback_sink(callback, &self.options),
+        )
+    }
+
+    /// Run the client on a new thread and receive responses over a bounded channel with `capacity`.
+    ///
+    /// Unlike `run`, responses own their data, so they can be kept or sent to other threads.
+    pub fn spawn(self, capacity: usize) -> Responses<OwnedOptionPublicResponse> {
+        Responses::spawn(self.handle(), capacity, move |tx| {
+            run(
+                &self.uri,
+                &self.subscriber,
+                None,
+                &self.options,
+                &self.control,
+                parse_sink::<OptionPublicResponseArg, _>(
+                    |res: OptionPublicResponse| tx.send(res.into_owned()).is_ok(),
+                    &self.options,
+                ),
+            )
+        })
+    }
+
+    /// Connect and return a stream of the received responses. Requires the `async` feature.
+    ///
+    /// Pings are sent by a background task, so it must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn stream(&self) -> Result<ResponseStream<OwnedOptionPublicResponse>> {
+        connect_stream::<OptionPublicResponseArg, _>(
+            &self.uri,
+            &self.subscriber,
+            None,
+            &self.options,
+            &self.control,
+        )
+        .await
+    }
+
+    /// Connect and return a stream of the raw frames, which responses borrow from.
+    /// Requires the `async` feature.
+    ///
+    /// Pings are sent by a background task, so it must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn frames(&self) -> Result<FrameStream<OptionPublicResponseArg>> {
+        connect_frames(
+            &self.uri,
+            &self.subscriber,
+            None,
+            &self.options,
+            &self.control,
+        )
+        .await
     }
 }
 
 pub struct OptionWebSocketApiClientBuilder {
     uri: String,
+    options: Options,
 }
 
 impl OptionWebSocketApiClientBuilder {
@@ -57,6 +136,7 @@ impl OptionWebSocketApiClientBuilder {
     pub fn new() -> Self {
         Self {
             uri: MAINNET_OPTION.to_string(),
+            options: Options::default(),
         }
     }
 
@@ -75,11 +155,42 @@ impl OptionWebSocketApiClientBuilder {
         self
     }
 
+    /// Reconnect with the given policy when the connection is lost.
+    ///
+    /// All topics are resubscribed after reconnecting.
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.options.reconnect = Some(policy);
+        self
+    }
+
+    /// Set a handler which is notified of connection events, such as disconnected and reconnected.
+    pub fn on_event<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        self.options.on_event = Some(Arc::new(handler));
+        self
+    }
+
+    /// Record every received text frame, e.g., to replay an incident later.
+    pub fn record(mut self, recorder: Recorder) -> Self {
+        self.options.recorder = Some(recorder);
+        self
+    }
+
     /// Build a option websocket api client.
     pub fn build(self) -> OptionWebsocketApiClient {
         OptionWebsocketApiClient {
             uri: self.uri,
             subscriber: Subscriber::new(),
+            options: self.options,
+            control: Control::new(),
         }
     }
 }
+
+impl Default for OptionWebSocketApiClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,406 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use super::num::{value, Value};
+use super::response::{BaseOptionPublicResponse, BasePublicResponse, Number, Orderbook};
+use super::Handle;
+use crate::enums::DataType;
+use crate::error::{BybitError, Result};
+
+/// A price level of an [`OrderBook`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Level {
+    pub price: Value,
+    pub size: Value,
+}
+
+/// A local order book maintained from the `orderbook.{depth}.{symbol}` topic.
+///
+/// A `snapshot` message resets the book and a `delta` message updates it,
+/// where a level with zero size is removed. Works for spot, linear, inverse and option books.
+///
+/// A delta whose update ID does not follow the last one, or whose cross sequence goes backwards,
+/// means updates are missing or out of order. The book is then cleared and marked invalid,
+/// and deltas are ignored until the next snapshot. So is a message with a malformed level,
+/// rather than being applied in part. With a handle of the client,
+/// the topic is resubscribed to get a fresh snapshot and [`Event::Resync`](super::event::Event::Resync) is notified.
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    symbol: String,
+    bids: BTreeMap<Price, Value>,
+    asks: BTreeMap<Price, Value>,
+    update_id: u64,
+    seq: Option<u64>,
+    ts: u64,
+    valid: bool,
+    handle: Option<Handle>,
+}
+
+impl OrderBook {
+    /// Create an empty `OrderBook`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resubscribe the topic with `handle` when the book is out of sync.
+    pub fn with_handle(mut self, handle: Handle) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+
+    /// Apply an orderbook message of spot, linear or inverse channels.
+    pub fn apply(&mut self, res: &BasePublicResponse<Orderbook>) -> Result<()> {
+        let result = self.update(&res.type_, res.ts, &res.data);
+        self.resync_on_error(&res.topic, result)
+    }
+
+    /// Apply an orderbook message of option channels.
+    pub fn apply_option(&mut self, res: &BaseOptionPublicResponse<Orderbook>) -> Result<()> {
+        let result = self.update(&res.type_, res.ts, &res.data);
+        self.resync_on_error(&res.topic, result)
+    }
+
+    /// Apply orderbook data of the given data type.
+    ///
+    /// Returns [`BybitError::OrderBookOutOfSync`] if updates are missing or out of order,
+    /// or [`BybitError::InvalidNumber`] if a level is malformed. The book is invalid after either.
+    /// The topic is not resubscribed, since it is unknown here.
+    pub fn update(&mut self, type_: &DataType, ts: u64, data: &Orderbook) -> Result<()> {
+        if *type_ == DataType::Snapshot {
+            self.bids.clear();
+            self.asks.clear();
+            self.symbol = data.s.to_string();
+            self.valid = true;
+        } else if !self.valid {
+            // Wait for the next snapshot.
+            return Ok(());
+        } else if let Some(reason) = self.check(data) {
+            self.clear();
+            self.valid = false;
+            return Err(BybitError::OrderBookOutOfSync(reason));
+        }
+        if let Err(e) = self.update_levels(data) {
+            // The message is applied in part, so the book is inconsistent.
+            self.clear();
+            self.valid = false;
+            return Err(e);
+        }
+        self.update_id = data.u;
+        self.seq = data.seq;
+        self.ts = ts;
+        Ok(())
+    }
+
+    fn update_levels(&mut self, data: &Orderbook) -> Result<()> {
+        for item in &data.b {
+            update_level(&mut self.bids, &item.price, &item.size)?;
+        }
+        for item in &data.a {
+            update_level(&mut self.asks, &item.price, &item.size)?;
+        }
+        Ok(())
+    }
+
+    /// Check whether a delta follows the last applied message and return the reason if not.
+    fn check(&self, data: &Orderbook) -> Option<String> {
+        if data.u == 1 {
+            return Some("update ID is reset by a service restart".to_string());
+        }
+        if data.u != self.update_id + 1 {
+            return Some(format!(
+                "expected update ID {}, got {}",
+                self.update_id + 1,
+                data.u
+            ));
+        }
+        match (self.seq, data.seq) {
+            (Some(last), Some(seq)) if seq < last => {
+                Some(format!("stale cross sequence {}, last is {}", seq, last))
+            }
+            _ => None,
+        }
+    }
+
+    fn resync_on_error(&self, topic: &str, result: Result<()>) -> Result<()> {
+        if let (Err(e), Some(handle)) = (&result, &self.handle) {
+            match e {
+                BybitError::OrderBookOutOfSync(reason) => handle.resync(topic, reason.as_str()),
+                e => handle.resync(topic, e.to_string()),
+            }
+        }
+        result
+    }
+
+    /// Remove all levels.
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    /// Whether the book is built from a snapshot and all deltas since then.
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// Symbol name.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// The update ID of the last applied message.
+    pub fn update_id(&self) -> u64 {
+        self.update_id
+    }
+
+    /// The cross sequence of the last applied message. Option does not have this field.
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    /// The timestamp (ms) of the last applied message.
+    pub fn ts(&self) -> u64 {
+        self.ts
+    }
+
+    /// Whether both sides are empty.
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    /// The best (highest) bid.
+    pub fn best_bid(&self) -> Option<Level> {
+        self.bids().next()
+    }
+
+    /// The best (lowest) ask.
+    pub fn best_ask(&self) -> Option<Level> {
+        self.asks().next()
+    }
+
+    /// Bids, sorted by price in descending order.
+    pub fn bids(&self) -> impl Iterator<Item = Level> + '_ {
+        self.bids.iter().rev().map(level)
+    }
+
+    /// Asks, sorted by price in ascending order.
+    pub fn asks(&self) -> impl Iterator<Item = Level> + '_ {
+        self.asks.iter().map(level)
+    }
+
+    /// The best `n` levels of (bids, asks).
+    pub fn depth(&self, n: usize) -> (Vec<Level>, Vec<Level>) {
+        (self.bids().take(n).collect(), self.asks().take(n).collect())
+    }
+
+    /// The mid price of the best bid and ask.
+    pub fn mid(&self) -> Option<Value> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        Some((bid.price + ask.price) / Value::from(2u8))
+    }
+
+    /// The difference between the best ask and bid.
+    pub fn spread(&self) -> Option<Value> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        Some(ask.price - bid.price)
+    }
+
+    /// The total size of bids priced at or above `price`, i.e., the size a sell order can fill down to `price`.
+    pub fn bid_size_to(&self, price: Value) -> Value {
+        self.bids.range(Price(price)..).map(|(_, size)| *size).sum()
+    }
+
+    /// The total size of asks priced at or below `price`, i.e., the size a buy order can fill up to `price`.
+    pub fn ask_size_to(&self, price: Value) -> Value {
+        self.asks
+            .range(..=Price(price))
+            .map(|(_, size)| *size)
+            .sum()
+    }
+}
+
+/// The price key which orders levels numerically.
+#[derive(Debug, Clone, Copy)]
+struct Price(Value);
+
+impl PartialEq for Price {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Price {}
+
+impl PartialOrd for Price {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Price {
+    #[cfg(not(feature = "decimal"))]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+
+    #[cfg(feature = "decimal")]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+fn level((price, size): (&Price, &Value)) -> Level {
+    Level {
+        price: price.0,
+        size: *size,
+    }
+}
+
+fn update_level(side: &mut BTreeMap<Price, Value>, price: &Number, size: &Number) -> Result<()> {
+    let (price, size) = (value(price)?, value(size)?);
+    if size == Value::default() {
+        side.remove(&Price(price));
+    } else {
+        side.insert(Price(price), size);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(text: &str) -> BasePublicResponse<'_, Orderbook<'_>> {
+        serde_json::from_str(text).unwrap()
+    }
+
+    fn v(s: &str) -> Value {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_and_delta() {
+        let mut book = OrderBook::new();
+        book.apply(&message(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1,"data":{"s":"BTCUSDT","b":[["9.5","1"],["10","2"]],"a":[["11","3"],["100","4"]],"u":1,"seq":10}}"#,
+        ))
+        .unwrap();
+        assert_eq!(book.symbol(), "BTCUSDT");
+        assert_eq!(book.best_bid().unwrap().price, v("10"));
+        // Numeric rather than lexicographic order.
+        assert_eq!(book.best_ask().unwrap().price, v("11"));
+        assert_eq!(book.mid(), Some(v("10.5")));
+        assert_eq!(book.spread(), Some(v("1")));
+
+        book.apply(&message(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":2,"data":{"s":"BTCUSDT","b":[["10","0"],["9.8","5"]],"a":[["10.5","1"]],"u":2,"seq":11}}"#,
+        ))
+        .unwrap();
+        let (bids, asks) = book.depth(2);
+        assert_eq!(
+            bids,
+            vec![
+                Level {
+                    price: v("9.8"),
+                    size: v("5")
+                },
+                Level {
+                    price: v("9.5"),
+                    size: v("1")
+                },
+            ]
+        );
+        assert_eq!(
+            asks[0],
+            Level {
+                price: v("10.5"),
+                size: v("1")
+            }
+        );
+        assert_eq!(book.bid_size_to(v("9.5")), v("6"));
+        assert_eq!(book.ask_size_to(v("11")), v("4"));
+        assert_eq!(book.update_id(), 2);
+        assert_eq!(book.seq(), Some(11));
+
+        book.apply(&message(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":3,"data":{"s":"BTCUSDT","b":[],"a":[["12","1"]],"u":1,"seq":20}}"#,
+        ))
+        .unwrap();
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.asks().count(), 1);
+    }
+
+    #[test]
+    fn test_out_of_sync() {
+        let control = super::super::Control::new();
+        let mut book = OrderBook::new().with_handle(control.handle());
+        let delta = |u: u64, seq: u64| {
+            format!(
+                r#"{{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":2,"data":{{"s":"BTCUSDT","b":[["10","1"]],"a":[],"u":{},"seq":{}}}}}"#,
+                u, seq
+            )
+        };
+
+        // Deltas before a snapshot are ignored.
+        book.apply(&message(&delta(5, 10))).unwrap();
+        assert!(!book.is_valid());
+        assert!(book.is_empty());
+
+        book.apply(&message(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1,"data":{"s":"BTCUSDT","b":[["9","1"]],"a":[["11","1"]],"u":5,"seq":10}}"#,
+        ))
+        .unwrap();
+        assert!(book.is_valid());
+        book.apply(&message(&delta(6, 11))).unwrap();
+        assert_eq!(book.best_bid().unwrap().price, v("10"));
+
+        // Missing update.
+        let err = book.apply(&message(&delta(8, 12))).unwrap_err();
+        assert!(matches!(err, BybitError::OrderBookOutOfSync(_)));
+        assert!(!book.is_valid());
+        assert!(book.is_empty());
+        let command = control.rx.lock().unwrap().try_recv().unwrap();
+        assert!(matches!(
+            command,
+            super::super::Command::Resync { topic, .. } if topic == "orderbook.50.BTCUSDT"
+        ));
+
+        // Stale cross sequence.
+        book.apply(&message(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1,"data":{"s":"BTCUSDT","b":[],"a":[],"u":1,"seq":20}}"#,
+        ))
+        .unwrap();
+        assert!(book.apply(&message(&delta(2, 19))).is_err());
+    }
+
+    #[cfg(not(feature = "decimal"))]
+    #[test]
+    fn test_malformed_level() {
+        let control = super::super::Control::new();
+        let mut book = OrderBook::new().with_handle(control.handle());
+        book.apply(&message(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1,"data":{"s":"BTCUSDT","b":[["9","1"]],"a":[["11","1"]],"u":5,"seq":10}}"#,
+        ))
+        .unwrap();
+
+        // A malformed level invalidates the book instead of leaving it applied in part.
+        let err = book
+            .apply(&message(
+                r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":2,"data":{"s":"BTCUSDT","b":[["10","1"]],"a":[["x","1"]],"u":6,"seq":11}}"#,
+            ))
+            .unwrap_err();
+        assert!(matches!(err, BybitError::InvalidNumber(_)));
+        assert!(!book.is_valid());
+        assert!(book.is_empty());
+        assert_eq!(book.update_id(), 5);
+        let command = control.rx.lock().unwrap().try_recv().unwrap();
+        assert!(matches!(command, super::super::Command::Resync { .. }));
+
+        // So does a malformed snapshot.
+        assert!(book
+            .apply(&message(
+                r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":3,"data":{"s":"BTCUSDT","b":[["9","x"]],"a":[],"u":1,"seq":20}}"#,
+            ))
+            .is_err());
+        assert!(!book.is_valid());
+    }
+}
@@ -1,8 +1,15 @@
 use super::callback::Callback;
-use super::response::PrivateResponseArg;
+use super::event::{Event, ParseFailures};
+use super::frame::Responses;
+use super::reconnect::ReconnectPolicy;
+use super::record::Recorder;
+use super::response::{IntoOwned, OwnedPrivateResponse, PrivateResponse, PrivateResponseArg};
+#[cfg(feature = "async")]
+use super::stream::{connect_frames, connect_stream, FrameStream, ResponseStream};
 use super::Subscriber;
-use super::{run, Credentials};
+use super::{callback_sink, parse_sink, run, Control, Credentials, Handle, Options};
 use crate::error::Result;
+use std::sync::Arc;
 
 const MAINNET_PRIVATE: &str = "wss://stream.bybit.com/v5/private";
 const TESTNET_PRIVATE: &str = "wss://stream-testnet.bybit.com/v5/private";
@@ -11,6 +18,8 @@ pub struct PrivateWebsocketApiClient {
     uri: String,
     subscriber: Subscriber,
     credentials: Credentials,
+    options: Options,
+    control: Control,
 }
 
 impl PrivateWebsocketApiClient {
@@ -34,18 +43,81 @@ impl PrivateWebsocketApiClient {
         self.subscriber.sub_greek();
     }
 
+    /// Get a handle to control the client from other threads, e.g., to shut it down.
+    pub fn handle(&self) -> Handle {
+        self.control.handle()
+    }
+
+    /// Get the counts of received frames which failed to parse, shared with the running client.
+    pub fn parse_failures(&self) -> ParseFailures {
+        self.options.parse_failures.clone()
+    }
+
     pub fn run<C: Callback<PrivateResponseArg>>(&self, callback: C) -> Result<()> {
         run(
             &self.uri,
-            self.subscriber.topics(),
+            &self.subscriber,
+            Some(&self.credentials),
+            &self.options,
+            &self.control,
+            callback_sink(callback, &self.options),
+        )
+    }
+
+    /// Run the client on a new thread and receive responses over a bounded channel with `capacity`.
+    ///
+    /// Unlike `run`, responses own their data, so they can be kept or sent to other threads.
+    pub fn spawn(self, capacity: usize) -> Responses<OwnedPrivateResponse> {
+        Responses::spawn(self.handle(), capacity, move |tx| {
+            run(
+                &self.uri,
+                &self.subscriber,
+                Some(&self.credentials),
+                &self.options,
+                &self.control,
+                parse_sink::<PrivateResponseArg, _>(
+                    |res: PrivateResponse| tx.send(res.into_owned()).is_ok(),
+                    &self.options,
+                ),
+            )
+        })
+    }
+
+    /// Connect and return a stream of the received responses. Requires the `async` feature.
+    ///
+    /// Pings are sent by a background task, so it must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn stream(&self) -> Result<ResponseStream<OwnedPrivateResponse>> {
+        connect_stream::<PrivateResponseArg, _>(
+            &self.uri,
+            &self.subscriber,
+            Some(&self.credentials),
+            &self.options,
+            &self.control,
+        )
+        .await
+    }
+
+    /// Connect and return a stream of the raw frames, which responses borrow from.
+    /// Requires the `async` feature.
+    ///
+    /// Pings are sent by a background task, so it must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn frames(&self) -> Result<FrameStream<PrivateResponseArg>> {
+        connect_frames(
+            &self.uri,
+            &self.subscriber,
             Some(&self.credentials),
-            callback,
+            &self.options,
+            &self.control,
         )
+        .await
     }
 }
 
 pub struct PrivateWebSocketApiClientBuilder {
     uri: String,
+    options: Options,
 }
 
 impl PrivateWebSocketApiClientBuilder {
@@ -53,6 +125,7 @@ impl PrivateWebSocketApiClientBuilder {
     pub fn new() -> Self {
         Self {
             uri: MAINNET_PRIVATE.to_string(),
+            options: Options::default(),
         }
     }
 
@@ -71,6 +144,29 @@ impl PrivateWebSocketApiClientBuilder {
         self
     }
 
+    /// Reconnect with the given policy when the connection is lost.
+    ///
+    /// The client is authenticated again and all topics are resubscribed after reconnecting.
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.options.reconnect = Some(policy);
+        self
+    }
+
+    /// Set a handler which is notified of connection events, such as disconnected and reconnected.
+    pub fn on_event<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        self.options.on_event = Some(Arc::new(handler));
+        self
+    }
+
+    /// Record every received text frame, e.g., to replay an incident later.
+    pub fn record(mut self, recorder: Recorder) -> Self {
+        self.options.recorder = Some(recorder);
+        self
+    }
+
     /// Build a private websocket api client with api key and secret key.
     pub fn build_with_credentials<S: AsRef<str>>(
         self,
@@ -80,6 +176,8 @@ impl PrivateWebSocketApiClientBuilder {
         PrivateWebsocketApiClient {
             uri: self.uri,
             subscriber: Subscriber::new(),
+            options: self.options,
+            control: Control::new(),
             credentials: Credentials {
                 api_key: api_key.as_ref().to_owned(),
                 secret: secret.as_ref().to_owned(),
@@ -87,3 +185,9 @@ impl PrivateWebSocketApiClientBuilder {
         }
     }
 }
+
+impl Default for PrivateWebSocketApiClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::BybitError;
+
+/// The policy to reconnect a websocket api client after the connection is lost.
+///
+/// The delay before each attempt grows exponentially from `initial_delay` up to `max_delay`,
+/// and is randomly shortened by up to `jitter` (a fraction between 0 and 1) of itself,
+/// so that many clients do not hammer the server at the same moment.
+/// The attempt counter is reset once a connection proves healthy, i.e., a request such as
+/// `auth`, `subscribe` or `ping` succeeds on it, rather than as soon as it is established.
+#[derive(Clone)]
+pub struct ReconnectPolicy {
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+    jitter: f64,
+    max_attempts: Option<u32>,
+    on_give_up: Option<GiveUpHook>,
+}
+
+type GiveUpHook = Arc<dyn Fn(&BybitError) + Send + Sync>;
+
+impl ReconnectPolicy {
+    /// Create a new `ReconnectPolicy` which retries forever,
+    /// starting from a 1 second delay doubled on each attempt up to 60 seconds, with 50% jitter.
+    pub fn new() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.5,
+            max_attempts: None,
+            on_give_up: None,
+        }
+    }
+
+    /// Set the delay before the first attempt.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Set the upper bound of the delay between attempts.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Set the factor the delay is multiplied by after each failed attempt.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Set the jitter fraction. 0 disables jitter, 1 means the delay is anywhere between 0 and the computed delay.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Give up after `attempts` consecutive failed attempts.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Set a hook which is called with the last error when the policy gives up reconnecting.
+    pub fn on_give_up<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&BybitError) + Send + Sync + 'static,
+    {
+        self.on_give_up = Some(Arc::new(hook));
+        self
+    }
+
+    /// Whether another attempt is allowed after `attempt` attempts have been made.
+    pub(crate) fn allows(&self, attempt: u32) -> bool {
+        !matches!(self.max_attempts, Some(max) if attempt >= max)
+    }
+
+    /// Notify the give-up hook.
+    pub(crate) fn give_up(&self, err: &BybitError) {
+        if let Some(hook) = &self.on_give_up {
+            hook(err);
+        }
+    }
+
+    /// The delay before the `attempt`-th (starting from 1) attempt.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let exp = self.multiplier.powi(attempt.saturating_sub(1) as i32);
+        let delay = self
+            .initial_delay
+            .mul_f64(exp.min(u32::MAX as f64))
+            .min(self.max_delay);
+        delay.mul_f64(1.0 - self.jitter * random())
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ReconnectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconnectPolicy")
+            .field("initial_delay", &self.initial_delay)
+            .field("max_delay", &self.max_delay)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .field("max_attempts", &self.max_attempts)
+            .finish()
+    }
+}
+
+/// A random number in [0, 1).
+///
+/// `RandomState` is seeded randomly per instance, which is good enough for jitter.
+fn random() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let policy = ReconnectPolicy::new()
+            .initial_delay(Duration::from_secs(1))
+            .max_delay(Duration::from_secs(10))
+            .jitter(0.0);
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(100), Duration::from_secs(10));
+
+        let policy = policy.jitter(0.5);
+        for attempt in 1..10 {
+            let delay = policy.delay(attempt);
+            assert!(delay <= Duration::from_secs(10));
+            assert!(delay >= Duration::from_millis(500));
+        }
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        let policy = ReconnectPolicy::new().max_attempts(2);
+        assert!(policy.allows(1));
+        assert!(!policy.allows(2));
+        assert!(ReconnectPolicy::new().allows(u32::MAX));
+    }
+}
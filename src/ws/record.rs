@@ -0,0 +1,321 @@
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::callback::{Arg, Callback};
+use super::event::{Event, ParseFailures};
+use super::{callback_sink, Options};
+use crate::error::Result;
+use crate::util::millis;
+
+/// The interval to flush recorded frames at.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The magic number of zstd frames.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// A text frame in a recording.
+///
+/// Recordings are JSON lines, e.g., `{"ts":1672304486868,"frame":"{\"topic\":...}"}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedFrame {
+    /// The local time (ms) the frame was received at.
+    pub ts: u64,
+    /// The raw text of the frame.
+    pub frame: String,
+}
+
+/// A recorder which writes every text frame received by a client to a recording,
+/// together with the local receive time.
+///
+/// Set it with `record()` of a client builder. Frames are buffered, and flushed when a frame
+/// is recorded a second or more after the last flush, on [`flush`](Self::flush), and when the
+/// last clone of the recorder is dropped. Clones share the same recording.
+#[derive(Clone)]
+pub struct Recorder {
+    writer: Arc<Mutex<Writer>>,
+}
+
+struct Writer {
+    inner: BufWriter<Box<dyn Write + Send>>,
+    flushed: Instant,
+}
+
+impl Recorder {
+    /// Record to any writer.
+    pub fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Writer {
+                inner: BufWriter::new(Box::new(writer)),
+                flushed: Instant::now(),
+            })),
+        }
+    }
+
+    /// Record to a file at `path`, which is truncated if it exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(File::create(path)?))
+    }
+
+    /// Record to a zstd-compressed file at `path`, which is truncated if it exists.
+    /// Requires the `zstd` feature.
+    ///
+    /// The compressed stream is finished when the last clone of the recorder is dropped.
+    #[cfg(feature = "zstd")]
+    pub fn create_zstd<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let encoder = zstd::stream::write::Encoder::new(File::create(path)?, 0)?;
+        Ok(Self::new(encoder.auto_finish()))
+    }
+
+    /// Record a frame received now. Errors are logged, so they never break the connection.
+    pub(crate) fn record(&self, frame: &str) {
+        let line = serde_json::to_string(&RecordedFrame {
+            ts: millis() as u64,
+            frame: frame.to_string(),
+        })
+        .unwrap();
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let mut result = writeln!(writer.inner, "{}", line);
+        if result.is_ok() && writer.flushed.elapsed() >= FLUSH_INTERVAL {
+            writer.flushed = Instant::now();
+            result = writer.inner.flush();
+        }
+        if let Err(e) = result {
+            error!("Failed to record frame: {}", e);
+        }
+    }
+
+    /// Flush the frames recorded so far.
+    pub fn flush(&self) -> Result<()> {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.flushed = Instant::now();
+        Ok(writer.inner.flush()?)
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        if let Err(e) = self.inner.flush() {
+            error!("Failed to flush recording: {}", e);
+        }
+    }
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder").finish_non_exhaustive()
+    }
+}
+
+/// The pace to replay a recording at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    /// The original intervals between frames.
+    Original,
+    /// The original intervals divided by the factor, e.g., `10.0` is 10 times faster.
+    Factor(f64),
+    /// As fast as possible.
+    Max,
+}
+
+/// A replayer which feeds a recording to a callback, as if the frames were received by a client.
+///
+/// `A` is the kind of responses in the recording, e.g., `FuturePublicResponseArg` for a linear
+/// or inverse channel. Frames go through the same deserialization and callback path as `run`
+/// of the clients: frames which fail to parse are logged and skipped.
+pub struct Replayer<A> {
+    reader: Box<dyn BufRead + Send>,
+    speed: Speed,
+    options: Options,
+    arg: PhantomData<fn() -> A>,
+}
+
+impl<A: Arg> Replayer<A> {
+    /// Replay from any reader of a recording which is not compressed.
+    pub fn new<R: Read + Send + 'static>(reader: R) -> Self {
+        Self {
+            reader: Box::new(BufReader::new(reader)),
+            speed: Speed::Original,
+            options: Options::default(),
+            arg: PhantomData,
+        }
+    }
+
+    /// Replay a recording file at `path`. Compressed recordings require the `zstd` feature.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        if reader.fill_buf()?.starts_with(&ZSTD_MAGIC) {
+            return Self::zstd(reader);
+        }
+        Ok(Self::new(reader))
+    }
+
+    #[cfg(feature = "zstd")]
+    fn zstd(reader: BufReader<File>) -> Result<Self> {
+        Ok(Self::new(zstd::stream::read::Decoder::with_buffer(reader)?))
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    fn zstd(_: BufReader<File>) -> Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "the recording is compressed, enable the `zstd` feature to replay it",
+        ))?
+    }
+
+    /// Set the pace to replay at. Defaults to [`Speed::Original`].
+    pub fn speed(mut self, speed: Speed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Set a handler which is notified of frames which fail to parse.
+    pub fn on_event<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        self.options.on_event = Some(Arc::new(handler));
+        self
+    }
+
+    /// Get the counts of replayed frames which failed to parse.
+    pub fn parse_failures(&self) -> ParseFailures {
+        self.options.parse_failures.clone()
+    }
+
+    /// Iterate over the frames of the recording without pacing.
+    pub fn frames(self) -> impl Iterator<Item = Result<RecordedFrame>> {
+        self.reader
+            .lines()
+            .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+    }
+
+    /// Feed the frames to `callback` at the pace set, until the recording ends.
+    ///
+    /// A malformed line of the recording is an error.
+    pub fn replay<C: Callback<A>>(self, callback: C) -> Result<()> {
+        let speed = self.speed;
+        let mut sink = callback_sink(callback, &self.options);
+        let mut start: Option<(Instant, u64)> = None;
+        for frame in self.frames() {
+            let frame = frame?;
+            let (started, first) = *start.get_or_insert((Instant::now(), frame.ts));
+            let elapsed = Duration::from_millis(frame.ts.saturating_sub(first));
+            let due = match speed {
+                Speed::Original => Some(elapsed),
+                Speed::Factor(factor) if factor > 0.0 => Some(elapsed.div_f64(factor)),
+                _ => None,
+            };
+            if let Some(wait) = due.and_then(|due| due.checked_sub(started.elapsed())) {
+                thread::sleep(wait);
+            }
+            sink(frame.frame);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::response::{FuturePublicResponse, FuturePublicResponseArg};
+
+    fn orderbook(u: u64) -> String {
+        format!(
+            r#"{{"topic":"orderbook.1.BTCUSDT","type":"delta","ts":1672304486868,"data":{{"s":"BTCUSDT","b":[],"a":[],"u":{},"seq":7961638724}}}}"#,
+            u
+        )
+    }
+
+    fn recording(frames: &[(u64, String)]) -> Vec<u8> {
+        let mut text = String::new();
+        for (ts, frame) in frames {
+            let frame = RecordedFrame {
+                ts: *ts,
+                frame: frame.clone(),
+            };
+            text.push_str(&serde_json::to_string(&frame).unwrap());
+            text.push('\n');
+        }
+        text.into_bytes()
+    }
+
+    fn replay(replayer: Replayer<FuturePublicResponseArg>) -> Vec<u64> {
+        let mut updates = Vec::new();
+        replayer
+            .replay(|res: FuturePublicResponse| {
+                if let FuturePublicResponse::Orderbook(res) = res {
+                    updates.push(res.data.u);
+                }
+            })
+            .unwrap();
+        updates
+    }
+
+    #[test]
+    fn test_record() {
+        let path = std::env::temp_dir().join(format!("bybit-record-{}.jsonl", millis()));
+        let recorder = Recorder::create(&path).unwrap();
+        recorder.clone().record(&orderbook(1));
+        recorder.record("not json");
+        recorder.record(&orderbook(2));
+        recorder.flush().unwrap();
+
+        let frames: Vec<_> = Replayer::<FuturePublicResponseArg>::open(&path)
+            .unwrap()
+            .frames()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[1].frame, "not json");
+        assert!(frames[0].ts <= frames[2].ts);
+        drop(recorder);
+
+        // Frames which fail to parse are skipped and counted, as they are by clients.
+        let replayer = Replayer::open(&path).unwrap().speed(Speed::Max);
+        let failures = replayer.parse_failures();
+        assert_eq!(replay(replayer), vec![1, 2]);
+        assert_eq!(failures.get(""), 1);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_speed() {
+        let data = recording(&[(1000, orderbook(1)), (1200, orderbook(2))]);
+        let started = Instant::now();
+        let replayer = Replayer::new(std::io::Cursor::new(data.clone())).speed(Speed::Factor(4.0));
+        assert_eq!(replay(replayer), vec![1, 2]);
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_millis(200));
+
+        let started = Instant::now();
+        replay(Replayer::new(std::io::Cursor::new(data)));
+        assert!(started.elapsed() >= Duration::from_millis(200));
+
+        let replayer = Replayer::<FuturePublicResponseArg>::new(std::io::Cursor::new("{"));
+        assert!(replayer.replay(|_: FuturePublicResponse| {}).is_err());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd() {
+        let path = std::env::temp_dir().join(format!("bybit-record-{}.jsonl.zst", millis()));
+        let recorder = Recorder::create_zstd(&path).unwrap();
+        recorder.record(&orderbook(1));
+        recorder.record(&orderbook(2));
+        drop(recorder);
+
+        let replayer = Replayer::open(&path).unwrap().speed(Speed::Max);
+        assert_eq!(replay(replayer), vec![1, 2]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
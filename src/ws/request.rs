@@ -0,0 +1,184 @@
+use log::*;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::event::Event;
+use super::response::OptionSubscriptionData;
+use super::{Credentials, Options};
+use crate::error::{BybitError, Result};
+use crate::util::{millis, sign};
+
+static REQ_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Generate a `req_id` unique in the process.
+pub(super) fn next_req_id() -> String {
+    REQ_ID.fetch_add(1, Ordering::Relaxed).to_string()
+}
+
+#[derive(Serialize)]
+struct Op<'a> {
+    req_id: &'a str,
+    op: &'a str,
+    args: &'a [String],
+}
+
+/// The reply of an op request.
+///
+/// Public and private channels reply with `success`, while the trade channel replies with `retCode`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Reply<'a> {
+    success: Option<bool>,
+    ret_code: Option<i64>,
+    #[serde(default, alias = "ret_msg")]
+    ret_msg: &'a str,
+    #[serde(alias = "req_id")]
+    req_id: Option<&'a str>,
+    op: Option<&'a str>,
+    #[serde(borrow)]
+    data: Option<ReplyData<'a>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReplyData<'a> {
+    /// Option channels reply subscriptions with the failed topics instead.
+    #[serde(borrow)]
+    Subscription(OptionSubscriptionData<'a>),
+    Other(IgnoredAny),
+}
+
+struct Request {
+    op: &'static str,
+    args: Vec<String>,
+}
+
+/// The requests sent on a connection which are waiting for replies.
+#[derive(Default)]
+pub(super) struct Requests {
+    pending: HashMap<String, Request>,
+    confirmed: bool,
+    authenticated: bool,
+}
+
+impl Requests {
+    /// Create an auth request.
+    pub(super) fn auth(&mut self, credentials: &Credentials) -> String {
+        let expires = millis() + 10000;
+        let val = format!("GET/realtime{}", expires);
+        let signature = sign(&credentials.secret, &val);
+        let args = [credentials.api_key.clone(), expires.to_string(), signature];
+        // Do not keep the signature around.
+        self.create("auth", &args, Vec::new())
+    }
+
+    /// Create an op request, e.g., `subscribe`, with a generated `req_id`.
+    pub(super) fn op(&mut self, op: &'static str, args: Vec<String>) -> String {
+        self.create(op, &args.clone(), args)
+    }
+
+    fn create(&mut self, op: &'static str, args: &[String], kept: Vec<String>) -> String {
+        let req_id = next_req_id();
+        let req = serde_json::to_string(&Op {
+            req_id: &req_id,
+            op,
+            args,
+        })
+        .unwrap();
+        self.pending.insert(req_id, Request { op, args: kept });
+        req
+    }
+
+    /// Track an order request sent on the trade channel, which is framed by the sender.
+    pub(super) fn track(&mut self, req_id: String, op: &'static str) {
+        self.pending.insert(
+            req_id.clone(),
+            Request {
+                op,
+                args: vec![req_id],
+            },
+        );
+    }
+
+    /// Whether any request succeeded, e.g., `auth`, `subscribe` or `ping`,
+    /// which proves the connection healthy.
+    pub(super) fn confirmed(&self) -> bool {
+        self.confirmed
+    }
+
+    /// Whether the `auth` request succeeded. Orders are only sent once it has,
+    /// as they would be signed with a stale timestamp if queued until then.
+    pub(super) fn authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    /// Correlate a received frame with the pending requests if it is a reply.
+    ///
+    /// A rejected authentication is an error. Other rejected requests are notified
+    /// as [`Event::Rejected`].
+    pub(super) fn reply(&mut self, content: &str, options: &Options) -> Result<()> {
+        // Data frames always have a topic. Skip them without parsing.
+        if content.contains("\"topic\"") {
+            return Ok(());
+        }
+        let reply: Reply = match serde_json::from_str(content) {
+            Ok(reply) => reply,
+            Err(_) => return Ok(()),
+        };
+        // Option channels reply pings without `success`.
+        if reply.op == Some("pong") {
+            self.confirmed = true;
+        }
+        let Some(success) = reply.success.or(reply.ret_code.map(|code| code == 0)) else {
+            return Ok(());
+        };
+        if success {
+            self.confirmed = true;
+        }
+        let request = match reply.req_id.and_then(|req_id| self.pending.remove(req_id)) {
+            // The trade channel does not echo the `req_id` of auth requests.
+            None if reply.op == Some("auth") => Some(Request {
+                op: "auth",
+                args: Vec::new(),
+            }),
+            request => request,
+        };
+
+        if let Some(ReplyData::Subscription(data)) = reply.data {
+            if !data.fail_topics.is_empty() {
+                warn!("Failed to subscribe: {:?}", data.fail_topics);
+                options.notify(Event::Rejected {
+                    op: "subscribe".to_string(),
+                    args: data.fail_topics.iter().map(|t| t.to_string()).collect(),
+                    reason: reply.ret_msg.to_string(),
+                });
+            }
+            return Ok(());
+        }
+
+        if success {
+            if let Some(Request { op: "auth", .. }) = request {
+                self.authenticated = true;
+            }
+            return Ok(());
+        }
+        match request {
+            Some(Request { op: "auth", .. }) => Err(BybitError::AuthFailed(reply.ret_msg.into())),
+            Some(Request { op, args }) => {
+                warn!("Failed to {} {:?}: {}", op, args, reply.ret_msg);
+                options.notify(Event::Rejected {
+                    op: op.to_string(),
+                    args,
+                    reason: reply.ret_msg.to_string(),
+                });
+                Ok(())
+            }
+            None => {
+                warn!("Request failed: {}", reply.ret_msg);
+                Ok(())
+            }
+        }
+    }
+}
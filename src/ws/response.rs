@@ -1,59 +1,126 @@
+pub(crate) mod de;
+
 use super::callback::Arg;
-use serde::Deserialize;
+use crate::enums::*;
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+/// A number, e.g., price, size or balance.
+///
+/// It is the string sent by Bybit, or a `Decimal` if the `decimal` feature is enabled.
+#[cfg(not(feature = "decimal"))]
+pub type Number<'a> = Cow<'a, str>;
+/// A number, e.g., price, size or balance.
+///
+/// It is the string sent by Bybit, or a `Decimal` if the `decimal` feature is enabled.
+#[cfg(feature = "decimal")]
+pub type Number<'a> = rust_decimal::Decimal;
+
+/// A number which Bybit may send as an empty string, e.g., the average price of an unfilled order.
+///
+/// It is the string sent by Bybit, or an `Option<Decimal>` which is `None` for an empty string
+/// if the `decimal` feature is enabled.
+#[cfg(not(feature = "decimal"))]
+pub type OptionalNumber<'a> = Cow<'a, str>;
+/// A number which Bybit may send as an empty string, e.g., the average price of an unfilled order.
+///
+/// It is the string sent by Bybit, or an `Option<Decimal>` which is `None` for an empty string
+/// if the `decimal` feature is enabled.
+#[cfg(feature = "decimal")]
+pub type OptionalNumber<'a> = Option<rust_decimal::Decimal>;
+
+/// A timestamp (ms) which Bybit sends as a string.
+///
+/// It is the string sent by Bybit, or a `u64` if the `decimal` feature is enabled.
+#[cfg(not(feature = "decimal"))]
+pub type Timestamp<'a> = Cow<'a, str>;
+/// A timestamp (ms) which Bybit sends as a string.
+///
+/// It is the string sent by Bybit, or a `u64` if the `decimal` feature is enabled.
+#[cfg(feature = "decimal")]
+pub type Timestamp<'a> = u64;
+
+/// Get the milliseconds of a [`Timestamp`]. A malformed one is `0`.
+#[cfg(not(feature = "decimal"))]
+pub fn timestamp_millis(ts: &Timestamp) -> u64 {
+    ts.parse().unwrap_or(0)
+}
+
+/// Get the milliseconds of a [`Timestamp`]. A malformed one is `0`.
+#[cfg(feature = "decimal")]
+pub fn timestamp_millis(ts: &Timestamp) -> u64 {
+    *ts
+}
 
 /// The pong/subscription response.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct OpResponse<'a> {
     pub success: bool,
-    pub ret_msg: &'a str,
-    pub conn_id: &'a str,
-    pub req_id: Option<&'a str>,
-    pub op: &'a str,
+    #[serde(borrow)]
+    pub ret_msg: Cow<'a, str>,
+    #[serde(borrow)]
+    pub conn_id: Cow<'a, str>,
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub req_id: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub op: Cow<'a, str>,
 }
 
 /// The option pong response of public channels.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct OptionPongResponse<'a> {
-    pub args: [&'a str; 1],
-    pub op: &'a str,
+    #[serde(borrow, deserialize_with = "de::array_cow")]
+    pub args: [Cow<'a, str>; 1],
+    #[serde(borrow)]
+    pub op: Cow<'a, str>,
 }
 
 /// The data in option subscription response.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OptionSubscriptionData<'a> {
-    #[serde(borrow)]
-    pub fail_topics: Vec<&'a str>,
-    pub success_topics: Vec<&'a str>,
+    #[serde(borrow, deserialize_with = "de::vec_cow")]
+    pub fail_topics: Vec<Cow<'a, str>>,
+    #[serde(borrow, deserialize_with = "de::vec_cow")]
+    pub success_topics: Vec<Cow<'a, str>>,
 }
 
 /// The option subscription response.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct OptionSubscriptionResponse<'a> {
     pub success: bool,
-    pub conn_id: &'a str,
+    #[serde(borrow)]
+    pub conn_id: Cow<'a, str>,
+    #[serde(borrow)]
     pub data: OptionSubscriptionData<'a>,
     #[serde(alias = "type")]
-    pub type_: &'a str,
+    #[serde(borrow)]
+    pub type_: Cow<'a, str>,
 }
 
 /// The pong response of private channels.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct PrivatePongResponse<'a> {
-    pub req_id: Option<&'a str>,
-    pub op: &'a str,
-    pub args: [&'a str; 1],
-    pub conn_id: &'a str,
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub req_id: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub op: Cow<'a, str>,
+    #[serde(borrow, deserialize_with = "de::array_cow")]
+    pub args: [Cow<'a, str>; 1],
+    #[serde(borrow)]
+    pub conn_id: Cow<'a, str>,
 }
 
 /// The base response which contains common fields of public channels.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct BasePublicResponse<'a, Data> {
     /// Topic name.
-    pub topic: &'a str,
+    #[serde(borrow)]
+    pub topic: Cow<'a, str>,
     /// Data type. `snapshot`, `delta`.
     #[serde(alias = "type")]
-    pub type_: &'a str,
+    pub type_: DataType,
     /// The timestamp (ms) that the system generates the data.
     pub ts: u64,
     /// The data vary on the topic.
@@ -61,13 +128,14 @@ pub struct BasePublicResponse<'a, Data> {
 }
 
 /// The base ticker response which contains common fields.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct BaseTickerPublicResponse<'a, Data> {
     /// Topic name.
-    pub topic: &'a str,
+    #[serde(borrow)]
+    pub topic: Cow<'a, str>,
     /// Data type. `snapshot`, `delta`.
     #[serde(alias = "type")]
-    pub type_: &'a str,
+    pub type_: DataType,
     /// Cross sequence.
     pub cs: u64,
     /// The timestamp (ms) that the system generates the data.
@@ -76,15 +144,17 @@ pub struct BaseTickerPublicResponse<'a, Data> {
     pub data: Data,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct BaseOptionPublicResponse<'a, Data> {
     /// message ID
-    pub id: &'a str,
+    #[serde(borrow)]
+    pub id: Cow<'a, str>,
     /// Topic name.
-    pub topic: &'a str,
-    #[serde(alias = "type")]
+    #[serde(borrow)]
+    pub topic: Cow<'a, str>,
     /// Data type. `snapshot`.
-    pub type_: &'a str,
+    #[serde(alias = "type")]
+    pub type_: DataType,
     /// The timestamp (ms) that the system generates the data.
     pub ts: u64,
     /// The data vary on the topic.
@@ -92,31 +162,49 @@ pub struct BaseOptionPublicResponse<'a, Data> {
 }
 
 /// The base response which contains common fields of private channels.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BasePrivateResponse<'a, Data> {
     /// Message ID.
-    pub id: &'a str,
+    #[serde(borrow)]
+    pub id: Cow<'a, str>,
     /// Topic name.
-    pub topic: &'a str,
+    #[serde(borrow)]
+    pub topic: Cow<'a, str>,
     /// Data created timestamp (ms).
     pub creation_time: u64,
     /// The data vary on the topic.
     pub data: Data,
 }
 
-/// The (price, size) pair of orderbook.
-#[derive(Deserialize, Debug)]
-pub struct OrderbookItem<'a>(pub &'a str, pub &'a str);
+/// The (price, size) pair of orderbook, sent as an array of two numbers.
+///
+/// The shape is the same with or without the `decimal` feature. Decimals do not borrow
+/// from the frame, so a marker keeps the lifetime. Match it with
+/// `OrderbookItem { price, size, .. }`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct OrderbookItem<'a> {
+    #[cfg_attr(not(feature = "decimal"), serde(borrow))]
+    #[serde(deserialize_with = "de::number")]
+    pub price: Number<'a>,
+    #[cfg_attr(not(feature = "decimal"), serde(borrow))]
+    #[serde(deserialize_with = "de::number")]
+    pub size: Number<'a>,
+    #[serde(skip)]
+    marker: PhantomData<&'a ()>,
+}
 
 /// The orderbook data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Orderbook<'a> {
     /// Symbol name.
-    pub s: &'a str,
+    #[serde(borrow)]
+    pub s: Cow<'a, str>,
     /// Bids. For `snapshot` stream, the element is sorted by price in descending order.
+    #[serde(borrow)]
     pub b: Vec<OrderbookItem<'a>>,
     /// Asks. For `snapshot` stream, the element is sorted by price in ascending order.
+    #[serde(borrow)]
     pub a: Vec<OrderbookItem<'a>>,
     /// Update ID. Is a sequence.
     /// Occasionally, you'll receive "u"=1, which is a snapshot data due to the restart of the service.
@@ -128,183 +216,253 @@ pub struct Orderbook<'a> {
 
 /// The trade data.
 #[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Trade<'a> {
     /// The timestamp (ms) that the order is filled.
     pub T: u64,
     /// Symbol name.
-    pub s: &'a str,
+    #[serde(borrow)]
+    pub s: Cow<'a, str>,
     /// Side. `Buy`, `Sell`.
-    pub S: &'a str,
+    pub S: Side,
     /// Trade size.
-    pub v: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub v: Number<'a>,
     /// Trade price.
-    pub p: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub p: Number<'a>,
     /// Direction of price change. Unique field for future.
-    pub L: Option<&'a str>,
+    #[serde(default)]
+    pub L: Option<TickDirection>,
     /// Trade ID.
-    pub i: &'a str,
+    #[serde(borrow)]
+    pub i: Cow<'a, str>,
     /// Whether it is a block trade order or not.
     pub BT: bool,
 }
 
 /// The spot ticker data. (`snapshot` only)
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SpotTicker<'a> {
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Last price.
-    pub last_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub last_price: Number<'a>,
     /// The highest price in the last 24 hours.
-    pub high_price_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub high_price_24h: Number<'a>,
     /// The lowest price in the last 24 hours.
-    pub low_price_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub low_price_24h: Number<'a>,
     /// Percentage change of market price relative to 24h.
-    pub prev_price_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub prev_price_24h: Number<'a>,
     /// Volume for 24h.
-    pub volume_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub volume_24h: Number<'a>,
     /// Turnover for 24h.
-    pub turnover_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub turnover_24h: Number<'a>,
     /// Percentage change of market price relative to 24h.
-    pub price_24h_pcnt: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub price_24h_pcnt: Number<'a>,
     /// USD index price. It can be empty.
-    pub usd_index_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub usd_index_price: OptionalNumber<'a>,
 }
 
 /// The option ticker data. (`snapshot` only)
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OptionTicker<'a> {
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Best bid price.
-    pub bid_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub bid_price: Number<'a>,
     /// Best bid size.
-    pub bid_size: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub bid_size: Number<'a>,
     /// Best bid iv.
-    pub bid_iv: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub bid_iv: Number<'a>,
     /// Best ask price.
-    pub ask_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub ask_price: Number<'a>,
     /// Best ask size.
-    pub ask_size: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub ask_size: Number<'a>,
     /// Best ask iv.
-    pub ask_iv: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub ask_iv: Number<'a>,
     /// Last price.
-    pub last_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub last_price: Number<'a>,
     /// The highest price in the last 24 hours.
-    pub high_price_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub high_price_24h: Number<'a>,
     /// The lowest price in the last 24 hours.
-    pub low_price_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub low_price_24h: Number<'a>,
     /// Market price.
-    pub mark_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub mark_price: Number<'a>,
     /// Index price.
-    pub index_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub index_price: Number<'a>,
     /// Mark price iv.
-    pub mark_price_iv: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub mark_price_iv: Number<'a>,
     /// Underlying price.
-    pub underlying_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub underlying_price: Number<'a>,
     /// Open interest size.
-    pub open_interest: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub open_interest: Number<'a>,
     /// Turnover for 24h.
-    pub turnover_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub turnover_24h: Number<'a>,
     /// Volume for 24h.
-    pub volume_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub volume_24h: Number<'a>,
     /// Total volume.
-    pub total_volume: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub total_volume: Number<'a>,
     /// Total turnover.
-    pub total_turnover: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub total_turnover: Number<'a>,
     /// Delta.
-    pub delta: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub delta: Number<'a>,
     /// Gamma.
-    pub gamma: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub gamma: Number<'a>,
     /// Vega.
-    pub vega: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub vega: Number<'a>,
     /// Theta.
-    pub theta: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub theta: Number<'a>,
     /// Predicated delivery price. It has value when 30 min before delivery.
-    pub predicted_delivery_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub predicted_delivery_price: OptionalNumber<'a>,
     /// The change in the last 24 hous.
-    pub change_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub change_24h: Number<'a>,
 }
 
 /// The future ticker data.
 ///
 /// This data utilises the snapshot field and delta field. `None` means field value has not changed.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FutureTicker<'a> {
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Tick direction.
-    pub tick_direction: Option<&'a str>,
+    #[serde(default)]
+    pub tick_direction: Option<TickDirection>,
     /// Percentage change of market price in the last 24 hours.
-    pub price_24h_pcnt: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub price_24h_pcnt: Option<Number<'a>>,
     /// Last price.
-    pub last_price: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub last_price: Option<Number<'a>>,
     /// Market price 24 hours ago.
-    pub prev_price_24h: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub prev_price_24h: Option<Number<'a>>,
     /// The highest price in the last 24 hours.
-    pub high_price_24h: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub high_price_24h: Option<Number<'a>>,
     /// The lowest price in the last 24 hours.
-    pub low_price_24h: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub low_price_24h: Option<Number<'a>>,
     /// Market price an hour ago.
-    pub prev_price_1h: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub prev_price_1h: Option<Number<'a>>,
     /// Mark price.
-    pub mark_price: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub mark_price: Option<Number<'a>>,
     /// Index price.
-    pub index_price: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub index_price: Option<Number<'a>>,
     /// Open interest size.
-    pub open_interest: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub open_interest: Option<Number<'a>>,
     /// Open interest value.
-    pub open_interest_value: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub open_interest_value: Option<Number<'a>>,
     /// Turnover for 24h.
-    pub turnover_24h: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub turnover_24h: Option<Number<'a>>,
     /// Volume for 24h.
-    pub volume_24h: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub volume_24h: Option<Number<'a>>,
     /// Next funding timestamp (ms).
-    pub next_funding_time: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub next_funding_time: Option<Cow<'a, str>>,
     /// Funding rate.
-    pub funding_rate: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub funding_rate: Option<Number<'a>>,
     /// Best bid price.
-    pub bid1_price: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub bid1_price: Option<Number<'a>>,
     /// Best bid size.
-    pub bid1_size: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub bid1_size: Option<Number<'a>>,
     /// Best ask price.
-    pub ask1_price: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub ask1_price: Option<Number<'a>>,
     /// Best ask size.
-    pub ask1_size: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub ask1_size: Option<Number<'a>>,
     /// Delivery date time (UTC+0). Unique field for inverse futures.
-    pub delivery_time: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub delivery_time: Option<Cow<'a, str>>,
     /// Delivery fee rate. Unique field for inverse futures.
-    pub basis_rate: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub basis_rate: Option<Number<'a>>,
     /// Delivery fee rate. Unique field for inverse futures.
-    pub delivery_fee_rate: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub delivery_fee_rate: Option<Number<'a>>,
     /// Predicated delivery price. Unique field for inverse futures.
-    pub predicted_delivery_price: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub predicted_delivery_price: Option<Number<'a>>,
 }
 
 /// The (leveraged token) kline data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Kline<'a> {
     /// The start timestamp (ms)
     pub start: u64,
     /// The end timestamp (ms). It is current timestamp if it does not reach to the end time of candle.
     pub end: u64,
     /// Kline interval.
-    pub interval: &'a str,
+    #[serde(borrow)]
+    pub interval: Cow<'a, str>,
     /// Open price.
-    pub open: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub open: Number<'a>,
     /// Close price.
-    pub close: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub close: Number<'a>,
     /// Highest price.
-    pub high: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub high: Number<'a>,
     /// Lowest price.
-    pub low: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub low: Number<'a>,
     /// Trade volume. Leveraged token does not have this field.
-    pub volume: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub volume: Option<Number<'a>>,
     /// Turnover. Leveraged token does not have this field.
-    pub turnover: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub turnover: Option<Number<'a>>,
     /// Weather the tick is ended or not.
     pub confirm: bool,
     /// The timestamp (ms) of the last matched order in the candle.
@@ -312,75 +470,96 @@ pub struct Kline<'a> {
 }
 
 /// The liquidation data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Liquidation<'a> {
     /// The updated timestamp (ms).
     pub updated_time: u64,
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Order side. `Buy`, `Sell`.
-    pub side: &'a str,
+    pub side: Side,
     /// Executed size.
-    pub size: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub size: Number<'a>,
     /// Executed price.
-    pub price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub price: Number<'a>,
 }
 
 // The leveraged token ticker data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LtTicker<'a> {
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Market price change percentage in the past 24 hours.
-    pub price_24h_pcnt: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub price_24h_pcnt: Number<'a>,
     /// The last price.
-    pub last_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub last_price: Number<'a>,
     /// Market price 24 hours ago.
-    pub prev_price_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub prev_price_24h: Number<'a>,
     /// Highest price in the past 24 hours.
-    pub high_price_24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub high_price_24h: Number<'a>,
     /// Lowest price in the past 24 hours.
-    pub low_price24h: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub low_price24h: Number<'a>,
 }
 
 /// The leveraged token nav data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LtNav<'a> {
     /// The generated timestamp of nav.
     pub time: u64,
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Net asset value.
-    pub nav: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub nav: Number<'a>,
     /// Total position value = basket value * total circulation.
-    pub basket_position: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub basket_position: Number<'a>,
     /// Leverage.
-    pub leverage: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub leverage: Number<'a>,
     /// Basket loan.
-    pub basket_loan: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub basket_loan: Number<'a>,
     /// Circulation.
-    pub circulation: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub circulation: Number<'a>,
     /// Basket.
-    pub basket: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub basket: Number<'a>,
 }
 
 /// The position data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Position<'a> {
     /// Product type.
     /// - Unified account: does not have this field.
     /// - Normal account: `linear`, `inverse`.
-    pub category: Option<&'a str>,
+    ///
+    /// REST responses do not have this field. The REST client fills it with the requested category.
+    #[serde(default)]
+    pub category: Option<Category>,
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Position side: `Buy`, `Sell`.
-    pub side: &'a str,
+    pub side: Side,
     /// Position size.
-    pub size: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub size: Number<'a>,
     /// Used to identify positions in different position modes.
     /// - 0 one-way mode position.
     /// - 1 Buy side of hedge-mode position.
@@ -389,298 +568,390 @@ pub struct Position<'a> {
     /// Trade mode. 0: cross margin, 1: isolated margin. Always 0 under unified margin account.
     pub trade_mode: u8,
     /// Position value.
-    pub position_value: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub position_value: Number<'a>,
     /// Risk limit ID.
     /// _Note_: for portfolio margin mode, it returns 0, which the risk limit value is invalid.
     pub risk_id: u16,
     /// Risk limit value corresponding to riskId.
     /// _Note_: for portfolio margin mode, it returns "", which the risk limit value is invalid.
-    pub risk_limit_value: &'a str,
-    /// Entry price.
-    pub entry_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub risk_limit_value: OptionalNumber<'a>,
+    /// Entry price. REST responses name it `avgPrice`.
+    #[serde(alias = "avgPrice")]
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub entry_price: Number<'a>,
     /// Mark price
-    pub mark_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub mark_price: Number<'a>,
     /// Leverage.
     /// _Note_: for portfolio margin mode, it returns "", which the leverage value is invalid.
-    pub leverage: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub leverage: OptionalNumber<'a>,
     /// Position margin. Unified account does not have this field.
-    pub position_balance: Option<&'a str>,
+    #[serde(borrow, default, deserialize_with = "de::option_number")]
+    pub position_balance: Option<Number<'a>>,
     /// Whether to add margin automatically. 0: false, 1: true. Unified account does not have this field.
     pub auto_add_margin: Option<u8>,
     /// Position maintenance margin.
     /// _Note_: for portfolio margin mode, it returns "".
     #[serde(alias = "positionMM")]
-    pub position_mm: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub position_mm: OptionalNumber<'a>,
     /// Position initial margin.
     /// _Note_: for portfolio margin mode, it returns "".
     #[serde(alias = "positionIM")]
-    pub position_im: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub position_im: OptionalNumber<'a>,
     /// Est.liquidation price. "" for Unified trade(spot/linear/options).
-    pub liq_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub liq_price: OptionalNumber<'a>,
     /// Est.bankruptcy price. "" for Unified trade(spot/linear/options).
-    pub bust_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub bust_price: OptionalNumber<'a>,
     /// Tp/Sl mode: `Full`, `Partial`.
-    pub tpsl_mode: &'a str,
+    pub tpsl_mode: TpslMode,
     /// Take profit price.
-    pub take_profit: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub take_profit: OptionalNumber<'a>,
     /// Stop loss price.
-    pub stop_loss: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub stop_loss: OptionalNumber<'a>,
     /// Trailing stop.
-    pub trailing_stop: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub trailing_stop: OptionalNumber<'a>,
     /// Unrealised profit and loss.
-    pub unrealised_pnl: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub unrealised_pnl: Number<'a>,
     /// Cumulative realised PnL.
-    pub cum_realised_pnl: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub cum_realised_pnl: Number<'a>,
     /// Position status.
     /// -`Normal`.
     /// - `Liq`: in the liquidation progress.
     /// - `Adl`: in the auto-deleverage progress.
-    pub position_status: &'a str,
+    pub position_status: PositionStatus,
     /// Position created timestamp (ms).
-    pub created_time: &'a str,
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub created_time: Timestamp<'a>,
     /// Position data updated timestamp (ms).
-    pub updated_time: &'a str,
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub updated_time: Timestamp<'a>,
 }
 
 /// The execution data.
 ///
 /// You may have multiple executions for one order in a single message.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Execution<'a> {
     /// Product type.
     /// - Unified account: `spot`, `linear`, `option`.
     /// - Normal account: `linear`, `inverse`.
-    pub category: &'a str,
+    pub category: Category,
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Whether to borrow. Valid for `spot` only.
     /// - 0 (default): false.
     /// - 1: true.
-    pub is_leverage: &'a str,
+    #[serde(borrow)]
+    pub is_leverage: Cow<'a, str>,
     /// Order ID.
-    pub order_id: &'a str,
+    #[serde(borrow)]
+    pub order_id: Cow<'a, str>,
     /// User customized order ID.
-    pub order_link_id: &'a str,
+    #[serde(borrow)]
+    pub order_link_id: Cow<'a, str>,
     /// Side. `Buy`, `Sell`.
-    pub side: &'a str,
+    pub side: Side,
     /// Order price.
-    pub order_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub order_price: OptionalNumber<'a>,
     /// Order qty.
-    pub order_qty: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub order_qty: Number<'a>,
     /// The remaining qty not executed.
-    pub leaves_qty: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub leaves_qty: Number<'a>,
     /// Order type. `Market`, `Limit`.
-    pub order_type: &'a str,
+    pub order_type: OrderType,
     /// Stop order type. If the order is not stop order, any type is not returned.
-    pub stop_order_type: &'a str,
+    pub stop_order_type: StopOrderType,
     /// Executed trading fee.
-    pub exec_fee: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub exec_fee: Number<'a>,
     /// Execution ID.
-    pub exec_id: &'a str,
+    #[serde(borrow)]
+    pub exec_id: Cow<'a, str>,
     /// Execution price.
-    pub exec_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub exec_price: Number<'a>,
     /// Execution qty.
-    pub exec_qty: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub exec_qty: Number<'a>,
     /// Executed type.
-    pub exec_type: &'a str,
+    pub exec_type: ExecType,
     /// Executed order value.
-    pub exec_value: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub exec_value: Number<'a>,
     /// Executed timestamp (ms).
-    pub exec_time: &'a str,
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub exec_time: Timestamp<'a>,
     /// Is maker order. true: maker, false: taker.
     pub is_maker: bool,
     /// Trading fee rate.
-    pub fee_rate: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub fee_rate: Number<'a>,
     /// Implied volatility. Valid for option.
-    pub trade_iv: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub trade_iv: OptionalNumber<'a>,
     /// Implied volatility of mark price. Valid for option.
-    pub mark_iv: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub mark_iv: OptionalNumber<'a>,
     /// The mark price of the symbol when executing.
-    pub mark_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub mark_price: OptionalNumber<'a>,
     /// The index price of the symbol when executing.
-    pub index_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub index_price: OptionalNumber<'a>,
     /// The underlying price of the symbol when executing. Valid for option.
-    pub underlying_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub underlying_price: OptionalNumber<'a>,
     /// Paradigm block trade ID.
-    pub block_trade_id: &'a str,
+    #[serde(borrow)]
+    pub block_trade_id: Cow<'a, str>,
+}
+
+fn unknown_category() -> Category {
+    Category::Unknown(String::new())
 }
 
 /// The order data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Order<'a> {
     /// Product type.
     /// - Unified account: `spot`, `linear`, `option`.
     /// - Normal account: `linear`, `inverse`.
-    pub category: &'a str,
+    ///
+    /// REST responses do not have this field. The REST client fills it with the requested category.
+    #[serde(default = "unknown_category")]
+    pub category: Category,
     /// Order ID.
-    pub order_id: &'a str,
+    #[serde(borrow)]
+    pub order_id: Cow<'a, str>,
     /// User customised order ID.
-    pub order_link_id: &'a str,
+    #[serde(borrow)]
+    pub order_link_id: Cow<'a, str>,
     /// Whether to borrow. `spot` returns this field only. 0 (default): false, 1: true.
-    pub is_leverage: &'a str,
+    #[serde(borrow)]
+    pub is_leverage: Cow<'a, str>,
     /// Block trade ID.
-    pub block_trade_id: &'a str,
+    #[serde(borrow)]
+    pub block_trade_id: Cow<'a, str>,
     /// Symbol name.
-    pub symbol: &'a str,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
     /// Order price.
-    pub price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub price: OptionalNumber<'a>,
     /// Order qty.
-    pub qty: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub qty: Number<'a>,
     /// Side. `Buy`, `Sell`.
-    pub side: &'a str,
+    pub side: Side,
     /// Position index. Used to identify positions in different position modes.
     pub position_idx: u8,
     /// Order status.
-    pub order_status: &'a str,
+    pub order_status: OrderStatus,
     /// Cancel type.
-    pub cancel_type: &'a str,
+    pub cancel_type: CancelType,
     /// Reject reason.
-    pub reject_reason: &'a str,
+    #[serde(borrow)]
+    pub reject_reason: Cow<'a, str>,
     /// Average filled price. If unfilled, it is "".
-    pub avg_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub avg_price: OptionalNumber<'a>,
     /// The remaining qty not executed.
-    pub leaves_qty: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub leaves_qty: OptionalNumber<'a>,
     /// The remaining value not executed.
-    pub leaves_value: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub leaves_value: OptionalNumber<'a>,
     /// Cumulative executed order qty.
-    pub cum_exec_qty: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub cum_exec_qty: OptionalNumber<'a>,
     /// Cumulative executed order value.
-    pub cum_exec_value: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub cum_exec_value: OptionalNumber<'a>,
     /// Cumulative executed trading fee.
-    pub cum_exec_fee: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub cum_exec_fee: OptionalNumber<'a>,
     /// Time in force.
-    pub time_in_force: &'a str,
+    pub time_in_force: TimeInForce,
     /// Order type. `Market`, `Limit`.
-    pub order_type: &'a str,
+    pub order_type: OrderType,
     /// Stop order type.
-    pub stop_order_type: &'a str,
+    pub stop_order_type: StopOrderType,
     /// Implied volatility.
-    pub order_iv: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub order_iv: OptionalNumber<'a>,
     /// Trigger price. If stopOrderType=TrailingStop, it is activate price. Otherwise, it is trigger price.
-    pub trigger_price: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub trigger_price: OptionalNumber<'a>,
     /// Take profit price.
-    pub take_profit: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub take_profit: OptionalNumber<'a>,
     /// Stop loss price.
-    pub stop_loss: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub stop_loss: OptionalNumber<'a>,
     /// The price type to trigger take profit.
-    pub tp_trigger_by: &'a str,
+    pub tp_trigger_by: TriggerBy,
     /// The price type to trigger stop loss.
-    pub sl_trigger_by: &'a str,
+    pub sl_trigger_by: TriggerBy,
     /// Trigger direction. 1: rise, 2: fall.
     pub trigger_direction: u8,
     /// The price type of trigger price.
-    pub trigger_by: &'a str,
+    pub trigger_by: TriggerBy,
     /// Last price when place the order. For linear only.
-    pub last_price_on_created: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub last_price_on_created: OptionalNumber<'a>,
     /// Reduce only. `true` means reduce position size.
     pub reduce_only: bool,
     /// Close on trigger.
     pub close_on_trigger: bool,
     /// Order created timestamp (ms).
-    pub created_time: &'a str,
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub created_time: Timestamp<'a>,
     /// Order updated timestamp (ms).
-    pub updated_time: &'a str,
+    #[serde(borrow, deserialize_with = "de::timestamp")]
+    pub updated_time: Timestamp<'a>,
 }
 
 /// The wallet coin data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WalletCoin<'a> {
     /// Coin name, such as BTC, ETH, USDT, USDC.
-    pub coin: &'a str,
+    #[serde(borrow)]
+    pub coin: Cow<'a, str>,
     /// Equity of current coin.
-    pub equity: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub equity: Number<'a>,
     /// USD value of current coin. If this coin cannot be collateral, then it is 0.
-    pub usd_value: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub usd_value: OptionalNumber<'a>,
     /// Wallet balance of current coin.
-    pub wallet_balance: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub wallet_balance: Number<'a>,
     /// Borrow amount of current coin.
-    pub borrow_amount: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub borrow_amount: OptionalNumber<'a>,
     /// Available amount to borrow of current coin.
-    pub available_to_borrow: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub available_to_borrow: OptionalNumber<'a>,
     /// Available amount to withdraw of current coin.
-    pub available_to_withdraw: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub available_to_withdraw: OptionalNumber<'a>,
     /// Accrued interest.
-    pub accrued_interest: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub accrued_interest: OptionalNumber<'a>,
     /// Pre-occupied margin for order. For portfolio margin mode, it returns "".
     #[serde(alias = "totalOrderIM")]
-    pub total_order_im: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_order_im: OptionalNumber<'a>,
     /// Sum of initial margin of all positions + Pre-occupied liquidation fee. For portfolio margin mode, it returns "".
     #[serde(alias = "totalPositionIM")]
-    pub total_position_im: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_position_im: OptionalNumber<'a>,
     /// Sum of maintenance margin for all positions. For portfolio margin mode, it returns "".
     #[serde(alias = "totalPositionMM")]
-    pub total_position_mm: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_position_mm: OptionalNumber<'a>,
     /// Unrealised P&L.
-    pub unrealised_pnl: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub unrealised_pnl: OptionalNumber<'a>,
     /// Cumulative Realised P&L.
-    pub cum_realised_pnl: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub cum_realised_pnl: OptionalNumber<'a>,
 }
 
 /// The wallet data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Wallet<'a> {
     /// Account type.
     /// - Unified account: UNIFIED.
     /// - Normal account: CONTRACT.
-    pub account_type: &'a str,
+    pub account_type: AccountType,
     /// Initial Margin Rate: Account Total Initial Margin Base Coin / Account Margin Balance Base Coin.
     /// In non-unified mode, the field will be returned as an empty string.
     #[serde(alias = "accountIMRate")]
-    pub account_im_rate: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub account_im_rate: OptionalNumber<'a>,
     /// Maintenance Margin Rate: Account Total Maintenance Margin Base Coin / Account Margin Balance Base Coin.
     /// In non-unified mode, the field will be returned as an empty string.
     #[serde(alias = "accountMMRate")]
-    pub account_mm_rate: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub account_mm_rate: OptionalNumber<'a>,
     /// Equity of account converted to usd：Account Margin Balance Base Coin + Account Option Value Base Coin.
     /// In non-unified mode, the field will be returned as an empty string.
-    pub total_equity: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_equity: OptionalNumber<'a>,
     /// Wallet Balance of account converted to usd：∑ Asset Wallet Balance By USD value of each asset.
     /// In non-unified mode, the field will be returned as an empty string.
-    pub total_wallet_balance: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_wallet_balance: OptionalNumber<'a>,
     /// Margin Balance of account converted to usd：totalWalletBalance + totalPerpUPL.
     /// In non-unified mode, the field will be returned as an empty string.
-    pub total_margin_balance: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_margin_balance: OptionalNumber<'a>,
     /// Available Balance of account converted to usd：Regular mode：totalMarginBalance - totalInitialMargin.
     /// In non-unified mode, the field will be returned as an empty string.
-    pub total_available_balance: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_available_balance: OptionalNumber<'a>,
     /// Unrealised P&L of perpetuals of account converted to usd：∑ Each perp upl by base coin.
     /// In non-unified mode, the field will be returned as an empty string.
     #[serde(alias = "totalPerpUPL")]
-    pub total_perp_upl: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_perp_upl: OptionalNumber<'a>,
     /// Initial Margin of account converted to usd：∑ Asset Total Initial Margin Base Coin.
     /// In non-unified mode, the field will be returned as an empty string.
-    pub total_initial_margin: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_initial_margin: OptionalNumber<'a>,
     /// Maintenance Margin of account converted to usd: ∑ Asset Total Maintenance Margin Base Coin.
     /// In non-unified mode, the field will be returned as an empty string.
-    pub total_maintenance_margin: &'a str,
+    #[serde(borrow, deserialize_with = "de::optional_number")]
+    pub total_maintenance_margin: OptionalNumber<'a>,
     /// Coin.
+    #[serde(borrow)]
     pub coin: Vec<WalletCoin<'a>>,
 }
 
 /// The greeks data.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Greek<'a> {
     /// Base coin.
-    pub base_coin: &'a str,
+    #[serde(borrow)]
+    pub base_coin: Cow<'a, str>,
     /// Delta value.
-    pub total_delta: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub total_delta: Number<'a>,
     /// Gamma value.
-    pub total_gamma: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub total_gamma: Number<'a>,
     /// Vega value.
-    pub total_vega: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub total_vega: Number<'a>,
     /// Theta value.
-    pub total_theta: &'a str,
+    #[serde(borrow, deserialize_with = "de::number")]
+    pub total_theta: Number<'a>,
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(untagged)]
+#[derive(Debug, Clone)]
 pub enum SpotPublicResponse<'a> {
-    #[serde(borrow)]
     Orderbook(BasePublicResponse<'a, Orderbook<'a>>),
     Trade(BasePublicResponse<'a, Vec<Trade<'a>>>),
     Ticker(BaseTickerPublicResponse<'a, SpotTicker<'a>>),
@@ -690,15 +961,47 @@ pub enum SpotPublicResponse<'a> {
     Op(OpResponse<'a>),
 }
 
+impl<'de: 'a, 'a> Deserialize<'de> for SpotPublicResponse<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        de::dispatch(deserializer)
+    }
+}
+
+impl<'de: 'a, 'a> de::Dispatch<'de> for SpotPublicResponse<'a> {
+    fn dispatch<S: de::Source<'de>>(tag: de::Tag<'de>, source: S) -> Result<Self, S::Error> {
+        Ok(match tag.topic() {
+            Some("orderbook") => Self::Orderbook(source.parse()?),
+            Some("publicTrade") => Self::Trade(source.parse()?),
+            Some("tickers") => Self::Ticker(source.parse()?),
+            Some("kline" | "kline_lt") => Self::Kline(source.parse()?),
+            Some("tickers_lt") => Self::LtTicker(source.parse()?),
+            Some("lt") => Self::LtNav(source.parse()?),
+            Some(topic) => return Err(de::unknown_topic(topic)),
+            None => Self::Op(source.parse()?),
+        })
+    }
+}
+
 pub struct SpotPublicResponseArg;
 impl Arg for SpotPublicResponseArg {
     type ValueType<'a> = SpotPublicResponse<'a>;
+
+    fn parse(frame: &str) -> serde_json::Result<Self::ValueType<'_>> {
+        de::dispatch_str(frame)
+    }
+
+    #[cfg(feature = "simd-json")]
+    fn parse_mut<'a>(
+        frame: &'a mut [u8],
+        buffers: &mut simd_json::Buffers,
+    ) -> simd_json::Result<Self::ValueType<'a>> {
+        de::dispatch_mut(frame, buffers)
+    }
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
 pub enum FuturePublicResponse<'a> {
-    #[serde(borrow)]
     Orderbook(BasePublicResponse<'a, Orderbook<'a>>),
     Trade(BasePublicResponse<'a, Vec<Trade<'a>>>),
     Ticker(BaseTickerPublicResponse<'a, FutureTicker<'a>>),
@@ -707,15 +1010,46 @@ pub enum FuturePublicResponse<'a> {
     Op(OpResponse<'a>),
 }
 
+impl<'de: 'a, 'a> Deserialize<'de> for FuturePublicResponse<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        de::dispatch(deserializer)
+    }
+}
+
+impl<'de: 'a, 'a> de::Dispatch<'de> for FuturePublicResponse<'a> {
+    fn dispatch<S: de::Source<'de>>(tag: de::Tag<'de>, source: S) -> Result<Self, S::Error> {
+        Ok(match tag.topic() {
+            Some("orderbook") => Self::Orderbook(source.parse()?),
+            Some("publicTrade") => Self::Trade(source.parse()?),
+            Some("tickers") => Self::Ticker(source.parse()?),
+            Some("kline") => Self::Kline(source.parse()?),
+            Some("liquidation") => Self::Liquidation(source.parse()?),
+            Some(topic) => return Err(de::unknown_topic(topic)),
+            None => Self::Op(source.parse()?),
+        })
+    }
+}
+
 pub struct FuturePublicResponseArg;
 impl Arg for FuturePublicResponseArg {
     type ValueType<'a> = FuturePublicResponse<'a>;
+
+    fn parse(frame: &str) -> serde_json::Result<Self::ValueType<'_>> {
+        de::dispatch_str(frame)
+    }
+
+    #[cfg(feature = "simd-json")]
+    fn parse_mut<'a>(
+        frame: &'a mut [u8],
+        buffers: &mut simd_json::Buffers,
+    ) -> simd_json::Result<Self::ValueType<'a>> {
+        de::dispatch_mut(frame, buffers)
+    }
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
 pub enum OptionPublicResponse<'a> {
-    #[serde(borrow)]
     Orderbook(BaseOptionPublicResponse<'a, Orderbook<'a>>),
     Trade(BaseOptionPublicResponse<'a, Vec<Trade<'a>>>),
     Ticker(BaseOptionPublicResponse<'a, OptionTicker<'a>>),
@@ -723,15 +1057,44 @@ pub enum OptionPublicResponse<'a> {
     Subscription(OptionSubscriptionResponse<'a>),
 }
 
+impl<'de: 'a, 'a> Deserialize<'de> for OptionPublicResponse<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        de::dispatch(deserializer)
+    }
+}
+
+impl<'de: 'a, 'a> de::Dispatch<'de> for OptionPublicResponse<'a> {
+    fn dispatch<S: de::Source<'de>>(tag: de::Tag<'de>, source: S) -> Result<Self, S::Error> {
+        Ok(match tag.topic() {
+            Some("orderbook") => Self::Orderbook(source.parse()?),
+            Some("publicTrade") => Self::Trade(source.parse()?),
+            Some("tickers") => Self::Ticker(source.parse()?),
+            Some(topic) => return Err(de::unknown_topic(topic)),
+            None if tag.op() == Some("pong") => Self::Pong(source.parse()?),
+            None => Self::Subscription(source.parse()?),
+        })
+    }
+}
+
 pub struct OptionPublicResponseArg;
 impl Arg for OptionPublicResponseArg {
     type ValueType<'a> = OptionPublicResponse<'a>;
+
+    fn parse(frame: &str) -> serde_json::Result<Self::ValueType<'_>> {
+        de::dispatch_str(frame)
+    }
+
+    #[cfg(feature = "simd-json")]
+    fn parse_mut<'a>(
+        frame: &'a mut [u8],
+        buffers: &mut simd_json::Buffers,
+    ) -> simd_json::Result<Self::ValueType<'a>> {
+        de::dispatch_mut(frame, buffers)
+    }
 }
 
-#[derive(Deserialize, Debug)]
-#[serde(untagged)]
+#[derive(Debug, Clone)]
 pub enum PrivateResponse<'a> {
-    #[serde(borrow)]
     Position(BasePrivateResponse<'a, Vec<Position<'a>>>),
     Execution(BasePrivateResponse<'a, Vec<Execution<'a>>>),
     Order(BasePrivateResponse<'a, Vec<Order<'a>>>),
@@ -741,7 +1104,555 @@ pub enum PrivateResponse<'a> {
     Op(OpResponse<'a>),
 }
 
+impl<'de: 'a, 'a> Deserialize<'de> for PrivateResponse<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        de::dispatch(deserializer)
+    }
+}
+
+impl<'de: 'a, 'a> de::Dispatch<'de> for PrivateResponse<'a> {
+    fn dispatch<S: de::Source<'de>>(tag: de::Tag<'de>, source: S) -> Result<Self, S::Error> {
+        Ok(match tag.topic() {
+            Some("position") => Self::Position(source.parse()?),
+            Some("execution") => Self::Execution(source.parse()?),
+            Some("order") => Self::Order(source.parse()?),
+            Some("wallet") => Self::Wallet(source.parse()?),
+            Some("greeks") => Self::Greek(source.parse()?),
+            Some(topic) => return Err(de::unknown_topic(topic)),
+            None if tag.op() == Some("pong") => Self::Pong(source.parse()?),
+            None => Self::Op(source.parse()?),
+        })
+    }
+}
+
 pub struct PrivateResponseArg;
 impl Arg for PrivateResponseArg {
     type ValueType<'a> = PrivateResponse<'a>;
+
+    fn parse(frame: &str) -> serde_json::Result<Self::ValueType<'_>> {
+        de::dispatch_str(frame)
+    }
+
+    #[cfg(feature = "simd-json")]
+    fn parse_mut<'a>(
+        frame: &'a mut [u8],
+        buffers: &mut simd_json::Buffers,
+    ) -> simd_json::Result<Self::ValueType<'a>> {
+        de::dispatch_mut(frame, buffers)
+    }
+}
+
+/// The reply of the auth and ping requests on the trade channel.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeOpResponse<'a> {
+    #[serde(borrow, default, deserialize_with = "de::option_cow")]
+    pub req_id: Option<Cow<'a, str>>,
+    /// `0` means success.
+    pub ret_code: i64,
+    #[serde(borrow)]
+    pub ret_msg: Cow<'a, str>,
+    #[serde(borrow)]
+    pub conn_id: Cow<'a, str>,
+}
+
+/// The IDs of the order in an order reply. They are empty if the request failed.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderReplyData<'a> {
+    /// Order ID.
+    #[serde(borrow, default)]
+    pub order_id: Cow<'a, str>,
+    /// User customised order ID.
+    #[serde(borrow, default)]
+    pub order_link_id: Cow<'a, str>,
+}
+
+/// The rate limit of the order requests, sent with each order reply.
+#[derive(Deserialize, Debug, Clone)]
+pub struct RateLimit<'a> {
+    /// The limit of the current endpoint.
+    #[serde(rename = "X-Bapi-Limit", deserialize_with = "de::from_str")]
+    pub limit: u32,
+    /// The remaining requests in the current window.
+    #[serde(rename = "X-Bapi-Limit-Status", deserialize_with = "de::from_str")]
+    pub status: u32,
+    /// The timestamp (ms) when the current window resets.
+    #[serde(
+        rename = "X-Bapi-Limit-Reset-Timestamp",
+        deserialize_with = "de::from_str"
+    )]
+    pub reset_timestamp: u64,
+    /// Trace ID, used to track the request.
+    #[serde(rename = "Traceid", borrow, default)]
+    pub trace_id: Cow<'a, str>,
+    /// The server timestamp (ms).
+    #[serde(rename = "Timenow", deserialize_with = "de::from_str")]
+    pub time_now: u64,
+}
+
+/// The reply of an order request on the trade channel.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderResponse<'a> {
+    /// The `req_id` of the originating request, returned by [`super::trade::TradeHandle`].
+    #[serde(borrow)]
+    pub req_id: Cow<'a, str>,
+    /// `0` means success.
+    pub ret_code: i64,
+    #[serde(borrow)]
+    pub ret_msg: Cow<'a, str>,
+    #[serde(borrow, default)]
+    pub data: OrderReplyData<'a>,
+    /// The rate limit. It may be missing if the request is rejected before reaching the rate limiter.
+    #[serde(borrow, default)]
+    pub header: Option<RateLimit<'a>>,
+    #[serde(borrow)]
+    pub conn_id: Cow<'a, str>,
+}
+
+impl<'a> OrderResponse<'a> {
+    /// Whether the request succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.ret_code == 0
+    }
+}
+
+/// The responses of the trade channel, tagged by op.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "op")]
+pub enum TradeResponse<'a> {
+    #[serde(rename = "order.create", borrow)]
+    Create(OrderResponse<'a>),
+    #[serde(rename = "order.amend", borrow)]
+    Amend(OrderResponse<'a>),
+    #[serde(rename = "order.cancel", borrow)]
+    Cancel(OrderResponse<'a>),
+    #[serde(rename = "auth", borrow)]
+    Auth(TradeOpResponse<'a>),
+    #[serde(rename = "pong", borrow)]
+    Pong(TradeOpResponse<'a>),
+}
+
+pub struct TradeResponseArg;
+impl Arg for TradeResponseArg {
+    type ValueType<'a> = TradeResponse<'a>;
+}
+
+/// Convert a response which borrows from the received frame into one which owns its data,
+/// so it can be kept after the callback returns or sent to other threads.
+///
+/// The owned version of a response type is the same type with the `'static` lifetime,
+/// e.g., `Orderbook<'static>`.
+pub trait IntoOwned {
+    type Owned: 'static;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+impl<'a> IntoOwned for Cow<'a, str> {
+    type Owned = Cow<'static, str>;
+
+    fn into_owned(self) -> Cow<'static, str> {
+        Cow::Owned(self.into_owned())
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(IntoOwned::into_owned)
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter().map(IntoOwned::into_owned).collect()
+    }
+}
+
+impl<T: IntoOwned, const N: usize> IntoOwned for [T; N] {
+    type Owned = [T::Owned; N];
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(IntoOwned::into_owned)
+    }
+}
+
+macro_rules! impl_into_owned_static {
+    ($($ty:ty),*) => {
+        $(
+            impl $crate::ws::response::IntoOwned for $ty {
+                type Owned = $ty;
+
+                fn into_owned(self) -> Self::Owned {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_into_owned_static;
+
+impl_into_owned_static!(bool, u8, u16, u32, u64, i64, String);
+impl_into_owned_static!(
+    DataType,
+    Category,
+    Side,
+    OrderStatus,
+    OrderType,
+    StopOrderType,
+    TimeInForce,
+    TriggerBy,
+    CancelType,
+    ExecType,
+    TickDirection,
+    PositionStatus,
+    TpslMode,
+    AccountType,
+    InstrumentStatus,
+    ContractType,
+    OptionsType,
+    IntervalTime,
+    MarginMode,
+    TransactionType,
+    TransferStatus,
+    WithdrawStatus,
+    DepositStatus
+);
+#[cfg(feature = "decimal")]
+impl_into_owned_static!(rust_decimal::Decimal);
+
+macro_rules! impl_into_owned {
+    ($($name:ident { $($field:ident),* $(,)? })*) => {
+        $(
+            impl<'a> $crate::ws::response::IntoOwned for $name<'a> {
+                type Owned = $name<'static>;
+
+                fn into_owned(self) -> Self::Owned {
+                    $name {
+                        $($field: $crate::ws::response::IntoOwned::into_owned(self.$field)),*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_into_owned;
+
+macro_rules! impl_into_owned_base {
+    ($($name:ident { $($field:ident),* $(,)? })*) => {
+        $(
+            impl<'a, Data: $crate::ws::response::IntoOwned> $crate::ws::response::IntoOwned
+                for $name<'a, Data> {
+                type Owned = $name<'static, Data::Owned>;
+
+                fn into_owned(self) -> Self::Owned {
+                    $name {
+                        $($field: $crate::ws::response::IntoOwned::into_owned(self.$field)),*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_into_owned_base;
+
+macro_rules! impl_into_owned_enum {
+    ($($name:ident { $($variant:ident),* $(,)? })*) => {
+        $(
+            impl<'a> $crate::ws::response::IntoOwned for $name<'a> {
+                type Owned = $name<'static>;
+
+                fn into_owned(self) -> Self::Owned {
+                    match self {
+                        $($name::$variant(res) => $name::$variant(res.into_owned())),*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl<'a> IntoOwned for OrderbookItem<'a> {
+    type Owned = OrderbookItem<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        OrderbookItem {
+            price: IntoOwned::into_owned(self.price),
+            size: IntoOwned::into_owned(self.size),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl_into_owned_base! {
+    BasePublicResponse { topic, type_, ts, data }
+    BaseTickerPublicResponse { topic, type_, cs, ts, data }
+    BaseOptionPublicResponse { id, topic, type_, ts, data }
+    BasePrivateResponse { id, topic, creation_time, data }
+}
+
+impl_into_owned! {
+    OpResponse { success, ret_msg, conn_id, req_id, op }
+    OptionPongResponse { args, op }
+    OptionSubscriptionData { fail_topics, success_topics }
+    OptionSubscriptionResponse { success, conn_id, data, type_ }
+    PrivatePongResponse { req_id, op, args, conn_id }
+    Orderbook { s, b, a, u, seq }
+    Trade { T, s, S, v, p, L, i, BT }
+    SpotTicker {
+        symbol, last_price, high_price_24h, low_price_24h, prev_price_24h, volume_24h, turnover_24h,
+        price_24h_pcnt, usd_index_price
+    }
+    OptionTicker {
+        symbol, bid_price, bid_size, bid_iv, ask_price, ask_size, ask_iv, last_price,
+        high_price_24h, low_price_24h, mark_price, index_price, mark_price_iv, underlying_price,
+        open_interest, turnover_24h, volume_24h, total_volume, total_turnover, delta, gamma, vega,
+        theta, predicted_delivery_price, change_24h
+    }
+    FutureTicker {
+        symbol, tick_direction, price_24h_pcnt, last_price, prev_price_24h, high_price_24h,
+        low_price_24h, prev_price_1h, mark_price, index_price, open_interest, open_interest_value,
+        turnover_24h, volume_24h, next_funding_time, funding_rate, bid1_price, bid1_size,
+        ask1_price, ask1_size, delivery_time, basis_rate, delivery_fee_rate,
+        predicted_delivery_price
+    }
+    Kline { start, end, interval, open, close, high, low, volume, turnover, confirm, timestamp }
+    Liquidation { updated_time, symbol, side, size, price }
+    LtTicker { symbol, price_24h_pcnt, last_price, prev_price_24h, high_price_24h, low_price24h }
+    LtNav { time, symbol, nav, basket_position, leverage, basket_loan, circulation, basket }
+    Position {
+        category, symbol, side, size, position_idx, trade_mode, position_value, risk_id,
+        risk_limit_value, entry_price, mark_price, leverage, position_balance, auto_add_margin,
+        position_mm, position_im, liq_price, bust_price, tpsl_mode, take_profit, stop_loss,
+        trailing_stop, unrealised_pnl, cum_realised_pnl, position_status, created_time, updated_time
+    }
+    Execution {
+        category, symbol, is_leverage, order_id, order_link_id, side, order_price, order_qty,
+        leaves_qty, order_type, stop_order_type, exec_fee, exec_id, exec_price, exec_qty, exec_type,
+        exec_value, exec_time, is_maker, fee_rate, trade_iv, mark_iv, mark_price, index_price,
+        underlying_price, block_trade_id
+    }
+    Order {
+        category, order_id, order_link_id, is_leverage, block_trade_id, symbol, price, qty, side,
+        position_idx, order_status, cancel_type, reject_reason, avg_price, leaves_qty, leaves_value,
+        cum_exec_qty, cum_exec_value, cum_exec_fee, time_in_force, order_type, stop_order_type,
+        order_iv, trigger_price, take_profit, stop_loss, tp_trigger_by, sl_trigger_by,
+        trigger_direction, trigger_by, last_price_on_created, reduce_only, close_on_trigger,
+        created_time, updated_time
+    }
+    WalletCoin {
+        coin, equity, usd_value, wallet_balance, borrow_amount, available_to_borrow,
+        available_to_withdraw, accrued_interest, total_order_im, total_position_im,
+        total_position_mm, unrealised_pnl, cum_realised_pnl
+    }
+    Wallet {
+        account_type, account_im_rate, account_mm_rate, total_equity, total_wallet_balance,
+        total_margin_balance, total_available_balance, total_perp_upl, total_initial_margin,
+        total_maintenance_margin, coin
+    }
+    Greek { base_coin, total_delta, total_gamma, total_vega, total_theta }
+    TradeOpResponse { req_id, ret_code, ret_msg, conn_id }
+    OrderReplyData { order_id, order_link_id }
+    RateLimit { limit, status, reset_timestamp, trace_id, time_now }
+    OrderResponse { req_id, ret_code, ret_msg, data, header, conn_id }
+}
+
+impl_into_owned_enum! {
+    SpotPublicResponse { Orderbook, Trade, Ticker, Kline, LtTicker, LtNav, Op }
+    FuturePublicResponse { Orderbook, Trade, Ticker, Kline, Liquidation, Op }
+    OptionPublicResponse { Orderbook, Trade, Ticker, Pong, Subscription }
+    PrivateResponse { Position, Execution, Order, Wallet, Greek, Pong, Op }
+    TradeResponse { Create, Amend, Cancel, Auth, Pong }
+}
+
+/// The owned version of [`SpotPublicResponse`].
+pub type OwnedSpotPublicResponse = SpotPublicResponse<'static>;
+
+/// The owned version of [`FuturePublicResponse`].
+pub type OwnedFuturePublicResponse = FuturePublicResponse<'static>;
+
+/// The owned version of [`OptionPublicResponse`].
+pub type OwnedOptionPublicResponse = OptionPublicResponse<'static>;
+
+/// The owned version of [`PrivateResponse`].
+pub type OwnedPrivateResponse = PrivateResponse<'static>;
+
+/// The owned version of [`TradeResponse`].
+pub type OwnedTradeResponse = TradeResponse<'static>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_owned() {
+        let text = String::from(
+            r#"{"topic":"orderbook.1.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{"s":"BTCUSDT","b":[["16493.50","0.006"]],"a":[["16611.00","0.029"]],"u":18521288,"seq":7961638724}}"#,
+        );
+        let res: SpotPublicResponse = serde_json::from_str(&text).unwrap();
+        let SpotPublicResponse::Orderbook(res) = &res else {
+            panic!("not an orderbook");
+        };
+        assert!(matches!(res.topic, Cow::Borrowed(_)));
+        assert!(matches!(res.data.s, Cow::Borrowed(_)));
+
+        let owned: OwnedSpotPublicResponse =
+            SpotPublicResponse::Orderbook(res.clone()).into_owned();
+        drop(text);
+        let SpotPublicResponse::Orderbook(res) = owned else {
+            panic!("not an orderbook");
+        };
+        assert_eq!(res.data.s, "BTCUSDT");
+        assert_eq!(res.data.a[0].price.to_string(), "16611.00");
+        assert_eq!(res.data.seq, Some(7961638724));
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let text = r#"{"id":"5923240c6880ab-c59f-420b-9adb-3639adc9dd90","topic":"position.linear","creationTime":1672364174455,"data":[]}"#;
+        let res: PrivateResponse = serde_json::from_str(text).unwrap();
+        assert!(matches!(res, PrivateResponse::Position(res) if res.data.is_empty()));
+
+        let text = r#"{"req_id":"100001","op":"pong","args":["1672916271846"],"conn_id":"cfcb4ocsvfriu23r3er0-1b"}"#;
+        let res: PrivateResponse = serde_json::from_str(text).unwrap();
+        assert!(matches!(res, PrivateResponse::Pong(_)));
+
+        let text =
+            r#"{"success":true,"ret_msg":"","conn_id":"cejreaspqfh3sjdnldmg-p","op":"subscribe"}"#;
+        let res: FuturePublicResponse = serde_json::from_str(text).unwrap();
+        assert!(matches!(res, FuturePublicResponse::Op(res) if res.op == "subscribe"));
+
+        // The topic is found after other fields, or with escapes.
+        let text = r#"{"ts":1672304484978,"type":"delta","topic":"orderbook.1.BTCUSDT","data":{"s":"BTCUSDT","b":[],"a":[],"u":2}}"#;
+        let res: FuturePublicResponse = serde_json::from_str(text).unwrap();
+        assert!(matches!(res, FuturePublicResponse::Orderbook(res) if res.data.u == 2));
+        let text = text.replace("orderbook.1", r"orderbook\u002e1");
+        let res = FuturePublicResponseArg::parse(&text).unwrap();
+        assert!(
+            matches!(res, FuturePublicResponse::Orderbook(res) if res.topic == "orderbook.1.BTCUSDT")
+        );
+
+        // Errors tell the topic or the field, instead of not matching any variant.
+        let text = r#"{"topic":"unknown.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{}}"#;
+        let err = serde_json::from_str::<FuturePublicResponse>(text).unwrap_err();
+        assert!(err.to_string().contains("unknown topic `unknown`"));
+
+        let text = r#"{"topic":"orderbook.1.BTCUSDT","type":"delta","ts":1672304484978,"data":{"s":"BTCUSDT","b":[],"a":[]}}"#;
+        let err = serde_json::from_str::<FuturePublicResponse>(text).unwrap_err();
+        assert!(err.to_string().contains("missing field `u`"));
+
+        // Responses are deserialized from other deserializers as well.
+        let value: serde_json::Value =
+            serde_json::from_str(&text.replace(r#""a":[]"#, r#""a":[["16611.00","0.029"]],"u":3"#))
+                .unwrap();
+        let res = FuturePublicResponse::deserialize(value).unwrap();
+        let FuturePublicResponse::Orderbook(res) = res else {
+            panic!("not an orderbook");
+        };
+        assert_eq!(res.data.u, 3);
+        assert_eq!(res.data.a.len(), 1);
+        let text = r#"{"req_id":"100001","op":"pong","args":["1672916271846"],"conn_id":"cfcb4ocsvfriu23r3er0-1b"}"#;
+        let mut deserializer = serde_json::Deserializer::from_reader(text.as_bytes());
+        let res = PrivateResponse::deserialize(&mut deserializer).unwrap();
+        assert!(matches!(res, PrivateResponse::Pong(_)));
+    }
+
+    #[cfg(feature = "simd-json")]
+    #[test]
+    fn test_simd_json() {
+        let mut buffers = simd_json::Buffers::default();
+        let mut frame = br#"{"topic":"orderbook.1.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{"s":"BTCUSDT","b":[["16493.50","0.006"]],"a":[["16611.00","0.029"]],"u":18521288,"seq":7961638724}}"#.to_vec();
+        let res = FuturePublicResponseArg::parse_mut(&mut frame, &mut buffers).unwrap();
+        let FuturePublicResponse::Orderbook(res) = res else {
+            panic!("not an orderbook");
+        };
+        assert!(matches!(res.topic, Cow::Borrowed(_)));
+        assert!(matches!(res.data.s, Cow::Borrowed(_)));
+        assert_eq!(res.data.u, 18521288);
+
+        let mut frame = br#"{"req_id":"100001","op":"pong","args":["1672916271846"],"conn_id":"cfcb4ocsvfriu23r3er0-1b"}"#.to_vec();
+        let res = PrivateResponseArg::parse_mut(&mut frame, &mut buffers).unwrap();
+        assert!(matches!(res, PrivateResponse::Pong(_)));
+
+        let mut frame =
+            br#"{"topic":"unknown.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{}}"#
+                .to_vec();
+        let err = FuturePublicResponseArg::parse_mut(&mut frame, &mut buffers).unwrap_err();
+        assert!(err.to_string().contains("unknown topic `unknown`"));
+
+        let mut frame =
+            br#"{"retCode":0,"retMsg":"OK","op":"auth","connId":"cgrp5l9jo0bm9nsl9ha0-1u"}"#
+                .to_vec();
+        let res = TradeResponseArg::parse_mut(&mut frame, &mut buffers).unwrap();
+        assert!(matches!(res, TradeResponse::Auth(res) if res.ret_code == 0));
+    }
+
+    #[test]
+    fn test_trade_response() {
+        let text = r#"{"reqId":"test-005","retCode":0,"retMsg":"OK","op":"order.create","data":{"orderId":"1c7cd0fd-4a76-4e43-9d0b-cda5b4f8cd48","orderLinkId":""},"header":{"X-Bapi-Limit":"10","X-Bapi-Limit-Status":"9","X-Bapi-Limit-Reset-Timestamp":"1685430655325","Traceid":"df7b89ff9c3e3b52f15e7b1b3bfa0a8d","Timenow":"1685430655336"},"connId":"cgrp5l9jo0bm9nsl9ha0-1u"}"#;
+        let TradeResponse::Create(res) = serde_json::from_str(text).unwrap() else {
+            panic!("not an order.create reply");
+        };
+        assert!(res.is_ok());
+        assert_eq!(res.req_id, "test-005");
+        assert_eq!(res.data.order_id, "1c7cd0fd-4a76-4e43-9d0b-cda5b4f8cd48");
+        let header = res.header.unwrap();
+        assert_eq!(header.limit, 10);
+        assert_eq!(header.status, 9);
+        assert_eq!(header.reset_timestamp, 1685430655325);
+
+        let text = r#"{"reqId":"test-006","retCode":10404,"retMsg":"unsupported op","op":"order.cancel","data":{},"connId":"cgrp5l9jo0bm9nsl9ha0-1u"}"#;
+        let TradeResponse::Cancel(res) = serde_json::from_str(text).unwrap() else {
+            panic!("not an order.cancel reply");
+        };
+        assert!(!res.is_ok());
+        assert_eq!(res.ret_code, 10404);
+        assert!(res.header.is_none());
+        assert_eq!(res.data.order_id, "");
+
+        let text = r#"{"retCode":0,"retMsg":"OK","op":"auth","connId":"cgrp5l9jo0bm9nsl9ha0-1u"}"#;
+        let res: TradeResponse = serde_json::from_str(text).unwrap();
+        assert!(
+            matches!(res, TradeResponse::Auth(res) if res.ret_code == 0 && res.req_id.is_none())
+        );
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal() {
+        use rust_decimal::Decimal;
+
+        let text = r#"{"symbol":"BTCUSDT","price":"16500.5","qty":"0.01","avgPrice":"","triggerPrice":"1e2","createdTime":"1672364262444"}"#;
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Partial<'a> {
+            #[serde(borrow)]
+            symbol: Cow<'a, str>,
+            #[serde(borrow, deserialize_with = "de::optional_number")]
+            price: OptionalNumber<'a>,
+            #[serde(borrow, deserialize_with = "de::number")]
+            qty: Number<'a>,
+            #[serde(borrow, deserialize_with = "de::optional_number")]
+            avg_price: OptionalNumber<'a>,
+            #[serde(borrow, deserialize_with = "de::optional_number")]
+            trigger_price: OptionalNumber<'a>,
+            #[serde(borrow, deserialize_with = "de::timestamp")]
+            created_time: Timestamp<'a>,
+        }
+        let order: Partial = serde_json::from_str(text).unwrap();
+        assert_eq!(order.symbol, "BTCUSDT");
+        assert_eq!(order.price, Some(Decimal::new(165005, 1)));
+        assert_eq!(order.qty, Decimal::new(1, 2));
+        assert_eq!(order.avg_price, None);
+        assert_eq!(order.trigger_price, Some(Decimal::new(100, 0)));
+        assert_eq!(order.created_time, 1672364262444);
+    }
 }
@@ -0,0 +1,569 @@
+//! Deserializers borrowing strings from the input when possible.
+//!
+//! Serde borrows `Cow<'a, str>` fields annotated with `#[serde(borrow)]`,
+//! but not the ones nested in other types, such as `Option<Cow<'a, str>>`.
+
+use serde::de::value::{BorrowedStrDeserializer, MapDeserializer, SeqDeserializer};
+use serde::de::{IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::{de, forward_to_deserialize_any, Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+pub(crate) struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+pub(crate) fn option_cow<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<Borrowed>::deserialize(deserializer)?;
+    Ok(value.map(|Borrowed(s)| s))
+}
+
+pub(crate) fn vec_cow<'de: 'a, 'a, D>(deserializer: D) -> Result<Vec<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Vec::<Borrowed>::deserialize(deserializer)?;
+    Ok(value.into_iter().map(|Borrowed(s)| s).collect())
+}
+
+pub(crate) fn array_cow<'de: 'a, 'a, D, const N: usize>(
+    deserializer: D,
+) -> Result<[Cow<'a, str>; N], D::Error>
+where
+    D: Deserializer<'de>,
+    [Borrowed<'a>; N]: Deserialize<'de>,
+{
+    let value = <[Borrowed; N]>::deserialize(deserializer)?;
+    Ok(value.map(|Borrowed(s)| s))
+}
+
+/// Deserialize a value which Bybit sends as a string, e.g., a rate limit header.
+pub(crate) fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let Borrowed(s) = Borrowed::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+}
+
+/// Deserialize a number which Bybit always sends.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn number<'de: 'a, 'a, D>(deserializer: D) -> Result<Cow<'a, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Borrowed(s) = Borrowed::deserialize(deserializer)?;
+    Ok(s)
+}
+
+/// Deserialize a number which Bybit may send as an empty string.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn optional_number<'de: 'a, 'a, D>(deserializer: D) -> Result<Cow<'a, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    number(deserializer)
+}
+
+/// Deserialize a number which may be missing.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn option_number<'de: 'a, 'a, D>(
+    deserializer: D,
+) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    option_cow(deserializer)
+}
+
+/// Deserialize a timestamp (ms) which Bybit sends as a string.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn timestamp<'de: 'a, 'a, D>(deserializer: D) -> Result<Cow<'a, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    number(deserializer)
+}
+
+/// Deserialize a number into an owned value, for types which do not borrow from the input.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn owned_number<'de, D>(deserializer: D) -> Result<Cow<'static, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer).map(Cow::Owned)
+}
+
+/// Deserialize a number which may be missing into an owned value.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn owned_option_number<'de, D>(
+    deserializer: D,
+) -> Result<Option<Cow<'static, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer).map(|value| value.map(Cow::Owned))
+}
+
+/// Deserialize a timestamp (ms) into an owned value, for types which do not borrow from the input.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn owned_timestamp<'de, D>(deserializer: D) -> Result<Cow<'static, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    owned_number(deserializer)
+}
+
+/// The fields which decide the variant of a response.
+#[derive(Deserialize)]
+pub(crate) struct Tag<'a> {
+    #[serde(borrow, default)]
+    topic: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    op: Option<Cow<'a, str>>,
+}
+
+impl<'a> Tag<'a> {
+    /// Read the tag of a frame.
+    ///
+    /// Bybit sends the topic ahead of the data, so the leading string fields are scanned first.
+    /// The whole frame is parsed only if they do not have the topic, e.g., for small op replies.
+    pub(crate) fn read(text: &'a str) -> serde_json::Result<Self> {
+        match Self::scan(text) {
+            Some(topic) => Ok(Self {
+                topic: Some(Cow::Borrowed(topic)),
+                op: None,
+            }),
+            None => serde_json::from_str(text),
+        }
+    }
+
+    /// Find the topic among the leading string fields without escapes.
+    fn scan(text: &'a str) -> Option<&'a str> {
+        let mut rest = text.trim_start().strip_prefix('{')?;
+        loop {
+            let (key, tail) = plain_str(rest)?;
+            let (value, tail) = plain_str(tail.trim_start().strip_prefix(':')?)?;
+            if key == "topic" {
+                return Some(value);
+            }
+            rest = tail.trim_start().strip_prefix(',')?;
+        }
+    }
+
+    /// The topic of a frame, or its op if it does not have a topic. It is empty if the frame
+    /// has neither.
+    pub(crate) fn key(text: &str) -> String {
+        match Tag::read(text) {
+            Ok(Tag {
+                topic: Some(key), ..
+            })
+            | Ok(Tag { op: Some(key), .. }) => key.into_owned(),
+            _ => String::new(),
+        }
+    }
+
+    /// The topic without the symbol and parameters, e.g., `orderbook` of `orderbook.50.BTCUSDT`.
+    pub(crate) fn topic(&self) -> Option<&str> {
+        let topic = self.topic.as_deref()?;
+        Some(topic.split('.').next().unwrap_or(topic))
+    }
+
+    /// The op of replies, e.g., `subscribe` or `pong`.
+    pub(crate) fn op(&self) -> Option<&str> {
+        self.op.as_deref()
+    }
+}
+
+/// Split a JSON string without escapes from the text following it.
+fn plain_str(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start().strip_prefix('"')?;
+    let end = text.find(['"', '\\'])?;
+    (text.as_bytes()[end] == b'"').then(|| (&text[..end], &text[end + 1..]))
+}
+
+/// A frame which a response is deserialized from, after its [`Tag`] is read.
+pub(crate) trait Source<'de> {
+    type Error: de::Error;
+
+    /// Deserialize the whole frame.
+    fn parse<T: Deserialize<'de>>(self) -> Result<T, Self::Error>;
+}
+
+/// The raw text of a frame, parsed by `serde_json`.
+impl<'de> Source<'de> for &'de str {
+    type Error = serde_json::Error;
+
+    fn parse<T: Deserialize<'de>>(self) -> Result<T, Self::Error> {
+        serde_json::from_str(self)
+    }
+}
+
+/// A frame already parsed into a tape by simd-json.
+#[cfg(feature = "simd-json")]
+impl<'de> Source<'de> for &mut simd_json::Deserializer<'de> {
+    type Error = simd_json::Error;
+
+    fn parse<T: Deserialize<'de>>(self) -> Result<T, Self::Error> {
+        self.restart();
+        T::deserialize(self)
+    }
+}
+
+/// A response whose variant is chosen by the [`Tag`] of a frame, instead of trying each
+/// variant in turn like an untagged enum.
+pub(crate) trait Dispatch<'de>: Sized {
+    fn dispatch<S: Source<'de>>(tag: Tag<'de>, source: S) -> Result<Self, S::Error>;
+}
+
+/// Deserialize a response from any deserializer by buffering it first, then reading its [`Tag`]
+/// and deserializing the variant from the buffer.
+///
+/// Strings are borrowed from the input when the deserializer allows it, as they are by
+/// `serde_json::from_str`. Parsing a frame with `parse` of the response's [`Arg`](crate::ws::Arg)
+/// reads the tag from the raw text instead, which saves the buffering.
+pub(crate) fn dispatch<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Dispatch<'de>,
+{
+    let content = Content::deserialize(deserializer)?;
+    let tag = Tag {
+        topic: content.field("topic"),
+        op: content.field("op"),
+    };
+    T::dispatch(tag, ContentDeserializer::new(content))
+}
+
+/// Parse a response from the raw text of a frame.
+pub(crate) fn dispatch_str<'de, T: Dispatch<'de>>(text: &'de str) -> serde_json::Result<T> {
+    T::dispatch(Tag::read(text)?, text)
+}
+
+/// Parse a response with simd-json, which rewrites the frame in place.
+///
+/// The tag is read from the tape, so the frame is parsed only once.
+#[cfg(feature = "simd-json")]
+pub(crate) fn dispatch_mut<'de, T: Dispatch<'de>>(
+    frame: &'de mut [u8],
+    buffers: &mut simd_json::Buffers,
+) -> simd_json::Result<T> {
+    use simd_json::prelude::ValueIntoString;
+
+    let mut deserializer = simd_json::Deserializer::from_slice_with_buffers(frame, buffers)?;
+    let value = deserializer.as_value();
+    let field = |key| value.get(key).and_then(|value| value.into_string());
+    let tag = Tag {
+        topic: field("topic").map(Cow::Borrowed),
+        op: field("op").map(Cow::Borrowed),
+    };
+    T::dispatch(tag, &mut deserializer)
+}
+
+/// The error of a topic which the channel does not have.
+pub(crate) fn unknown_topic<E: de::Error>(topic: &str) -> E {
+    E::custom(format_args!("unknown topic `{}`", topic))
+}
+
+/// A value buffered from any deserializer, like the untagged enums of serde do.
+enum Content<'de> {
+    Bool(bool),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+    Str(&'de str),
+    String(String),
+    Unit,
+    Some(Box<Content<'de>>),
+    Seq(Vec<Content<'de>>),
+    Map(Vec<(Content<'de>, Content<'de>)>),
+}
+
+impl<'de> Content<'de> {
+    /// The string field of a map with `key`.
+    fn field(&self, key: &str) -> Option<Cow<'de, str>> {
+        let Content::Map(entries) = self else {
+            return None;
+        };
+        entries.iter().find_map(|entry| match entry {
+            (Content::Str(k), Content::Str(v)) if *k == key => Some(Cow::Borrowed(*v)),
+            (Content::Str(k), Content::String(v)) if *k == key => Some(Cow::Owned(v.clone())),
+            (Content::String(k), Content::Str(v)) if k == key => Some(Cow::Borrowed(*v)),
+            (Content::String(k), Content::String(v)) if k == key => Some(Cow::Owned(v.clone())),
+            _ => None,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Content<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ContentVisitor)
+    }
+}
+
+struct ContentVisitor;
+
+impl<'de> Visitor<'de> for ContentVisitor {
+    type Value = Content<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Content::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Content::I64(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Content::U64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Content::F64(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Content::String(v.to_owned()))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Content::Str(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Content::String(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Content::Unit)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Content::Unit)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        Content::deserialize(deserializer).map(|v| Content::Some(Box::new(v)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Content::Seq(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Content::Map(entries))
+    }
+}
+
+/// Deserialize a value from a [`Content`] buffer, with the error of the original deserializer.
+struct ContentDeserializer<'de, E> {
+    content: Content<'de>,
+    error: PhantomData<E>,
+}
+
+impl<'de, E> ContentDeserializer<'de, E> {
+    fn new(content: Content<'de>) -> Self {
+        Self {
+            content,
+            error: PhantomData,
+        }
+    }
+}
+
+impl<'de, E: de::Error> IntoDeserializer<'de, E> for Content<'de> {
+    type Deserializer = ContentDeserializer<'de, E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        ContentDeserializer::new(self)
+    }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for ContentDeserializer<'de, E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Bool(v) => visitor.visit_bool(v),
+            Content::U64(v) => visitor.visit_u64(v),
+            Content::I64(v) => visitor.visit_i64(v),
+            Content::F64(v) => visitor.visit_f64(v),
+            Content::Str(v) => visitor.visit_borrowed_str(v),
+            Content::String(v) => visitor.visit_string(v),
+            Content::Unit => visitor.visit_unit(),
+            Content::Some(v) => visitor.visit_some(v.into_deserializer()),
+            Content::Seq(v) => {
+                let mut seq = SeqDeserializer::new(v.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Content::Map(v) => {
+                let mut map = MapDeserializer::new(v.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Unit => visitor.visit_none(),
+            Content::Some(v) => visitor.visit_some(v.into_deserializer()),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        match self.content {
+            Content::Str(v) => visitor.visit_enum(BorrowedStrDeserializer::new(v)),
+            Content::String(v) => visitor.visit_enum(v.into_deserializer()),
+            _ => Err(E::custom(format_args!(
+                "expected a variant of `{}` in {:?}",
+                name, variants
+            ))),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// A frame buffered from any deserializer.
+impl<'de, E: de::Error> Source<'de> for ContentDeserializer<'de, E> {
+    type Error = E;
+
+    fn parse<T: Deserialize<'de>>(self) -> Result<T, Self::Error> {
+        T::deserialize(self)
+    }
+}
+
+#[cfg(feature = "decimal")]
+pub(crate) use self::decimal::*;
+
+#[cfg(feature = "decimal")]
+mod decimal {
+    use rust_decimal::Decimal;
+    use serde::de::{self, Visitor};
+    use serde::{Deserialize, Deserializer};
+    use std::fmt;
+    use std::str::FromStr;
+
+    use super::Borrowed;
+
+    /// Deserialize a number which Bybit always sends.
+    pub(crate) fn number<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        optional_number(deserializer)?.ok_or_else(|| de::Error::custom("empty number"))
+    }
+
+    /// Deserialize a number which Bybit may send as an empty string.
+    pub(crate) fn optional_number<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+
+    /// Deserialize a number which may be missing.
+    pub(crate) fn option_number<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+
+    /// Deserialize a timestamp (ms) which Bybit sends as a string.
+    pub(crate) fn timestamp<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Borrowed(s) = Borrowed::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+
+    /// Decimals are always owned.
+    pub(crate) use self::number as owned_number;
+
+    /// Decimals are always owned.
+    pub(crate) use self::option_number as owned_option_number;
+
+    /// Timestamps are always owned.
+    pub(crate) use self::timestamp as owned_timestamp;
+
+    /// Visit a decimal sent as a string or a JSON number. Empty strings and nulls are `None`.
+    struct DecimalVisitor;
+
+    impl<'de> Visitor<'de> for DecimalVisitor {
+        type Value = Option<Decimal>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a decimal number or an empty string")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            if v.is_empty() {
+                return Ok(None);
+            }
+            Decimal::from_str(v)
+                .or_else(|_| Decimal::from_scientific(v))
+                .map(Some)
+                .map_err(E::custom)
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(Some(v.into()))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            Ok(Some(v.into()))
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            Decimal::try_from(v).map(Some).map_err(E::custom)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+    }
+}
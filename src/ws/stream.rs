@@ -0,0 +1,362 @@
+use futures_util::{SinkExt, Stream, StreamExt};
+use log::*;
+use std::future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::time::{self, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use super::callback::Arg;
+use super::event::Event;
+use super::frame::Frame;
+use super::request::Requests;
+use super::response::IntoOwned;
+use super::{reject_order, Command, Control, Credentials, Options, Subscriber};
+use super::{CLOSE_TIMEOUT, PING, PING_INTERVAL};
+use crate::error::{BybitError, Result};
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A stream of the responses received by a websocket api client. Requires the `async` feature.
+///
+/// Each frame is parsed by the background task into an owned response,
+/// e.g., [`super::response::OwnedSpotPublicResponse`], so it can be kept or sent on freely.
+/// Frames which fail to parse are skipped, and counted and notified as they are by `run`.
+///
+/// The connection is driven by a background task which sends pings on a timer
+/// and reconnects according to the `ReconnectPolicy` of the client.
+/// The stream ends after the client is shut down by its handle, or yields the error and ends
+/// when the connection is lost and can't be reestablished.
+/// Dropping the stream closes the connection.
+pub struct ResponseStream<R> {
+    rx: mpsc::Receiver<Result<R>>,
+}
+
+impl<R> Stream for ResponseStream<R> {
+    type Item = Result<R>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// A stream of the raw frames received by a websocket api client. Requires the `async` feature.
+///
+/// Responses borrow from the frames, which are parsed on demand with [`Frame::parse`].
+/// Otherwise it behaves as [`ResponseStream`].
+pub struct FrameStream<A> {
+    rx: mpsc::Receiver<Result<Frame<A>>>,
+}
+
+impl<A> Stream for FrameStream<A> {
+    type Item = Result<Frame<A>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Convert a received frame into an item of a stream, or return `None` to skip it.
+type Deliver<T> = fn(String, &Arc<Options>) -> Option<T>;
+
+pub(super) async fn connect_stream<A, R>(
+    uri: &str,
+    subscriber: &Subscriber,
+    credentials: Option<&Credentials>,
+    options: &Options,
+    control: &Control,
+) -> Result<ResponseStream<R>>
+where
+    A: Arg,
+    R: Send + 'static,
+    for<'a> A::ValueType<'a>: IntoOwned<Owned = R>,
+{
+    let deliver: Deliver<R> = |content, options| match A::parse(&content) {
+        Ok(res) => Some(res.into_owned()),
+        Err(e) => {
+            options.parse_failed(&content, e);
+            None
+        }
+    };
+    let rx = spawn(uri, subscriber, credentials, options, control, deliver).await?;
+    Ok(ResponseStream { rx })
+}
+
+pub(super) async fn connect_frames<A>(
+    uri: &str,
+    subscriber: &Subscriber,
+    credentials: Option<&Credentials>,
+    options: &Options,
+    control: &Control,
+) -> Result<FrameStream<A>>
+where
+    A: Arg + Send + 'static,
+{
+    let deliver: Deliver<Frame<A>> = |content, options| Some(Frame::new(content, options));
+    let rx = spawn(uri, subscriber, credentials, options, control, deliver).await?;
+    Ok(FrameStream { rx })
+}
+
+/// Connect and spawn the task which drives the connection, delivering items over a channel.
+async fn spawn<T: Send + 'static>(
+    uri: &str,
+    subscriber: &Subscriber,
+    credentials: Option<&Credentials>,
+    options: &Options,
+    control: &Control,
+    deliver: Deliver<T>,
+) -> Result<mpsc::Receiver<Result<T>>> {
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let mut connection = Connection {
+        uri: uri.to_owned(),
+        subscriber: subscriber.clone(),
+        credentials: credentials.cloned(),
+        options: Arc::new(options.clone()),
+        commands: control.stream_commands(),
+        tx,
+        deliver,
+        attempt: 0,
+    };
+
+    // Apply the commands sent before, as `run` does.
+    while let Ok(command) = connection.commands.try_recv() {
+        if connection.apply(command, None).is_none() {
+            return Ok(rx);
+        }
+    }
+
+    // The first connection is retried with the policy as well, as `run` does.
+    let conn = loop {
+        let err = match connection.connect().await {
+            Ok(conn) => break conn,
+            Err(err) => err,
+        };
+        if !connection.wait(err).await? {
+            // Shut down before connected, so the stream ends right away.
+            return Ok(rx);
+        }
+    };
+    tokio::spawn(connection.drive(conn));
+    Ok(rx)
+}
+
+/// Open a connection, then authenticate and subscribe to topics.
+async fn connect(
+    uri: &str,
+    topics: &[String],
+    credentials: Option<&Credentials>,
+) -> Result<(Socket, Requests)> {
+    let (mut ws, _) = connect_async(uri).await?;
+    let mut requests = Requests::default();
+
+    // Authenticate
+    if let Some(credentials) = credentials {
+        ws.send(Message::Text(requests.auth(credentials))).await?;
+    }
+
+    // Subscribe
+    if !topics.is_empty() {
+        let req = requests.op("subscribe", topics.to_vec());
+        ws.send(Message::Text(req)).await?;
+    }
+
+    Ok((ws, requests))
+}
+
+/// Receive the next command sent by handles.
+///
+/// Once the client and all its handles are dropped, it never completes,
+/// as the stream keeps running until it is dropped.
+async fn command(commands: &mut UnboundedReceiver<Command>) -> Command {
+    match commands.recv().await {
+        Some(command) => command,
+        None => future::pending().await,
+    }
+}
+
+struct Connection<T> {
+    uri: String,
+    subscriber: Subscriber,
+    credentials: Option<Credentials>,
+    /// Shared with the frames, which count and notify parse failures.
+    options: Arc<Options>,
+    commands: UnboundedReceiver<Command>,
+    tx: mpsc::Sender<Result<T>>,
+    deliver: Deliver<T>,
+    /// The number of consecutive failed attempts to connect.
+    attempt: u32,
+}
+
+impl<T> Connection<T> {
+    async fn drive(mut self, mut conn: (Socket, Requests)) {
+        loop {
+            let (ws, mut requests) = conn;
+            let res = self.serve(ws, &mut requests).await;
+            // Only a healthy connection resets the backoff.
+            if requests.confirmed() {
+                self.attempt = 0;
+            }
+            let mut err = match res {
+                Ok(()) => return,
+                Err(err @ BybitError::AuthFailed(_)) => {
+                    // Retrying with the same credentials won't help.
+                    let _ = self.tx.send(Err(err)).await;
+                    return;
+                }
+                Err(err) => err,
+            };
+            self.options.notify(Event::Disconnected {
+                reason: err.to_string(),
+            });
+
+            conn = loop {
+                match self.wait(err).await {
+                    Ok(true) => {}
+                    Ok(false) => return,
+                    Err(err) => {
+                        let _ = self.tx.send(Err(err)).await;
+                        return;
+                    }
+                }
+                match self.connect().await {
+                    Ok(conn) => break conn,
+                    Err(e) => err = e,
+                }
+            };
+        }
+    }
+
+    /// Connect with the topics subscribed so far.
+    async fn connect(&self) -> Result<(Socket, Requests)> {
+        let topics = self.subscriber.topics();
+        let conn = connect(&self.uri, &topics, self.credentials.as_ref()).await?;
+        if self.attempt > 0 {
+            info!("Reconnected after {} attempt(s)", self.attempt);
+            self.options.notify(Event::Reconnected {
+                attempt: self.attempt,
+            });
+        }
+        Ok(conn)
+    }
+
+    /// Wait before the next attempt to connect after `err`, while applying commands.
+    ///
+    /// Returns `Ok(false)` if the client is shut down or the stream is dropped meanwhile,
+    /// or `err` if the policy gives up.
+    async fn wait(&mut self, err: BybitError) -> Result<bool> {
+        let policy = match &self.options.reconnect {
+            Some(policy) if policy.allows(self.attempt) => policy,
+            Some(policy) => {
+                policy.give_up(&err);
+                return Err(err);
+            }
+            None => return Err(err),
+        };
+
+        self.attempt += 1;
+        let delay = policy.delay(self.attempt);
+        warn!("Connection lost: {}. Reconnect in {:?}", err, delay);
+        self.options.notify(Event::Reconnecting {
+            attempt: self.attempt,
+            delay,
+        });
+        let sleep = time::sleep(delay);
+        tokio::pin!(sleep);
+        loop {
+            tokio::select! {
+                _ = &mut sleep => return Ok(true),
+                command = command(&mut self.commands) => {
+                    // Topics are subscribed on the next connection.
+                    if self.apply(command, None).is_none() {
+                        return Ok(false);
+                    }
+                }
+                _ = self.tx.closed() => return Ok(false),
+            }
+        }
+    }
+
+    /// Serve the connection until it is shut down, the stream is dropped or an error occurs.
+    async fn serve(&mut self, mut ws: Socket, requests: &mut Requests) -> Result<()> {
+        let mut ping = time::interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL);
+        loop {
+            tokio::select! {
+                msg = ws.next() => match msg {
+                    Some(Ok(Message::Text(content))) => {
+                        debug!("Received: {}", content);
+                        self.options.record(&content);
+                        let reply = requests.reply(&content, &self.options);
+                        if let Some(item) = (self.deliver)(content, &self.options) {
+                            if self.tx.send(Ok(item)).await.is_err() {
+                                close(ws).await;
+                                return Ok(());
+                            }
+                        }
+                        reply?;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => Err(e)?,
+                    None => Err(tungstenite::Error::ConnectionClosed)?,
+                },
+                _ = ping.tick() => ws.send(Message::Text(PING.into())).await?,
+                command = command(&mut self.commands) => match self.apply(command, Some(requests)) {
+                    Some(texts) => {
+                        for text in texts {
+                            ws.send(Message::Text(text)).await?;
+                        }
+                    }
+                    None => {
+                        close(ws).await;
+                        return Ok(());
+                    }
+                },
+                _ = self.tx.closed() => {
+                    close(ws).await;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Apply a command and return the frames to send, or `None` if shutdown is requested.
+    ///
+    /// Without `requests`, the client is disconnected and the frames are discarded.
+    fn apply(&self, command: Command, requests: Option<&mut Requests>) -> Option<Vec<String>> {
+        match (command, requests) {
+            (Command::Shutdown, _) => None,
+            (Command::Send { req_id, op, text }, Some(requests)) if requests.authenticated() => {
+                requests.track(req_id, op);
+                Some(vec![text])
+            }
+            (Command::Send { req_id, op, .. }, Some(_)) => {
+                reject_order(&self.options, op, req_id, "not authenticated");
+                Some(Vec::new())
+            }
+            (command, requests) => {
+                let ops = command.apply(&self.subscriber, &self.options);
+                Some(match requests {
+                    Some(requests) => ops
+                        .into_iter()
+                        .map(|(op, topics)| requests.op(op, topics))
+                        .collect(),
+                    None => Vec::new(),
+                })
+            }
+        }
+    }
+}
+
+/// Send a close frame and wait for the server to close the connection.
+async fn close(mut ws: Socket) {
+    if let Err(e) = ws.close(None).await {
+        debug!("Failed to close: {}", e);
+        return;
+    }
+    let drain = async { while let Some(Ok(_)) = ws.next().await {} };
+    let _ = time::timeout(CLOSE_TIMEOUT, drain).await;
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+
+use super::response::{
+    timestamp_millis, BasePrivateResponse, IntoOwned, Order, OwnedPrivateResponse, Position,
+    PrivateResponse, Wallet,
+};
+use crate::enums::{AccountType, Category};
+use crate::error::Result;
+use crate::rest::position::PositionListRequest;
+use crate::rest::trade::RealtimeOrdersRequest;
+use crate::rest::RestApiClient;
+
+/// The products to fetch a [`PrivateSnapshot`] for.
+#[derive(Debug, Clone)]
+pub struct SnapshotScope {
+    account_type: AccountType,
+    categories: Vec<(Category, Option<String>)>,
+}
+
+impl SnapshotScope {
+    /// Create an empty scope of the account type, e.g., `UNIFIED` or `CONTRACT`.
+    pub fn new(account_type: AccountType) -> Self {
+        Self {
+            account_type,
+            categories: Vec::new(),
+        }
+    }
+
+    /// Add positions and open orders of linear symbols settled in `settle_coin`, e.g., `USDT`.
+    pub fn linear<S: AsRef<str>>(self, settle_coin: S) -> Self {
+        self.add(Category::Linear, Some(settle_coin.as_ref()))
+    }
+
+    /// Add positions and open orders of inverse symbols settled in `settle_coin`, e.g., `BTC`.
+    pub fn inverse<S: AsRef<str>>(self, settle_coin: S) -> Self {
+        self.add(Category::Inverse, Some(settle_coin.as_ref()))
+    }
+
+    /// Add positions and open orders of options.
+    pub fn option(self) -> Self {
+        self.add(Category::Option, None)
+    }
+
+    /// Add open orders of spot. Spot does not have positions.
+    pub fn spot(self) -> Self {
+        self.add(Category::Spot, None)
+    }
+
+    fn add(mut self, category: Category, coin: Option<&str>) -> Self {
+        self.categories.push((category, coin.map(str::to_owned)));
+        self
+    }
+}
+
+/// The positions, open orders and wallets of an account at some moment, fetched with REST APIs.
+#[derive(Debug, Clone)]
+pub struct PrivateSnapshot {
+    pub positions: Vec<Position<'static>>,
+    pub orders: Vec<Order<'static>>,
+    pub wallets: Vec<Wallet<'static>>,
+    /// Server timestamp (ms) before anything is fetched.
+    pub time: u64,
+}
+
+impl PrivateSnapshot {
+    /// Fetch the wallets, and all pages of positions and open orders in `scope`.
+    pub fn fetch(client: &RestApiClient, scope: &SnapshotScope) -> Result<Self> {
+        let server_time = client.server_time()?;
+        let time = timestamp_millis(&server_time.time_nano) / 1_000_000;
+        let wallets = client
+            .wallet_balance(scope.account_type.clone(), None)?
+            .list;
+
+        let mut positions = Vec::new();
+        let mut orders = Vec::new();
+        for (category, coin) in &scope.categories {
+            if *category != Category::Spot {
+                let mut req = PositionListRequest::new(category.clone()).limit(200);
+                if let Some(coin) = coin {
+                    req = req.settle_coin(coin);
+                }
+                loop {
+                    let page = client.positions(&req)?;
+                    positions.extend(page.list);
+                    match page.next_page_cursor {
+                        Some(cursor) if !cursor.is_empty() => req = req.cursor(cursor),
+                        _ => break,
+                    }
+                }
+            }
+
+            let mut req = RealtimeOrdersRequest::new(category.clone()).limit(50);
+            if let Some(coin) = coin {
+                req = req.settle_coin(coin);
+            }
+            loop {
+                let page = client.realtime_orders(&req)?;
+                orders.extend(page.list);
+                match page.next_page_cursor {
+                    Some(cursor) if !cursor.is_empty() => req = req.cursor(cursor),
+                    _ => break,
+                }
+            }
+        }
+        Ok(Self {
+            positions,
+            orders,
+            wallets,
+            time,
+        })
+    }
+}
+
+/// The positions, open orders and wallets of an account, kept up to date by the private topics.
+///
+/// Websocket topics only push changes, so the state starts from a [`PrivateSnapshot`]:
+///
+/// 1. Subscribe to the position, order and wallet topics and feed every response to `apply`.
+///    Responses are buffered until a snapshot is loaded.
+/// 2. Fetch a snapshot with [`PrivateSnapshot::fetch`] and `load` it. The buffered responses
+///    are applied then, except the ones older than the snapshot.
+///
+/// Positions and orders are compared by `updated_time`, so an update older than the current
+/// position or order is discarded, as well as an update of one which is not in the snapshot
+/// and older than the snapshot, e.g., an order filled before the snapshot.
+/// Wallets are compared by the creation time of the message.
+///
+/// Updates may be missed while the connection is lost. `reset` the state on
+/// [`Event::Disconnected`](super::event::Event::Disconnected) and load a new snapshot after reconnecting.
+#[derive(Debug, Clone, Default)]
+pub struct PrivateState {
+    positions: HashMap<(String, u8), Position<'static>>,
+    orders: HashMap<String, Order<'static>>,
+    wallets: HashMap<AccountType, Wallet<'static>>,
+    time: u64,
+    buffer: Vec<OwnedPrivateResponse>,
+    synced: bool,
+}
+
+impl PrivateState {
+    /// Create an empty `PrivateState` waiting for a snapshot.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a response of the private channel, or buffer it if no snapshot is loaded.
+    ///
+    /// Responses other than positions, orders and wallets are ignored.
+    pub fn apply(&mut self, res: PrivateResponse) {
+        if !self.synced {
+            if matches!(
+                res,
+                PrivateResponse::Position(_)
+                    | PrivateResponse::Order(_)
+                    | PrivateResponse::Wallet(_)
+            ) {
+                self.buffer.push(res.into_owned());
+            }
+            return;
+        }
+        match res {
+            PrivateResponse::Position(res) => self.apply_positions(res.data),
+            PrivateResponse::Order(res) => self.apply_orders(res.data),
+            PrivateResponse::Wallet(res) => self.apply_wallets(res),
+            _ => {}
+        }
+    }
+
+    /// Replace the state with the snapshot and apply the buffered responses.
+    pub fn load(&mut self, snapshot: PrivateSnapshot) {
+        self.positions.clear();
+        self.orders.clear();
+        self.wallets.clear();
+        self.time = snapshot.time;
+        for position in snapshot.positions {
+            let key = (position.symbol.to_string(), position.position_idx);
+            self.positions.insert(key, position);
+        }
+        for order in snapshot.orders {
+            self.orders.insert(order.order_id.to_string(), order);
+        }
+        for wallet in snapshot.wallets {
+            self.wallets.insert(wallet.account_type.clone(), wallet);
+        }
+        self.synced = true;
+        for res in std::mem::take(&mut self.buffer) {
+            self.apply(res);
+        }
+    }
+
+    /// Clear the state and buffer responses until the next snapshot is loaded.
+    pub fn reset(&mut self) {
+        self.positions.clear();
+        self.orders.clear();
+        self.wallets.clear();
+        self.time = 0;
+        self.buffer.clear();
+        self.synced = false;
+    }
+
+    /// Whether a snapshot is loaded.
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Get the position of a symbol. `position_idx` is `0` for one-way mode.
+    pub fn position(&self, symbol: &str, position_idx: u8) -> Option<&Position<'static>> {
+        self.positions.get(&(symbol.to_string(), position_idx))
+    }
+
+    /// Iterate over the positions, including empty ones.
+    pub fn positions(&self) -> impl Iterator<Item = &Position<'static>> {
+        self.positions.values()
+    }
+
+    /// Get an open order by its order ID.
+    pub fn order(&self, order_id: &str) -> Option<&Order<'static>> {
+        self.orders.get(order_id)
+    }
+
+    /// Iterate over the open orders.
+    pub fn open_orders(&self) -> impl Iterator<Item = &Order<'static>> {
+        self.orders.values()
+    }
+
+    /// Get the wallet of an account type.
+    pub fn wallet(&self, account_type: &AccountType) -> Option<&Wallet<'static>> {
+        self.wallets.get(account_type)
+    }
+
+    fn apply_positions<'a>(&mut self, positions: Vec<Position<'a>>) {
+        for position in positions {
+            let key = (position.symbol.to_string(), position.position_idx);
+            let mut position = position.into_owned();
+            let updated_time = timestamp_millis(&position.updated_time);
+            match self.positions.get(&key) {
+                Some(current) => {
+                    if updated_time < timestamp_millis(&current.updated_time) {
+                        continue;
+                    }
+                    // Unified accounts do not send the category in websocket updates.
+                    if position.category.is_none() {
+                        position.category = current.category.clone();
+                    }
+                }
+                None if updated_time < self.time => continue,
+                None => {}
+            }
+            self.positions.insert(key, position);
+        }
+    }
+
+    fn apply_orders<'a>(&mut self, orders: Vec<Order<'a>>) {
+        for order in orders {
+            let updated_time = timestamp_millis(&order.updated_time);
+            match self.orders.get(order.order_id.as_ref()) {
+                Some(current) if updated_time < timestamp_millis(&current.updated_time) => continue,
+                None if updated_time < self.time => continue,
+                _ => {}
+            }
+            if order.order_status.is_final() {
+                self.orders.remove(order.order_id.as_ref());
+            } else {
+                self.orders
+                    .insert(order.order_id.to_string(), order.into_owned());
+            }
+        }
+    }
+
+    fn apply_wallets<'a>(&mut self, res: BasePrivateResponse<'a, Vec<Wallet<'a>>>) {
+        if res.creation_time < self.time {
+            return;
+        }
+        for wallet in res.data {
+            self.wallets
+                .insert(wallet.account_type.clone(), wallet.into_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::fixtures;
+    use serde_json::json;
+
+    fn order(order_id: &str, status: &str, updated_time: u64) -> String {
+        let order = fixtures::order(json!({
+            "orderId": order_id,
+            "orderStatus": status,
+            "updatedTime": updated_time.to_string(),
+        }));
+        fixtures::message("order", &order)
+    }
+
+    fn position(size: &str, updated_time: u64) -> String {
+        let position = fixtures::position(json!({
+            "size": size,
+            "updatedTime": updated_time.to_string(),
+        }));
+        fixtures::message("position", &position)
+    }
+
+    #[test]
+    fn test_sync() {
+        let mut state = PrivateState::new();
+        let texts = [
+            order("a", "New", 1500),
+            order("b", "New", 2500),
+            position("0.1", 1500),
+            position("0.2", 2500),
+        ];
+        for text in &texts {
+            state.apply(serde_json::from_str(text).unwrap());
+        }
+        assert!(!state.is_synced());
+        assert_eq!(state.open_orders().count(), 0);
+
+        let parse = |text: &str| -> OwnedPrivateResponse {
+            serde_json::from_str::<PrivateResponse>(text)
+                .unwrap()
+                .into_owned()
+        };
+        let PrivateResponse::Position(snapshot_position) = parse(&position("0.3", 3000)) else {
+            panic!("not a position");
+        };
+        let mut snapshot_position = snapshot_position.data;
+        snapshot_position[0].category = Some(Category::Linear);
+        state.load(PrivateSnapshot {
+            positions: snapshot_position,
+            orders: Vec::new(),
+            wallets: Vec::new(),
+            time: 2000,
+        });
+
+        assert!(state.is_synced());
+        // Order a was closed before the snapshot, and order b was placed after it.
+        assert!(state.order("a").is_none());
+        assert!(state.order("b").is_some());
+        // Both buffered positions are older than the snapshot.
+        let current = state.position("ETHUSDT", 0).unwrap();
+        assert_eq!(current.size.to_string(), "0.3");
+
+        state.apply(parse(&position("0.4", 3500)));
+        let current = state.position("ETHUSDT", 0).unwrap();
+        assert_eq!(current.size.to_string(), "0.4");
+        assert_eq!(current.category, Some(Category::Linear));
+
+        state.apply(parse(&order("b", "Filled", 4000)));
+        assert!(state.order("b").is_none());
+    }
+}
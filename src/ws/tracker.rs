@@ -0,0 +1,331 @@
+use std::collections::{HashMap, HashSet};
+
+use super::num::{value, Value};
+use super::response::{timestamp_millis, Execution, IntoOwned, Order, PrivateResponse};
+use crate::enums::OrderStatus;
+use crate::error::Result;
+
+/// A change of an order reported by [`OrderTracker`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderEvent {
+    /// The status of an order changed, e.g., from `New` to `PartiallyFilled`.
+    Transition {
+        order_id: String,
+        order_link_id: String,
+        /// The previous status. `None` for the first update of an order.
+        from: Option<OrderStatus>,
+        to: OrderStatus,
+    },
+    /// An order is (partially) filled by an execution.
+    Fill {
+        order_id: String,
+        order_link_id: String,
+        exec_id: String,
+        exec_qty: Value,
+        exec_price: Value,
+        exec_fee: Value,
+        is_maker: bool,
+    },
+}
+
+/// An order tracked by [`OrderTracker`], merged from its order updates and executions.
+#[derive(Debug, Clone)]
+pub struct TrackedOrder {
+    order_id: String,
+    order_link_id: String,
+    order: Option<Order<'static>>,
+    fills: Vec<Execution<'static>>,
+    exec_ids: HashSet<String>,
+    filled_qty: Value,
+    filled_value: Value,
+    fee: Value,
+}
+
+impl TrackedOrder {
+    fn new(order_id: &str, order_link_id: &str) -> Self {
+        Self {
+            order_id: order_id.to_string(),
+            order_link_id: order_link_id.to_string(),
+            order: None,
+            fills: Vec::new(),
+            exec_ids: HashSet::new(),
+            filled_qty: Value::default(),
+            filled_value: Value::default(),
+            fee: Value::default(),
+        }
+    }
+
+    /// Order ID.
+    pub fn order_id(&self) -> &str {
+        &self.order_id
+    }
+
+    /// User customised order ID. It is empty if not set.
+    pub fn order_link_id(&self) -> &str {
+        &self.order_link_id
+    }
+
+    /// The status of the order. `None` if only executions of the order are received.
+    pub fn status(&self) -> Option<&OrderStatus> {
+        self.order.as_ref().map(|order| &order.order_status)
+    }
+
+    /// Whether the order is closed and will not be updated anymore.
+    pub fn is_final(&self) -> bool {
+        matches!(self.status(), Some(status) if status.is_final())
+    }
+
+    /// The last order update.
+    pub fn order(&self) -> Option<&Order<'static>> {
+        self.order.as_ref()
+    }
+
+    /// The executions of the order, in the order they are received.
+    pub fn fills(&self) -> &[Execution<'static>] {
+        &self.fills
+    }
+
+    /// The sum of the quantities of the executions.
+    pub fn filled_qty(&self) -> Value {
+        self.filled_qty
+    }
+
+    /// The average price of the executions, or `None` if the order has no execution.
+    pub fn avg_fill_price(&self) -> Option<Value> {
+        if self.filled_qty == Value::default() {
+            return None;
+        }
+        Some(self.filled_value / self.filled_qty)
+    }
+
+    /// The sum of the fees of the executions. Negative for rebates.
+    pub fn fee(&self) -> Value {
+        self.fee
+    }
+}
+
+/// Track the lifecycle of orders from the order and execution topics of the private channel.
+///
+/// Order updates drive the status of each order, e.g., `New` → `PartiallyFilled` → `Filled`.
+/// An update older than the last one by `updated_time` is discarded, and an order never
+/// leaves a final status (`Filled`, `Cancelled`, `Rejected`, `Deactivated`, ...).
+/// Executions are accumulated to each order, and an execution received twice,
+/// e.g., after reconnecting, is counted once by `exec_id`.
+///
+/// Orders can be looked up by `order_id` or `order_link_id`. Closed orders are kept until
+/// `remove_closed` is called.
+#[derive(Debug, Clone, Default)]
+pub struct OrderTracker {
+    orders: HashMap<String, TrackedOrder>,
+    links: HashMap<String, String>,
+}
+
+impl OrderTracker {
+    /// Create an empty `OrderTracker`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a response of the private channel and return the events it causes.
+    ///
+    /// Responses other than orders and executions are ignored.
+    pub fn apply(&mut self, res: &PrivateResponse) -> Result<Vec<OrderEvent>> {
+        match res {
+            PrivateResponse::Order(res) => Ok(self.apply_orders(&res.data)),
+            PrivateResponse::Execution(res) => self.apply_executions(&res.data),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Apply order updates, e.g., from the order topic or the open orders fetched with REST APIs.
+    pub fn apply_orders(&mut self, orders: &[Order]) -> Vec<OrderEvent> {
+        let mut events = Vec::new();
+        for order in orders {
+            let tracked = self.entry(&order.order_id, &order.order_link_id);
+            let from = match &tracked.order {
+                Some(last) => {
+                    if timestamp_millis(&order.updated_time) < timestamp_millis(&last.updated_time)
+                        || (last.order_status.is_final() && order.order_status != last.order_status)
+                    {
+                        continue;
+                    }
+                    Some(last.order_status.clone())
+                }
+                None => None,
+            };
+            tracked.order = Some(order.clone().into_owned());
+            if from.as_ref() != Some(&order.order_status) {
+                events.push(OrderEvent::Transition {
+                    order_id: tracked.order_id.clone(),
+                    order_link_id: tracked.order_link_id.clone(),
+                    from,
+                    to: order.order_status.clone(),
+                });
+            }
+        }
+        events
+    }
+
+    /// Apply executions, ignoring the ones already applied and the ones without an order, e.g., funding.
+    pub fn apply_executions(&mut self, executions: &[Execution]) -> Result<Vec<OrderEvent>> {
+        let mut events = Vec::new();
+        for execution in executions {
+            if execution.order_id.is_empty() {
+                continue;
+            }
+            let tracked = self.entry(&execution.order_id, &execution.order_link_id);
+            if tracked.exec_ids.contains(execution.exec_id.as_ref()) {
+                continue;
+            }
+            let exec_qty = value(&execution.exec_qty)?;
+            let exec_price = value(&execution.exec_price)?;
+            let exec_fee = value(&execution.exec_fee)?;
+            tracked.exec_ids.insert(execution.exec_id.to_string());
+            tracked.fills.push(execution.clone().into_owned());
+            tracked.filled_qty += exec_qty;
+            tracked.filled_value += exec_qty * exec_price;
+            tracked.fee += exec_fee;
+            events.push(OrderEvent::Fill {
+                order_id: tracked.order_id.clone(),
+                order_link_id: tracked.order_link_id.clone(),
+                exec_id: execution.exec_id.to_string(),
+                exec_qty,
+                exec_price,
+                exec_fee,
+                is_maker: execution.is_maker,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Get an order by its order ID.
+    pub fn get(&self, order_id: &str) -> Option<&TrackedOrder> {
+        self.orders.get(order_id)
+    }
+
+    /// Get an order by its user customised order ID.
+    pub fn get_by_link_id(&self, order_link_id: &str) -> Option<&TrackedOrder> {
+        self.links
+            .get(order_link_id)
+            .and_then(|order_id| self.orders.get(order_id))
+    }
+
+    /// Iterate over all tracked orders.
+    pub fn orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders.values()
+    }
+
+    /// Iterate over the orders which are not closed.
+    pub fn open_orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders.values().filter(|order| !order.is_final())
+    }
+
+    /// Remove closed orders and return how many are removed.
+    ///
+    /// Executions of a removed order received later are tracked as a new order without status.
+    pub fn remove_closed(&mut self) -> usize {
+        let before = self.orders.len();
+        self.orders.retain(|_, order| !order.is_final());
+        let orders = &self.orders;
+        self.links
+            .retain(|_, order_id| orders.contains_key(order_id.as_str()));
+        before - self.orders.len()
+    }
+
+    fn entry(&mut self, order_id: &str, order_link_id: &str) -> &mut TrackedOrder {
+        if !order_link_id.is_empty() {
+            self.links
+                .insert(order_link_id.to_string(), order_id.to_string());
+        }
+        let tracked = self
+            .orders
+            .entry(order_id.to_string())
+            .or_insert_with(|| TrackedOrder::new(order_id, order_link_id));
+        if tracked.order_link_id.is_empty() {
+            tracked.order_link_id = order_link_id.to_string();
+        }
+        tracked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::fixtures;
+    use serde_json::json;
+
+    fn order(status: &str, updated_time: u64) -> String {
+        let order = fixtures::order(json!({
+            "orderId": "f6e324ff",
+            "orderLinkId": "test-001",
+            "orderStatus": status,
+            "updatedTime": updated_time.to_string(),
+        }));
+        fixtures::message("order", &order)
+    }
+
+    fn execution(exec_id: &str, exec_qty: &str) -> String {
+        let execution = fixtures::execution(json!({
+            "orderId": "f6e324ff",
+            "execId": exec_id,
+            "execQty": exec_qty,
+            "execPrice": "1600.00",
+            "isMaker": true,
+        }));
+        fixtures::message("execution", &execution)
+    }
+
+    fn apply(tracker: &mut OrderTracker, text: &str) -> Vec<OrderEvent> {
+        tracker.apply(&serde_json::from_str(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_tracker() {
+        let mut tracker = OrderTracker::new();
+        let events = apply(&mut tracker, &order("New", 1000));
+        assert!(matches!(
+            &events[..],
+            [OrderEvent::Transition {
+                from: None,
+                to: OrderStatus::New,
+                ..
+            }]
+        ));
+
+        let events = apply(&mut tracker, &execution("e1", "0.05"));
+        assert!(matches!(
+            &events[..],
+            [OrderEvent::Fill { is_maker: true, .. }]
+        ));
+        // A duplicate execution is ignored.
+        assert!(apply(&mut tracker, &execution("e1", "0.05")).is_empty());
+
+        let events = apply(&mut tracker, &order("PartiallyFilled", 2000));
+        assert!(matches!(
+            &events[..],
+            [OrderEvent::Transition {
+                from: Some(OrderStatus::New),
+                to: OrderStatus::PartiallyFilled,
+                ..
+            }]
+        ));
+        // A stale update is ignored.
+        assert!(apply(&mut tracker, &order("New", 1500)).is_empty());
+
+        apply(&mut tracker, &execution("e2", "0.05"));
+        apply(&mut tracker, &order("Filled", 3000));
+        // A final status is never left.
+        assert!(apply(&mut tracker, &order("Cancelled", 4000)).is_empty());
+
+        let tracked = tracker.get_by_link_id("test-001").unwrap();
+        assert_eq!(tracked.order_id(), "f6e324ff");
+        assert_eq!(tracked.status(), Some(&OrderStatus::Filled));
+        assert_eq!(tracked.fills().len(), 2);
+        assert_eq!(tracked.filled_qty(), "0.1".parse::<Value>().unwrap());
+        assert_eq!(tracked.avg_fill_price(), Some("1600".parse().unwrap()));
+        assert_eq!(tracker.open_orders().count(), 0);
+
+        assert_eq!(tracker.remove_closed(), 1);
+        assert!(tracker.get_by_link_id("test-001").is_none());
+    }
+}
@@ -0,0 +1,280 @@
+use super::callback::Callback;
+use super::event::{Event, ParseFailures};
+use super::frame::Responses;
+use super::reconnect::ReconnectPolicy;
+use super::record::Recorder;
+use super::request::next_req_id;
+use super::response::{IntoOwned, OwnedTradeResponse, TradeResponse, TradeResponseArg};
+#[cfg(feature = "async")]
+use super::stream::{connect_frames, connect_stream, FrameStream, ResponseStream};
+use super::Subscriber;
+use super::{callback_sink, parse_sink, run, Command, Control, Credentials, Handle, Options};
+use crate::error::Result;
+use crate::rest::trade::{AmendOrderRequest, CancelOrderRequest, PlaceOrderRequest};
+use crate::util::millis;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+const MAINNET_TRADE: &str = "wss://stream.bybit.com/v5/trade";
+const TESTNET_TRADE: &str = "wss://stream-testnet.bybit.com/v5/trade";
+/// `run` polls for orders with this read timeout, so they are written with little delay.
+const READ_TIMEOUT: Duration = Duration::from_millis(1);
+const DEFAULT_RECV_WINDOW: u64 = 5000;
+
+/// A websocket api client which places, amends and cancels orders on the trade channel.
+///
+/// Orders are sent with the [`TradeHandle`] returned by `handle()`, which can be used
+/// from any thread, including the callback. Each reply carries the `req_id` of its request.
+pub struct TradeWebsocketApiClient {
+    uri: String,
+    subscriber: Subscriber,
+    credentials: Credentials,
+    options: Options,
+    control: Control,
+    recv_window: u64,
+}
+
+impl TradeWebsocketApiClient {
+    /// Get a handle to send orders and control the client from other threads.
+    pub fn handle(&self) -> TradeHandle {
+        TradeHandle {
+            handle: self.control.handle(),
+            recv_window: self.recv_window,
+        }
+    }
+
+    /// Get the counts of received frames which failed to parse, shared with the running client.
+    pub fn parse_failures(&self) -> ParseFailures {
+        self.options.parse_failures.clone()
+    }
+
+    pub fn run<C: Callback<TradeResponseArg>>(&self, callback: C) -> Result<()> {
+        run(
+            &self.uri,
+            &self.subscriber,
+            Some(&self.credentials),
+            &self.options,
+            &self.control,
+            callback_sink(callback, &self.options),
+        )
+    }
+
+    /// Run the client on a new thread and receive responses over a bounded channel with `capacity`.
+    ///
+    /// Unlike `run`, responses own their data, so they can be kept or sent to other threads.
+    pub fn spawn(self, capacity: usize) -> Responses<OwnedTradeResponse> {
+        Responses::spawn(self.control.handle(), capacity, move |tx| {
+            run(
+                &self.uri,
+                &self.subscriber,
+                Some(&self.credentials),
+                &self.options,
+                &self.control,
+                parse_sink::<TradeResponseArg, _>(
+                    |res: TradeResponse| tx.send(res.into_owned()).is_ok(),
+                    &self.options,
+                ),
+            )
+        })
+    }
+
+    /// Connect and return a stream of the received responses. Requires the `async` feature.
+    ///
+    /// Pings are sent by a background task, so it must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn stream(&self) -> Result<ResponseStream<OwnedTradeResponse>> {
+        connect_stream::<TradeResponseArg, _>(
+            &self.uri,
+            &self.subscriber,
+            Some(&self.credentials),
+            &self.options,
+            &self.control,
+        )
+        .await
+    }
+
+    /// Connect and return a stream of the raw frames, which responses borrow from.
+    /// Requires the `async` feature.
+    ///
+    /// Pings are sent by a background task, so it must be called within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn frames(&self) -> Result<FrameStream<TradeResponseArg>> {
+        connect_frames(
+            &self.uri,
+            &self.subscriber,
+            Some(&self.credentials),
+            &self.options,
+            &self.control,
+        )
+        .await
+    }
+}
+
+/// A handle to send orders on a running trade websocket api client.
+///
+/// Each method validates the request, sends it with a generated `req_id` and returns the `req_id`,
+/// which is carried by the reply, e.g., [`super::response::TradeResponse::Create`].
+/// A rejected request is also notified as [`Event::Rejected`] with the `req_id` as its args.
+///
+/// Orders are only sent once the client is connected and authenticated, e.g., after
+/// [`super::response::TradeResponse::Auth`] is received. Orders sent before, including
+/// while the client is not running yet or is reconnecting, are not queued but rejected
+/// with `"not connected"`, or `"not authenticated"` if the connection is waiting for the
+/// `auth` reply, as their timestamp would be stale once sent.
+#[derive(Clone, Debug)]
+pub struct TradeHandle {
+    handle: Handle,
+    recv_window: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderOp<'a, R> {
+    req_id: &'a str,
+    header: OrderOpHeader,
+    op: &'a str,
+    args: [&'a R; 1],
+}
+
+#[derive(Serialize)]
+struct OrderOpHeader {
+    #[serde(rename = "X-BAPI-TIMESTAMP")]
+    timestamp: String,
+    #[serde(rename = "X-BAPI-RECV-WINDOW")]
+    recv_window: String,
+}
+
+impl TradeHandle {
+    /// Place an order and return the `req_id` of the request.
+    pub fn place_order(&self, req: &PlaceOrderRequest) -> Result<String> {
+        req.validate()?;
+        self.send("order.create", req)
+    }
+
+    /// Amend an open order and return the `req_id` of the request.
+    pub fn amend_order(&self, req: &AmendOrderRequest) -> Result<String> {
+        req.validate()?;
+        self.send("order.amend", req)
+    }
+
+    /// Cancel an open order and return the `req_id` of the request.
+    pub fn cancel_order(&self, req: &CancelOrderRequest) -> Result<String> {
+        req.validate()?;
+        self.send("order.cancel", req)
+    }
+
+    /// Close the connection with a close frame and make `run` of the client return `Ok(())`.
+    pub fn shutdown(&self) {
+        self.handle.shutdown();
+    }
+
+    fn send<R: Serialize>(&self, op: &'static str, req: &R) -> Result<String> {
+        let req_id = next_req_id();
+        let text = serde_json::to_string(&OrderOp {
+            req_id: &req_id,
+            header: OrderOpHeader {
+                timestamp: millis().to_string(),
+                recv_window: self.recv_window.to_string(),
+            },
+            op,
+            args: [req],
+        })?;
+        let command = Command::Send {
+            req_id: req_id.clone(),
+            op,
+            text,
+        };
+        self.handle.send(command);
+        Ok(req_id)
+    }
+}
+
+pub struct TradeWebSocketApiClientBuilder {
+    uri: String,
+    options: Options,
+    recv_window: u64,
+}
+
+impl TradeWebSocketApiClientBuilder {
+    /// Create a new `TradeWebSocketApiClientBuilder`. Channel URI is set to the mainnet.
+    pub fn new() -> Self {
+        Self {
+            uri: MAINNET_TRADE.to_string(),
+            options: Options {
+                read_timeout: Some(READ_TIMEOUT),
+                ..Options::default()
+            },
+            recv_window: DEFAULT_RECV_WINDOW,
+        }
+    }
+
+    /// Change channel URI to the testnet.
+    pub fn testnet(mut self) -> Self {
+        self.uri = TESTNET_TRADE.to_string();
+        self
+    }
+
+    /// Set channel URI to the URI specified.
+    ///
+    /// Note URI should **match** with api client kind.
+    /// Do not set a spot channel URI to a trade api client.
+    pub fn uri<S: AsRef<str>>(mut self, uri: S) -> Self {
+        self.uri = uri.as_ref().to_owned();
+        self
+    }
+
+    /// Set how long (ms) an order request is valid after it is sent. Default: `5000`.
+    pub fn recv_window(mut self, recv_window: u64) -> Self {
+        self.recv_window = recv_window;
+        self
+    }
+
+    /// Reconnect with the given policy when the connection is lost.
+    ///
+    /// The client is authenticated again after reconnecting. Orders are not resent.
+    pub fn reconnect(mut self, policy: ReconnectPolicy) -> Self {
+        self.options.reconnect = Some(policy);
+        self
+    }
+
+    /// Set a handler which is notified of connection events, such as disconnected and reconnected.
+    pub fn on_event<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        self.options.on_event = Some(Arc::new(handler));
+        self
+    }
+
+    /// Record every received text frame, e.g., to replay an incident later.
+    pub fn record(mut self, recorder: Recorder) -> Self {
+        self.options.recorder = Some(recorder);
+        self
+    }
+
+    /// Build a trade websocket api client with api key and secret key.
+    pub fn build_with_credentials<S: AsRef<str>>(
+        self,
+        api_key: S,
+        secret: S,
+    ) -> TradeWebsocketApiClient {
+        TradeWebsocketApiClient {
+            uri: self.uri,
+            subscriber: Subscriber::new(),
+            options: self.options,
+            control: Control::new(),
+            credentials: Credentials {
+                api_key: api_key.as_ref().to_owned(),
+                secret: secret.as_ref().to_owned(),
+            },
+            recv_window: self.recv_window,
+        }
+    }
+}
+
+impl Default for TradeWebSocketApiClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
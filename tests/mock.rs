@@ -0,0 +1,700 @@
+use bybit::enums::{Category, OrderType, Side};
+use bybit::error::{BybitError, Result};
+use bybit::mock::{self, fixtures, wait_until, MockServer};
+use bybit::rest::trade::{CancelOrderRequest, PlaceOrderRequest};
+use bybit::ws::event::{Event, ParseFailures};
+use bybit::ws::frame::Responses;
+use bybit::ws::future::OrderbookDepth;
+use bybit::ws::option::OrderbookDepth as OptionDepth;
+use bybit::ws::reconnect::ReconnectPolicy;
+use bybit::ws::record::{Recorder, Replayer, Speed};
+use bybit::ws::response::{
+    FuturePublicResponse, FuturePublicResponseArg, OptionPublicResponse, OwnedFuturePublicResponse,
+    PrivateResponse, TradeResponse,
+};
+use bybit::ws::Handle;
+use bybit::WebSocketApiClient;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tungstenite::Message;
+
+const TOPIC: &str = "orderbook.1.BTCUSDT";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn orderbook(data_type: &str, u: u64) -> String {
+    mock::public_message(
+        TOPIC,
+        data_type,
+        &format!(
+            r#"{{"s":"BTCUSDT","b":[["16493.50","0.006"]],"a":[["16611.00","0.029"]],"u":{},"seq":7961638724}}"#,
+            u
+        ),
+    )
+}
+
+fn order() -> String {
+    fixtures::message("order", &fixtures::order(json!({ "orderId": "f6e324ff" })))
+}
+
+/// A linear client subscribed to [`TOPIC`] running on a thread.
+struct Linear {
+    handle: Handle,
+    /// The update IDs of the received order books.
+    updates: Arc<Mutex<Vec<u64>>>,
+    events: Arc<Mutex<Vec<Event>>>,
+    failures: ParseFailures,
+    thread: JoinHandle<Result<()>>,
+}
+
+impl Linear {
+    fn run(server: &MockServer, policy: Option<ReconnectPolicy>) -> Self {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let mut builder = WebSocketApiClient::future_linear()
+            .uri(server.uri())
+            .on_event(move |event| sink.lock().unwrap().push(event));
+        if let Some(policy) = policy {
+            builder = builder.reconnect(policy);
+        }
+        let mut client = builder.build();
+        client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+        let handle = client.handle();
+        let failures = client.parse_failures();
+
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = updates.clone();
+        let thread = thread::spawn(move || {
+            client.run(move |res: FuturePublicResponse| {
+                if let FuturePublicResponse::Orderbook(res) = res {
+                    sink.lock().unwrap().push(res.data.u);
+                }
+            })
+        });
+        Self {
+            handle,
+            updates,
+            events,
+            failures,
+            thread,
+        }
+    }
+
+    fn updates(&self) -> Vec<u64> {
+        self.updates.lock().unwrap().clone()
+    }
+
+    fn shutdown(self) -> Vec<Event> {
+        self.handle.shutdown();
+        self.thread.join().unwrap().unwrap();
+        let events = self.events.lock().unwrap();
+        events.clone()
+    }
+}
+
+#[test]
+fn test_subscribe() {
+    let server = MockServer::public().unwrap();
+    server.on_subscribe(TOPIC, [orderbook("snapshot", 1), orderbook("delta", 2)]);
+    let client = Linear::run(&server, None);
+
+    assert!(wait_until(TIMEOUT, || client.updates().len() == 2));
+    assert_eq!(server.push(orderbook("delta", 3)), 1);
+    assert!(wait_until(TIMEOUT, || client.updates().len() == 3));
+    assert_eq!(client.updates(), vec![1, 2, 3]);
+
+    client.handle.unsubscribe([TOPIC]);
+    assert!(wait_until(TIMEOUT, || server.count("unsubscribe", TOPIC) == 1));
+    client.shutdown();
+}
+
+#[test]
+fn test_reconnect() {
+    let server = MockServer::public().unwrap();
+    server.on_subscribe(TOPIC, [orderbook("snapshot", 1)]);
+    let policy = ReconnectPolicy::new()
+        .initial_delay(Duration::from_millis(10))
+        .jitter(0.0);
+    let client = Linear::run(&server, Some(policy));
+
+    assert!(wait_until(TIMEOUT, || client.updates().len() == 1));
+    server.disconnect();
+    // The topic is resubscribed and the snapshot is pushed again.
+    assert!(wait_until(TIMEOUT, || client.updates().len() == 2));
+    assert_eq!(server.connections(), 2);
+    assert_eq!(server.count("subscribe", TOPIC), 2);
+
+    let events = client.shutdown();
+    assert!(matches!(events[0], Event::Disconnected { .. }));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, Event::Reconnected { attempt: 1 })));
+}
+
+#[test]
+fn test_backoff() {
+    let server = MockServer::public().unwrap();
+    server.drop_on_accept(true);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let policy = ReconnectPolicy::new()
+        .initial_delay(Duration::from_millis(10))
+        .jitter(0.0)
+        .max_attempts(3);
+    let mut client = WebSocketApiClient::future_linear()
+        .uri(server.uri())
+        .reconnect(policy)
+        .on_event(move |event| sink.lock().unwrap().push(event))
+        .build();
+    client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+
+    // Connections which are dropped right after accepting don't reset the backoff.
+    assert!(client.run(|_: FuturePublicResponse| {}).is_err());
+    assert_eq!(server.connections(), 4);
+    let delays: Vec<_> = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match event {
+            Event::Reconnecting { delay, .. } => Some(delay.as_millis()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(delays, vec![10, 20, 40]);
+}
+
+#[test]
+fn test_option_backoff() {
+    let server = MockServer::option().unwrap();
+    let topic = "orderbook.25.BTC-30JUN23-30000-C";
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let policy = ReconnectPolicy::new()
+        .initial_delay(Duration::from_millis(10))
+        .jitter(0.0)
+        .max_attempts(2);
+    let mut client = WebSocketApiClient::option()
+        .uri(server.uri())
+        .reconnect(policy)
+        .on_event(move |event| sink.lock().unwrap().push(event))
+        .build();
+    client.subscribe_orderbook("BTC-30JUN23-30000-C", OptionDepth::Level25);
+    let handle = client.handle();
+    let thread = thread::spawn(move || client.run(|_: OptionPublicResponse| {}));
+
+    // Accepted option subscriptions prove the connections healthy, so the backoff is reset
+    // and the client does not give up after more drops than the policy allows in a row.
+    for n in 1..=3 {
+        assert!(wait_until(TIMEOUT, || server.count("subscribe", topic) == n));
+        server.disconnect();
+    }
+    assert!(wait_until(TIMEOUT, || server.count("subscribe", topic) == 4));
+    handle.shutdown();
+    thread.join().unwrap().unwrap();
+    let delays: Vec<_> = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match event {
+            Event::Reconnecting { delay, .. } => Some(delay.as_millis()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(delays, vec![10, 10, 10]);
+}
+
+#[test]
+fn test_rejected() {
+    let server = MockServer::public().unwrap();
+    server.reject(TOPIC, "Invalid topic");
+    let client = Linear::run(&server, None);
+
+    assert!(wait_until(TIMEOUT, || !client
+        .events
+        .lock()
+        .unwrap()
+        .is_empty()));
+    match &client.shutdown()[0] {
+        Event::Rejected { op, args, reason } => {
+            assert_eq!(op, "subscribe");
+            assert_eq!(args, &[TOPIC]);
+            assert_eq!(reason, "Invalid topic");
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn test_parse_failed() {
+    let server = MockServer::public().unwrap();
+    server.on_subscribe(TOPIC, [orderbook("snapshot", 1)]);
+    let client = Linear::run(&server, None);
+    assert!(wait_until(TIMEOUT, || client.updates().len() == 1));
+
+    // A changed schema and a new topic.
+    let changed = orderbook("delta", 2).replace(r#""u":2"#, r#""u":"2""#);
+    let unknown = mock::public_message("unknown.BTCUSDT", "snapshot", "{}");
+    server.push(changed.clone());
+    server.push(unknown);
+    server.push(orderbook("delta", 3));
+    assert!(wait_until(TIMEOUT, || client.updates().len() == 2));
+
+    assert_eq!(client.failures.get(TOPIC), 1);
+    assert_eq!(client.failures.get("unknown.BTCUSDT"), 1);
+    assert_eq!(client.failures.total(), 2);
+    let events = client.shutdown();
+    match &events[0] {
+        Event::ParseFailed { topic, text, .. } => {
+            assert_eq!(topic, TOPIC);
+            assert_eq!(text, &changed);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn test_record() {
+    let server = MockServer::public().unwrap();
+    server.on_subscribe(TOPIC, [orderbook("snapshot", 1), orderbook("delta", 2)]);
+    let path = std::env::temp_dir().join(format!("bybit-mock-{}.jsonl", std::process::id()));
+    let mut client = WebSocketApiClient::future_linear()
+        .uri(server.uri())
+        .record(Recorder::create(&path).unwrap())
+        .build();
+    client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+    let handle = client.handle();
+    let thread = thread::spawn(move || {
+        client.run(move |res: FuturePublicResponse| {
+            if let FuturePublicResponse::Orderbook(res) = res {
+                if res.data.u == 2 {
+                    handle.shutdown();
+                }
+            }
+        })
+    });
+    thread.join().unwrap().unwrap();
+
+    // The subscription reply is recorded as well.
+    let replayer = Replayer::<FuturePublicResponseArg>::open(&path).unwrap();
+    assert_eq!(replayer.frames().count(), 3);
+    let mut updates = Vec::new();
+    Replayer::<FuturePublicResponseArg>::open(&path)
+        .unwrap()
+        .speed(Speed::Max)
+        .replay(|res: FuturePublicResponse| {
+            if let FuturePublicResponse::Orderbook(res) = res {
+                updates.push(res.data.u);
+            }
+        })
+        .unwrap();
+    assert_eq!(updates, vec![1, 2]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_private() {
+    let server = MockServer::private("key", "secret").unwrap();
+    server.on_subscribe("order", [order()]);
+    let mut client = WebSocketApiClient::private()
+        .uri(server.uri())
+        .build_with_credentials("key", "secret");
+    client.subscribe_order();
+    let handle = client.handle();
+
+    let orders = Arc::new(Mutex::new(Vec::new()));
+    let sink = orders.clone();
+    let thread = thread::spawn(move || {
+        client.run(move |res: PrivateResponse| {
+            if let PrivateResponse::Order(res) = res {
+                sink.lock().unwrap().push(res.data[0].order_id.to_string());
+            }
+        })
+    });
+
+    assert!(wait_until(TIMEOUT, || orders.lock().unwrap().len() == 1));
+    assert_eq!(orders.lock().unwrap()[0], "f6e324ff");
+    assert_eq!(server.requests()[0]["op"], "auth");
+    handle.shutdown();
+    thread.join().unwrap().unwrap();
+}
+
+#[test]
+fn test_auth_failed() {
+    let server = MockServer::private("key", "secret").unwrap();
+    let mut client = WebSocketApiClient::private()
+        .uri(server.uri())
+        .build_with_credentials("key", "wrong");
+    client.subscribe_order();
+    match client.run(|_: PrivateResponse| {}) {
+        Err(BybitError::AuthFailed(reason)) => assert_eq!(reason, "Invalid sign"),
+        res => panic!("unexpected result {:?}", res),
+    }
+}
+
+#[test]
+fn test_ping() {
+    let public = MockServer::public().unwrap();
+    let private = MockServer::private("key", "secret").unwrap();
+    for (server, op) in [(&public, "ping"), (&private, "pong")] {
+        let (mut ws, _) = tungstenite::connect(server.uri()).unwrap();
+        ws.write_message(Message::Text(r#"{"req_id":"1","op":"ping"}"#.into()))
+            .unwrap();
+        let reply = ws.read_message().unwrap().into_text().unwrap();
+        let reply: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(reply["op"], op);
+        assert_eq!(reply["req_id"], "1");
+    }
+
+    // Subscribing before authenticating is rejected.
+    let (mut ws, _) = tungstenite::connect(private.uri()).unwrap();
+    ws.write_message(Message::Text(
+        r#"{"req_id":"2","op":"subscribe","args":["order"]}"#.into(),
+    ))
+    .unwrap();
+    let reply = ws.read_message().unwrap().into_text().unwrap();
+    let reply: serde_json::Value = serde_json::from_str(&reply).unwrap();
+    assert_eq!(reply["success"], false);
+}
+
+/// The replies of order requests: `(op, req_id, ret_code, order_id)`.
+type OrderReplies = Arc<Mutex<Vec<(String, String, i64, String)>>>;
+
+#[test]
+fn test_trade() {
+    let server = MockServer::trade("key", "secret").unwrap();
+    server.reject_orders("order.cancel", 110001, "Order does not exist");
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let client = WebSocketApiClient::trade()
+        .uri(server.uri())
+        .on_event(move |event| sink.lock().unwrap().push(event))
+        .build_with_credentials("key", "secret");
+    let handle = client.handle();
+    let place = PlaceOrderRequest::new(
+        Category::Linear,
+        "BTCUSDT",
+        Side::Buy,
+        OrderType::Limit,
+        "0.01",
+    )
+    .price("10000")
+    .order_link_id("link-1");
+    let cancel = CancelOrderRequest::new(Category::Linear, "BTCUSDT").order_id("1");
+
+    // Orders sent before the client is authenticated would be stale, so they are rejected.
+    let early = handle.place_order(&place).unwrap();
+
+    let authed = Arc::new(Mutex::new(false));
+    let replies = OrderReplies::default();
+    let (authed_sink, replies_sink) = (authed.clone(), replies.clone());
+    let thread = thread::spawn(move || {
+        client.run(move |res: TradeResponse| {
+            let (op, res) = match res {
+                TradeResponse::Auth(res) => {
+                    *authed_sink.lock().unwrap() = res.ret_code == 0;
+                    return;
+                }
+                TradeResponse::Create(res) => ("order.create", res),
+                TradeResponse::Cancel(res) => ("order.cancel", res),
+                _ => return,
+            };
+            replies_sink.lock().unwrap().push((
+                op.to_string(),
+                res.req_id.to_string(),
+                res.ret_code,
+                res.data.order_id.to_string(),
+            ));
+        })
+    });
+    assert!(wait_until(TIMEOUT, || *authed.lock().unwrap()));
+
+    // Replies are correlated with the requests by `req_id`.
+    let placed = handle.place_order(&place).unwrap();
+    let canceled = handle.cancel_order(&cancel).unwrap();
+    assert!(wait_until(TIMEOUT, || replies.lock().unwrap().len() == 2));
+    assert_eq!(
+        *replies.lock().unwrap(),
+        [
+            (
+                "order.create".to_string(),
+                placed.clone(),
+                0,
+                "mock-order-1".to_string()
+            ),
+            (
+                "order.cancel".to_string(),
+                canceled.clone(),
+                110001,
+                String::new()
+            ),
+        ]
+    );
+    assert!(server
+        .requests()
+        .iter()
+        .all(|req| req["reqId"] != early.as_str()));
+
+    handle.shutdown();
+    thread.join().unwrap().unwrap();
+    let events = events.lock().unwrap();
+    let rejected: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Rejected { op, args, reason } => Some((op.as_str(), &args[..], reason.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        rejected,
+        [
+            ("order.create", &[early][..], "not connected"),
+            ("order.cancel", &[canceled][..], "Order does not exist"),
+        ]
+    );
+}
+
+#[test]
+fn test_trade_unauthenticated() {
+    let server = MockServer::trade("key", "secret").unwrap();
+    server.hold_auth(true);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    let client = WebSocketApiClient::trade()
+        .uri(server.uri())
+        .on_event(move |event| sink.lock().unwrap().push(event))
+        .build_with_credentials("key", "secret");
+    let handle = client.handle();
+    let thread = thread::spawn(move || client.run(|_: TradeResponse| {}));
+    assert!(wait_until(TIMEOUT, || server.count("auth", "key") == 1));
+
+    // Orders sent while the connection waits for the auth reply are rejected as such.
+    let cancel = CancelOrderRequest::new(Category::Linear, "BTCUSDT").order_id("1");
+    let canceled = handle.cancel_order(&cancel).unwrap();
+    assert!(wait_until(TIMEOUT, || !events.lock().unwrap().is_empty()));
+    handle.shutdown();
+    thread.join().unwrap().unwrap();
+    match &events.lock().unwrap()[0] {
+        Event::Rejected { op, args, reason } => {
+            assert_eq!(op, "order.cancel");
+            assert_eq!(args, &[canceled]);
+            assert_eq!(reason, "not authenticated");
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+    assert_eq!(server.count("order.cancel", "1"), 0);
+}
+
+fn spawn(server: &MockServer, capacity: usize) -> Responses<OwnedFuturePublicResponse> {
+    let mut client = WebSocketApiClient::future_linear()
+        .uri(server.uri())
+        .build();
+    client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+    client.spawn(capacity)
+}
+
+/// The `u` of the next order book update, or `None` if the client stops or times out.
+fn next_update(responses: &Responses<OwnedFuturePublicResponse>) -> Option<u64> {
+    loop {
+        if let FuturePublicResponse::Orderbook(res) = responses.recv_timeout(TIMEOUT).ok()? {
+            return Some(res.data.u);
+        }
+    }
+}
+
+#[test]
+fn test_spawn() {
+    let server = MockServer::public().unwrap();
+    server.on_subscribe(TOPIC, [orderbook("snapshot", 1), orderbook("delta", 2)]);
+    let responses = spawn(&server, 16);
+    assert_eq!(next_update(&responses), Some(1));
+    assert_eq!(next_update(&responses), Some(2));
+
+    // Shutting down closes the connection and returns the result of the client.
+    responses.shutdown().unwrap();
+    assert!(wait_until(TIMEOUT, || server.push(orderbook("delta", 3)) == 0));
+}
+
+#[test]
+fn test_spawn_backpressure() {
+    let server = MockServer::public().unwrap();
+    let updates = (1..=20).map(|u| orderbook(if u == 1 { "snapshot" } else { "delta" }, u));
+    server.on_subscribe(TOPIC, updates);
+    let responses = spawn(&server, 1);
+
+    // The client thread blocks on the full channel instead of dropping responses.
+    thread::sleep(Duration::from_millis(100));
+    for u in 1..=20 {
+        assert_eq!(next_update(&responses), Some(u));
+    }
+    responses.shutdown().unwrap();
+}
+
+#[test]
+fn test_spawn_drop() {
+    let server = MockServer::public().unwrap();
+    server.on_subscribe(TOPIC, [orderbook("snapshot", 1)]);
+    let responses = spawn(&server, 16);
+    assert_eq!(next_update(&responses), Some(1));
+
+    // Dropping the responses shuts the client down.
+    drop(responses);
+    assert!(wait_until(TIMEOUT, || server.push(orderbook("delta", 2)) == 0));
+}
+
+#[cfg(feature = "async")]
+mod stream {
+    use super::*;
+    use bybit::ws::stream::ResponseStream;
+    use futures_util::StreamExt;
+    use std::net::TcpListener;
+    use tokio::time;
+
+    type Stream = ResponseStream<OwnedFuturePublicResponse>;
+
+    /// The `u` of the next order book update, or `None` if the stream ends or times out.
+    async fn next_update(stream: &mut Stream) -> Option<u64> {
+        loop {
+            let res = time::timeout(TIMEOUT, stream.next()).await.ok()??;
+            if let FuturePublicResponse::Orderbook(res) = res.unwrap() {
+                return Some(res.data.u);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_subscribe() {
+        let server = MockServer::public().unwrap();
+        server.on_subscribe(TOPIC, [orderbook("snapshot", 1), orderbook("delta", 2)]);
+        let mut client = WebSocketApiClient::future_linear()
+            .uri(server.uri())
+            .build();
+        client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+        let mut stream = client.stream().await.unwrap();
+        assert_eq!(next_update(&mut stream).await, Some(1));
+        assert_eq!(next_update(&mut stream).await, Some(2));
+
+        // Topics subscribed by the handle are sent on the running connection.
+        let topic = "orderbook.50.BTCUSDT";
+        server.on_subscribe(topic, [orderbook("snapshot", 3)]);
+        client.handle().subscribe([topic]);
+        assert_eq!(next_update(&mut stream).await, Some(3));
+        assert_eq!(server.count("subscribe", topic), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stream_reconnect() {
+        let server = MockServer::public().unwrap();
+        server.on_subscribe(TOPIC, [orderbook("snapshot", 1)]);
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let policy = ReconnectPolicy::new()
+            .initial_delay(Duration::from_millis(10))
+            .jitter(0.0);
+        let mut client = WebSocketApiClient::future_linear()
+            .uri(server.uri())
+            .reconnect(policy)
+            .on_event(move |event| sink.lock().unwrap().push(event))
+            .build();
+        client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+        let mut stream = client.stream().await.unwrap();
+        assert_eq!(next_update(&mut stream).await, Some(1));
+
+        server.disconnect();
+        // The topic is resubscribed and the snapshot is pushed again.
+        assert_eq!(next_update(&mut stream).await, Some(1));
+        assert_eq!(server.connections(), 2);
+        assert!(events
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|event| matches!(event, Event::Reconnected { attempt: 1 })));
+    }
+
+    #[tokio::test]
+    async fn test_stream_parse_failed() {
+        let server = MockServer::public().unwrap();
+        server.on_subscribe(TOPIC, [orderbook("snapshot", 1)]);
+        let mut client = WebSocketApiClient::future_linear()
+            .uri(server.uri())
+            .build();
+        client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+        let failures = client.parse_failures();
+        let mut stream = client.stream().await.unwrap();
+        assert_eq!(next_update(&mut stream).await, Some(1));
+
+        // Frames which fail to parse are skipped and counted, as they are by `run`.
+        let changed = orderbook("delta", 2).replace(r#""u":2"#, r#""u":"2""#);
+        server.push(changed.clone());
+        server.push(orderbook("delta", 3));
+        assert_eq!(next_update(&mut stream).await, Some(3));
+        assert_eq!(failures.get(TOPIC), 1);
+
+        // A raw frame is counted once, however often it is parsed.
+        let server = MockServer::public().unwrap();
+        server.on_subscribe(TOPIC, [changed]);
+        let mut client = WebSocketApiClient::future_linear()
+            .uri(server.uri())
+            .build();
+        client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+        let failures = client.parse_failures();
+        let mut frames = client.frames().await.unwrap();
+        loop {
+            let frame = time::timeout(TIMEOUT, frames.next()).await.unwrap();
+            let frame = frame.unwrap().unwrap();
+            if frame.parse().is_err() {
+                assert!(frame.parse().is_err());
+                break;
+            }
+        }
+        assert_eq!(failures.get(TOPIC), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stream_connect() {
+        // A port which refuses connections.
+        let uri = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("ws://{}", listener.local_addr().unwrap())
+        };
+        let attempts = Arc::new(Mutex::new(0));
+        let sink = attempts.clone();
+        let policy = ReconnectPolicy::new()
+            .initial_delay(Duration::from_millis(10))
+            .max_attempts(2);
+        let client = WebSocketApiClient::future_linear()
+            .uri(uri)
+            .reconnect(policy)
+            .on_event(move |event| {
+                if let Event::Reconnecting { .. } = event {
+                    *sink.lock().unwrap() += 1;
+                }
+            })
+            .build();
+
+        // The first connection is retried with the policy.
+        assert!(client.stream().await.is_err());
+        assert_eq!(*attempts.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stream_shutdown() {
+        let server = MockServer::public().unwrap();
+        server.on_subscribe(TOPIC, [orderbook("snapshot", 1)]);
+        let mut client = WebSocketApiClient::future_linear()
+            .uri(server.uri())
+            .build();
+        client.subscribe_orderbook("BTCUSDT", OrderbookDepth::Level1);
+        let mut stream = client.stream().await.unwrap();
+        assert_eq!(next_update(&mut stream).await, Some(1));
+
+        // The stream ends right after shutdown, without waiting for a poll interval.
+        client.handle().shutdown();
+        let ended = time::timeout(Duration::from_millis(500), async {
+            while let Some(frame) = stream.next().await {
+                frame.unwrap();
+            }
+        });
+        assert!(ended.await.is_ok());
+    }
+}